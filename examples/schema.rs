@@ -2,9 +2,28 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cw20::BalanceResponse;
 
-use cw_lockbox::msg::{AllLocksResponse, ExecuteMsg, InstantiateMsg, LockInfo, QueryMsg};
-use cw_lockbox::state::State;
+use cw_lockbox::msg::{
+    ActiveLocksResponse, ActivityReportResponse, AirdropClaimedResponse, AirdropResponse,
+    AllLocksResponse, CanUnlockResponse, ConfigResponse, ControllerResponse, ConverterExecuteMsg,
+    DepositHookQueryMsg, DepositsResponse, EmergencyWithdrawalResponse, EscheatConfigResponse,
+    ExecuteMsg, ExecutionGuardResponse, ExpiringLocksResponse, ExtensionCampaignResponse,
+    FeeShareExecuteMsg, FeeSharePendingResponse, FeeShareQueryMsg, FeeShareResponse,
+    FrozenTokensResponse, GuardianApprovalResponse, GuardianQueryMsg, IdPrefixResponse,
+    InstantiateMsg, LegacyLockersResponse, LendersResponse, ListingsResponse, LockCallbackMsg,
+    LockCountResponse, LockData, LockInfo, LockInfoV2, LockedAtHeightResponse, LockersResponse,
+    MigrateMsg, NotifyAck, NotifyChannelResponse, NotifyPacket, OwnersResponse,
+    OwnershipChallengeResponse, ParamRegistryQueryMsg, ParamRegistryResponse, ParamResponse,
+    PauseResponse, QueryMsg, ReceiveMsg, RecipientAllowlistResponse, ReferrerResponse,
+    RelayersResponse, ReleasePreviewResponse, RolesResponse, RouterExecuteMsg,
+    SimulateDepositResponse, SimulateResponse, SolvencyResponse, StreamResponse, SuccessorResponse,
+    TimeHighWaterResponse, TokenMigrationsResponse, UnlockScheduleResponse,
+    UpcomingExpirationsResponse, ValidateLockParamsResponse, VerifyOwnershipResponse,
+    VestingGroupResponse, VestingResponse, VestingScheduleResponse, YieldAdapterExecuteMsg,
+    YieldAdapterQueryMsg, YieldAdapterResponse, YieldBalanceResponse,
+};
+use cw_lockbox::state::Profile;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -14,8 +33,80 @@ fn main() {
 
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
-    export_schema(&schema_for!(State), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(LockInfo), &out_dir);
     export_schema(&schema_for!(AllLocksResponse), &out_dir);
+    export_schema(&schema_for!(LockData), &out_dir);
+    export_schema(&schema_for!(LockersResponse), &out_dir);
+    export_schema(&schema_for!(LegacyLockersResponse), &out_dir);
+    export_schema(&schema_for!(LendersResponse), &out_dir);
+    export_schema(&schema_for!(VestingResponse), &out_dir);
+    export_schema(&schema_for!(ReleasePreviewResponse), &out_dir);
+    export_schema(&schema_for!(CanUnlockResponse), &out_dir);
+    export_schema(&schema_for!(UnlockScheduleResponse), &out_dir);
+    export_schema(&schema_for!(LockedAtHeightResponse), &out_dir);
+    export_schema(&schema_for!(AirdropResponse), &out_dir);
+    export_schema(&schema_for!(AirdropClaimedResponse), &out_dir);
+    export_schema(&schema_for!(TimeHighWaterResponse), &out_dir);
+    export_schema(&schema_for!(ExecutionGuardResponse), &out_dir);
+    export_schema(&schema_for!(YieldAdapterResponse), &out_dir);
+    export_schema(&schema_for!(SolvencyResponse), &out_dir);
+    export_schema(&schema_for!(Profile), &out_dir);
+    export_schema(&schema_for!(ActiveLocksResponse), &out_dir);
+    export_schema(&schema_for!(LockCountResponse), &out_dir);
+    export_schema(&schema_for!(OwnersResponse), &out_dir);
+    export_schema(&schema_for!(DepositsResponse), &out_dir);
+    export_schema(&schema_for!(ActivityReportResponse), &out_dir);
+    export_schema(&schema_for!(LockInfoV2), &out_dir);
+    export_schema(&schema_for!(IdPrefixResponse), &out_dir);
+    export_schema(&schema_for!(UpcomingExpirationsResponse), &out_dir);
+    export_schema(&schema_for!(EmergencyWithdrawalResponse), &out_dir);
+    export_schema(&schema_for!(ReferrerResponse), &out_dir);
+    export_schema(&schema_for!(RecipientAllowlistResponse), &out_dir);
+    export_schema(&schema_for!(FrozenTokensResponse), &out_dir);
+    export_schema(&schema_for!(TokenMigrationsResponse), &out_dir);
+    export_schema(&schema_for!(ValidateLockParamsResponse), &out_dir);
+    export_schema(&schema_for!(RolesResponse), &out_dir);
+    export_schema(&schema_for!(StreamResponse), &out_dir);
+    export_schema(&schema_for!(VestingGroupResponse), &out_dir);
+    export_schema(&schema_for!(VestingScheduleResponse), &out_dir);
+    export_schema(&schema_for!(BalanceResponse), &out_dir);
+    export_schema(&schema_for!(OwnershipChallengeResponse), &out_dir);
+    export_schema(&schema_for!(VerifyOwnershipResponse), &out_dir);
+    export_schema(&schema_for!(FeeShareResponse), &out_dir);
+    export_schema(&schema_for!(EscheatConfigResponse), &out_dir);
+    export_schema(&schema_for!(RelayersResponse), &out_dir);
+    export_schema(&schema_for!(ControllerResponse), &out_dir);
+    export_schema(&schema_for!(NotifyChannelResponse), &out_dir);
+    export_schema(&schema_for!(NotifyPacket), &out_dir);
+    export_schema(&schema_for!(NotifyAck), &out_dir);
+    export_schema(&schema_for!(SimulateResponse), &out_dir);
+    export_schema(&schema_for!(ExpiringLocksResponse), &out_dir);
+    export_schema(&schema_for!(ParamRegistryResponse), &out_dir);
+    export_schema(&schema_for!(ExtensionCampaignResponse), &out_dir);
+    export_schema(&schema_for!(ListingsResponse), &out_dir);
+    export_schema(&schema_for!(PauseResponse), &out_dir);
+    export_schema(&schema_for!(SuccessorResponse), &out_dir);
+
+    // cw20 Send payload
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
+
+    // interfaces of the integrated contracts
+    export_schema(&schema_for!(YieldAdapterExecuteMsg), &out_dir);
+    export_schema(&schema_for!(YieldAdapterQueryMsg), &out_dir);
+    export_schema(&schema_for!(YieldBalanceResponse), &out_dir);
+    export_schema(&schema_for!(FeeShareExecuteMsg), &out_dir);
+    export_schema(&schema_for!(FeeShareQueryMsg), &out_dir);
+    export_schema(&schema_for!(FeeSharePendingResponse), &out_dir);
+    export_schema(&schema_for!(ConverterExecuteMsg), &out_dir);
+    export_schema(&schema_for!(DepositHookQueryMsg), &out_dir);
+    export_schema(&schema_for!(SimulateDepositResponse), &out_dir);
+    export_schema(&schema_for!(RouterExecuteMsg), &out_dir);
+    export_schema(&schema_for!(LockCallbackMsg), &out_dir);
+    export_schema(&schema_for!(GuardianQueryMsg), &out_dir);
+    export_schema(&schema_for!(GuardianApprovalResponse), &out_dir);
+    export_schema(&schema_for!(ParamRegistryQueryMsg), &out_dir);
+    export_schema(&schema_for!(ParamResponse), &out_dir);
 }
@@ -0,0 +1,114 @@
+//! Unlocks the locks past their grace period on behalf of their owners, sending UnlockFor
+//! msgs in batches. Configured by the env vars of LockboxClient::from_env plus
+//! KEEPER_BATCH_SIZE, KEEPER_GAS_PER_UNLOCK and KEEPER_INTERVAL seconds
+use std::collections::HashSet;
+use std::env;
+use std::process;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cosmwasm_std::Timestamp;
+use lockbox_interface::client::{ClientResult, LockboxClient};
+use lockbox_interface::msg::{
+    ConfigResponse, ExecuteMsg, ExpiringLocksResponse, LockData, QueryMsg,
+};
+
+struct Settings {
+    batch_size: usize,
+    gas_per_unlock: u64,
+    interval: u64,
+}
+
+#[tokio::main]
+async fn main() {
+    let settings = Settings {
+        batch_size: env_number("KEEPER_BATCH_SIZE", 10) as usize,
+        gas_per_unlock: env_number("KEEPER_GAS_PER_UNLOCK", 200_000),
+        interval: env_number("KEEPER_INTERVAL", 60),
+    };
+    let mut client = LockboxClient::from_env().unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    });
+
+    // locks failing alone are skipped until restart, such as a frozen token
+    let mut failed = HashSet::new();
+    loop {
+        if let Err(err) = process_expired(&mut client, &settings, &mut failed).await {
+            eprintln!("error: {}", err);
+        }
+        tokio::time::sleep(Duration::from_secs(settings.interval)).await;
+    }
+}
+
+async fn process_expired(
+    client: &mut LockboxClient,
+    settings: &Settings,
+    failed: &mut HashSet<(String, String)>,
+) -> ClientResult<()> {
+    let config: ConfigResponse = client.query(&QueryMsg::Config {}).await?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let before = Timestamp::from_seconds(now.saturating_sub(config.grace_period));
+
+    let mut expired = vec![];
+    let mut start_after = None;
+    loop {
+        let msg = QueryMsg::LocksExpiringBefore {
+            time: before,
+            start_after,
+            limit: Some(30),
+        };
+        let page: ExpiringLocksResponse = client.query(&msg).await?;
+        expired.extend(
+            page.locks
+                .into_iter()
+                .filter(|lock| !failed.contains(&(lock.owner.clone(), lock.id.clone()))),
+        );
+        start_after = match page.next_start_after {
+            Some(next) => Some(next),
+            None => break,
+        };
+    }
+
+    for batch in expired.chunks(settings.batch_size.max(1)) {
+        if let Err(err) = unlock(client, settings, batch).await {
+            // a single failing lock reverts the whole batch
+            eprintln!("batch failed: {}", err);
+            for lock in batch {
+                if let Err(err) = unlock(client, settings, std::slice::from_ref(lock)).await {
+                    eprintln!("unlock {} {} failed: {}", lock.owner, lock.id, err);
+                    failed.insert((lock.owner.clone(), lock.id.clone()));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn unlock(
+    client: &mut LockboxClient,
+    settings: &Settings,
+    locks: &[LockData],
+) -> ClientResult<()> {
+    let msgs = locks
+        .iter()
+        .map(|lock| ExecuteMsg::UnlockFor {
+            owner: lock.owner.clone(),
+            id: lock.id.clone(),
+        })
+        .collect();
+    client.gas.gas_limit = settings.gas_per_unlock * locks.len() as u64;
+    let res = client.execute(&ExecuteMsg::Batch { msgs }, vec![]).await?;
+    println!("unlocked {} locks in {}", locks.len(), res.hash);
+
+    Ok(())
+}
+
+fn env_number(name: &str, default: u64) -> u64 {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
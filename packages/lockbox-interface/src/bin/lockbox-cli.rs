@@ -0,0 +1,114 @@
+//! Operator commands over LockboxClient, configured by the env vars of LockboxClient::from_env:
+//!
+//! lockbox-cli lock <id> <expire unix seconds> <amount><denom>
+//! lockbox-cli unlock <id>
+//! lockbox-cli list [--expiring-within <30m|24h|7d>]
+//! lockbox-cli export-state
+use std::env;
+use std::error::Error;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cosmwasm_std::{to_vec, Coin, Timestamp, Uint128};
+use lockbox_interface::client::LockboxClient;
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(err) = run(&args).await {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+async fn run(args: &[String]) -> CliResult<()> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let client = LockboxClient::from_env()?;
+    let owner = client.sender().to_string();
+
+    match args.as_slice() {
+        ["lock", id, expire, amount] => {
+            let expire = Timestamp::from_seconds(expire.parse()?);
+            let res = client
+                .lock(id, Some(expire), vec![parse_coin(amount)?])
+                .await?;
+            println!("{}", res.hash);
+        }
+        ["unlock", id] => {
+            let res = client.unlock(id).await?;
+            println!("{}", res.hash);
+        }
+        ["list"] => list(&client, &owner, None).await?,
+        ["list", "--expiring-within", window] => {
+            list(&client, &owner, Some(parse_duration(window)?)).await?
+        }
+        ["export-state"] => {
+            let locks = client.owner_locks(&owner).await?;
+            println!("{}", String::from_utf8(to_vec(&locks)?)?);
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+const USAGE: &str = "usage: lockbox-cli lock <id> <expire> <amount><denom> | unlock <id> \
+                     | list [--expiring-within <duration>] | export-state";
+
+/// Prints id, expire and native balance of the sender locks, sorted by expire
+async fn list(client: &LockboxClient, owner: &str, window: Option<u64>) -> CliResult<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let mut locks = client.owner_locks(owner).await?;
+    if let Some(window) = window {
+        let until = Timestamp::from_seconds(now + window);
+        locks.retain(|lock| lock.expire <= until);
+    }
+    locks.sort_by_key(|lock| lock.expire.nanos());
+
+    for lock in locks {
+        let funds: Vec<String> = lock
+            .native_balance
+            .iter()
+            .map(|coin| format!("{}{}", coin.amount, coin.denom))
+            .chain(
+                lock.cw20_balance
+                    .iter()
+                    .map(|coin| format!("{}{}", coin.amount, coin.address)),
+            )
+            .collect();
+        println!(
+            "{}\t{}\t{}",
+            lock.id,
+            lock.expire.seconds(),
+            funds.join(",")
+        );
+    }
+    Ok(())
+}
+
+/// Parses 10ujuno into a coin
+fn parse_coin(value: &str) -> CliResult<Coin> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or("amount is missing the denom")?;
+    let (amount, denom) = value.split_at(split);
+
+    Ok(Coin {
+        denom: denom.into(),
+        amount: Uint128::new(amount.parse()?),
+    })
+}
+
+/// Parses 30m, 24h or 7d into seconds
+fn parse_duration(value: &str) -> CliResult<u64> {
+    let (amount, unit) = value.split_at(value.len().saturating_sub(1));
+    let unit = match unit {
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("invalid duration {}", value).into()),
+    };
+
+    Ok(amount.parse::<u64>()? * unit)
+}
@@ -0,0 +1,392 @@
+//! Async client signing and broadcasting lockbox msgs to a live chain, for bots and
+//! integration tests. Built with the client feature
+use std::fmt;
+
+use cosmrs::cosmwasm::{MsgExecuteContract, MsgInstantiateContract};
+use cosmrs::crypto::secp256k1::SigningKey;
+use cosmrs::proto::cosmos::auth::v1beta1::{
+    BaseAccount, QueryAccountRequest, QueryAccountResponse,
+};
+use cosmrs::proto::cosmwasm::wasm::v1::{
+    QuerySmartContractStateRequest, QuerySmartContractStateResponse,
+};
+use cosmrs::rpc::endpoint::broadcast::tx_commit;
+use cosmrs::rpc::{Client, HttpClient};
+use cosmrs::tendermint::chain;
+use cosmrs::tx::{self, Fee, Msg, SignDoc, SignerInfo};
+use cosmrs::{AccountId, ErrorReport};
+use cosmwasm_std::{from_slice, to_vec, Coin, Decimal, StdError, Timestamp, Uint128};
+use prost::Message;
+use serde::de::DeserializeOwned;
+
+use crate::msg::{AllLocksResponse, ExecuteMsg, InstantiateMsg, LockInfoV2, QueryMsg, QueryMsgV2};
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// Encoding or rpc failure
+    Cosmrs(ErrorReport),
+    /// Invalid private key, or the tx could not be signed
+    Signing(ErrorReport),
+    /// Missing or invalid env var
+    Env(String),
+    /// Client is not set up for the call, such as a missing contract or an invalid chain id
+    Config(String),
+    /// Msg could not be serialized or the response deserialized
+    Std(StdError),
+    /// Chain rejected the tx or the query, with its log
+    Rejected(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::Cosmrs(err) => write!(f, "{}", err),
+            ClientError::Signing(err) => write!(f, "signing: {}", err),
+            ClientError::Env(msg) => write!(f, "env: {}", msg),
+            ClientError::Config(msg) => write!(f, "config: {}", msg),
+            ClientError::Std(err) => write!(f, "{}", err),
+            ClientError::Rejected(log) => write!(f, "rejected: {}", log),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<ErrorReport> for ClientError {
+    fn from(err: ErrorReport) -> Self {
+        ClientError::Cosmrs(err)
+    }
+}
+
+impl From<cosmrs::rpc::Error> for ClientError {
+    fn from(err: cosmrs::rpc::Error) -> Self {
+        ClientError::Cosmrs(err.into())
+    }
+}
+
+impl From<prost::DecodeError> for ClientError {
+    fn from(err: prost::DecodeError) -> Self {
+        ClientError::Cosmrs(err.into())
+    }
+}
+
+impl From<StdError> for ClientError {
+    fn from(err: StdError) -> Self {
+        ClientError::Std(err)
+    }
+}
+
+pub type ClientResult<T> = Result<T, ClientError>;
+
+/// Fee paid by every tx, gas_limit * gas_price of denom rounded up
+#[derive(Clone, Debug)]
+pub struct GasConfig {
+    pub denom: String,
+    /// Price of a gas unit, such as 0.025
+    pub gas_price: Decimal,
+    pub gas_limit: u64,
+}
+
+impl GasConfig {
+    pub fn fee_amount(&self) -> u128 {
+        if self.gas_limit == 0 {
+            return 0;
+        }
+        let gas_limit = Uint128::from(self.gas_limit);
+        let amount = gas_limit * self.gas_price;
+        if Decimal::from_ratio(amount, gas_limit) < self.gas_price {
+            amount.u128() + 1
+        } else {
+            amount.u128()
+        }
+    }
+}
+
+pub struct LockboxClient {
+    rpc: HttpClient,
+    chain_id: chain::Id,
+    /// Lockbox contract, set by new or once instantiated
+    pub contract: Option<AccountId>,
+    key: SigningKey,
+    sender: AccountId,
+    pub gas: GasConfig,
+}
+
+impl LockboxClient {
+    /// Client signing with the secp256k1 private key, addresses use account_prefix
+    pub fn new(
+        rpc_url: &str,
+        chain_id: &str,
+        account_prefix: &str,
+        private_key: &[u8],
+        contract: Option<&str>,
+        gas: GasConfig,
+    ) -> ClientResult<Self> {
+        let key = SigningKey::from_slice(private_key).map_err(ClientError::Signing)?;
+        let sender = key
+            .public_key()
+            .account_id(account_prefix)
+            .map_err(|err| ClientError::Config(err.to_string()))?;
+
+        Ok(LockboxClient {
+            rpc: HttpClient::new(rpc_url)?,
+            chain_id: chain_id
+                .parse()
+                .map_err(|err: cosmrs::tendermint::Error| ClientError::Config(err.to_string()))?,
+            contract: contract
+                .map(str::parse)
+                .transpose()
+                .map_err(|err: ErrorReport| ClientError::Config(err.to_string()))?,
+            key,
+            sender,
+            gas,
+        })
+    }
+
+    /// Client configured by the LOCKBOX_RPC, LOCKBOX_CHAIN_ID, LOCKBOX_PREFIX,
+    /// LOCKBOX_PRIVATE_KEY (hex), LOCKBOX_CONTRACT and LOCKBOX_GAS_* env vars
+    pub fn from_env() -> ClientResult<Self> {
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| ClientError::Env(format!("{} is not set", name)))
+        };
+        let number = |name: &str| {
+            var(name)?
+                .parse::<u64>()
+                .map_err(|_| ClientError::Env(format!("{} is not a number", name)))
+        };
+        let gas = GasConfig {
+            denom: var("LOCKBOX_GAS_DENOM")?,
+            gas_price: var("LOCKBOX_GAS_PRICE")?
+                .parse::<Decimal>()
+                .map_err(|_| ClientError::Env("LOCKBOX_GAS_PRICE is not a decimal".into()))?,
+            gas_limit: number("LOCKBOX_GAS_LIMIT")?,
+        };
+        let key = hex::decode(var("LOCKBOX_PRIVATE_KEY")?)
+            .map_err(|_| ClientError::Env("LOCKBOX_PRIVATE_KEY is not hex".into()))?;
+
+        LockboxClient::new(
+            &var("LOCKBOX_RPC")?,
+            &var("LOCKBOX_CHAIN_ID")?,
+            &var("LOCKBOX_PREFIX")?,
+            &key,
+            Some(&var("LOCKBOX_CONTRACT")?),
+            gas,
+        )
+    }
+
+    pub fn sender(&self) -> &AccountId {
+        &self.sender
+    }
+
+    /// Instantiates code_id, the new contract is used by the following calls
+    pub async fn instantiate(
+        &mut self,
+        code_id: u64,
+        msg: &InstantiateMsg,
+        label: &str,
+    ) -> ClientResult<AccountId> {
+        let msg = MsgInstantiateContract {
+            sender: self.sender.clone(),
+            admin: Some(self.sender.clone()),
+            code_id,
+            label: Some(label.to_string()),
+            msg: to_vec(msg)?,
+            funds: vec![],
+        };
+        let res = self.broadcast(msg).await?;
+        let contract = res
+            .deliver_tx
+            .events
+            .iter()
+            .filter(|event| event.kind == "instantiate")
+            .flat_map(|event| event.attributes.iter())
+            .find(|attr| attr.key.to_string() == "_contract_address")
+            .ok_or_else(|| ClientError::Rejected("missing contract address".into()))?
+            .value
+            .to_string()
+            .parse::<AccountId>()?;
+        self.contract = Some(contract.clone());
+
+        Ok(contract)
+    }
+
+    pub async fn lock(
+        &self,
+        id: &str,
+        expire: Option<Timestamp>,
+        funds: Vec<Coin>,
+    ) -> ClientResult<tx_commit::Response> {
+        let msg = ExecuteMsg::Lock {
+            id: id.into(),
+            expire,
+            referrer: None,
+            amount: None,
+        };
+        self.execute(&msg, funds).await
+    }
+
+    pub async fn increase_lock(
+        &self,
+        id: &str,
+        funds: Vec<Coin>,
+    ) -> ClientResult<tx_commit::Response> {
+        let msg = ExecuteMsg::IncreaseLock {
+            id: id.into(),
+            min_remaining: None,
+        };
+        self.execute(&msg, funds).await
+    }
+
+    pub async fn unlock(&self, id: &str) -> ClientResult<tx_commit::Response> {
+        self.execute(&ExecuteMsg::Unlock { id: id.into() }, vec![])
+            .await
+    }
+
+    /// Signs and broadcasts any execute msg with the attached native funds
+    pub async fn execute(
+        &self,
+        msg: &ExecuteMsg,
+        funds: Vec<Coin>,
+    ) -> ClientResult<tx_commit::Response> {
+        let funds = funds
+            .into_iter()
+            .map(|coin| {
+                Ok(cosmrs::Coin {
+                    denom: coin.denom.parse()?,
+                    amount: coin.amount.u128(),
+                })
+            })
+            .collect::<ClientResult<Vec<_>>>()?;
+        let msg = MsgExecuteContract {
+            sender: self.sender.clone(),
+            contract: self.contract()?.clone(),
+            msg: to_vec(msg)?,
+            funds,
+        };
+        self.broadcast(msg).await
+    }
+
+    pub async fn lock_info(&self, owner: &str, id: &str) -> ClientResult<LockInfoV2> {
+        let msg = QueryMsg::V2(QueryMsgV2::Lock {
+            address: owner.into(),
+            id: id.into(),
+            viewing_key: None,
+            include_config: true,
+            include_token_info: false,
+        });
+        self.query(&msg).await
+    }
+
+    /// Every lock of owner, paging through AllLocks
+    pub async fn owner_locks(&self, owner: &str) -> ClientResult<Vec<LockInfoV2>> {
+        let mut locks = vec![];
+        let mut start_after = None;
+        loop {
+            let msg = QueryMsg::AllLocks {
+                address: owner.into(),
+                start_after,
+                limit: Some(30),
+            };
+            let page: AllLocksResponse = self.query(&msg).await?;
+            for id in page.locks {
+                locks.push(self.lock_info(owner, &id).await?);
+            }
+            start_after = match page.next_start_after {
+                Some(next) => Some(next),
+                None => return Ok(locks),
+            };
+        }
+    }
+
+    /// Smart query deserialized into the response type of msg
+    pub async fn query<T: DeserializeOwned>(&self, msg: &QueryMsg) -> ClientResult<T> {
+        let req = QuerySmartContractStateRequest {
+            address: self.contract()?.to_string(),
+            query_data: to_vec(msg)?,
+        };
+        let value = self
+            .abci_query("/cosmwasm.wasm.v1.Query/SmartContractState", req)
+            .await?;
+        let res = QuerySmartContractStateResponse::decode(value.as_slice())?;
+
+        Ok(from_slice(&res.data)?)
+    }
+
+    fn contract(&self) -> ClientResult<&AccountId> {
+        self.contract
+            .as_ref()
+            .ok_or_else(|| ClientError::Config("contract not instantiated".into()))
+    }
+
+    async fn abci_query<M: Message>(&self, path: &str, req: M) -> ClientResult<Vec<u8>> {
+        let res = self
+            .rpc
+            .abci_query(Some(path.to_string()), req.encode_to_vec(), None, false)
+            .await?;
+        if res.code.is_err() {
+            return Err(ClientError::Rejected(res.log.to_string()));
+        }
+        Ok(res.value)
+    }
+
+    async fn account(&self) -> ClientResult<BaseAccount> {
+        let req = QueryAccountRequest {
+            address: self.sender.to_string(),
+        };
+        let value = self
+            .abci_query("/cosmos.auth.v1beta1.Query/Account", req)
+            .await?;
+        let account = QueryAccountResponse::decode(value.as_slice())?
+            .account
+            .ok_or_else(|| ClientError::Rejected("account not found".into()))?;
+
+        Ok(BaseAccount::decode(account.value.as_slice())?)
+    }
+
+    async fn broadcast<M: Msg>(&self, msg: M) -> ClientResult<tx_commit::Response> {
+        let account = self.account().await?;
+        let fee = Fee::from_amount_and_gas(
+            cosmrs::Coin {
+                denom: self.gas.denom.parse()?,
+                amount: self.gas.fee_amount(),
+            },
+            self.gas.gas_limit,
+        );
+        let body = tx::Body::new(vec![msg.to_any()?], "", 0u32);
+        let auth_info =
+            SignerInfo::single_direct(Some(self.key.public_key()), account.sequence).auth_info(fee);
+        let sign_doc = SignDoc::new(&body, &auth_info, &self.chain_id, account.account_number)?;
+        let res = sign_doc
+            .sign(&self.key)
+            .map_err(ClientError::Signing)?
+            .broadcast_commit(&self.rpc)
+            .await?;
+
+        if res.check_tx.code.is_err() {
+            return Err(ClientError::Rejected(res.check_tx.log.to_string()));
+        }
+        if res.deliver_tx.code.is_err() {
+            return Err(ClientError::Rejected(res.deliver_tx.log.to_string()));
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fee_amount() {
+        let gas = |gas_price: &str, gas_limit: u64| GasConfig {
+            denom: "ujuno".into(),
+            gas_price: gas_price.parse().unwrap(),
+            gas_limit,
+        };
+        assert_eq!(5000, gas("0.025", 200_000).fee_amount());
+        // fractions of the smallest unit are rounded up
+        assert_eq!(3, gas("0.025", 81).fee_amount());
+        assert_eq!(400_000, gas("2", 200_000).fee_amount());
+        assert_eq!(0, gas("0.025", 0).fee_amount());
+    }
+}
@@ -0,0 +1,56 @@
+// Attribute keys and actions emitted by the lock lifecycle msgs, kept stable for indexers
+
+pub const ACTION: &str = "action";
+pub const OWNER: &str = "owner";
+pub const LOCK_ID: &str = "lock_id";
+/// Native denom or cw20 token address
+pub const ASSET: &str = "asset";
+pub const AMOUNT: &str = "amount";
+pub const EXPIRE: &str = "expire";
+/// Lock balance of the asset after a deposit
+pub const TOTAL: &str = "total";
+
+pub const LOCK: &str = "lock";
+pub const INCREASE_LOCK: &str = "increase_lock";
+pub const UNLOCK: &str = "unlock";
+pub const UNLOCK_MANY: &str = "unlock_many";
+pub const CLAIM_ASSET: &str = "claim_asset";
+pub const WITHDRAW_FROM: &str = "withdraw_from";
+pub const ABANDON_WARNING: &str = "abandon_warning";
+pub const ESCHEAT: &str = "escheat";
+pub const ROLLOVER: &str = "rollover";
+pub const SELL_LOCK: &str = "sell_lock";
+pub const BUY_LOCK: &str = "buy_lock";
+pub const LIST_LOCK: &str = "list_lock";
+pub const DELIST_LOCK: &str = "delist_lock";
+pub const CREATE_EXTENSION_CAMPAIGN: &str = "create_extension_campaign";
+pub const ACCEPT_EXTENSION: &str = "accept_extension";
+pub const CLAIM_EXTENSION_BONUS: &str = "claim_extension_bonus";
+pub const CLOSE_EXTENSION_CAMPAIGN: &str = "close_extension_campaign";
+pub const UPDATE_LENDERS: &str = "update_lenders";
+pub const APPROVE_LENDER: &str = "approve_lender";
+pub const ENCUMBER: &str = "encumber";
+pub const RELEASE_ENCUMBRANCE: &str = "release_encumbrance";
+pub const SEIZE_LOCK: &str = "seize_lock";
+pub const CLAIM_LP_REWARDS: &str = "claim_lp_rewards";
+pub const CREDIT_LP_REWARDS: &str = "credit_lp_rewards";
+/// Fee rewards paid to the recipient of a lock that can't hold them, or was released
+pub const PAY_LP_REWARDS: &str = "pay_lp_rewards";
+pub const GRANT_ROLE: &str = "grant_role";
+pub const REVOKE_ROLE: &str = "revoke_role";
+pub const MAP_CONTROLLER: &str = "map_controller";
+pub const UNMAP_CONTROLLER: &str = "unmap_controller";
+pub const SET_NOTIFY_CHANNEL: &str = "set_notify_channel";
+pub const CONNECT_NOTIFY_CHANNEL: &str = "connect_notify_channel";
+pub const CLOSE_NOTIFY_CHANNEL: &str = "close_notify_channel";
+/// Counterparty acknowledgement or timeout of a notify packet
+pub const NOTIFY_ACK: &str = "notify_ack";
+pub const NOTIFY_TIMEOUT: &str = "notify_timeout";
+/// Lender a seized lock was moved to
+pub const LENDER: &str = "lender";
+/// Address a role is granted to or revoked from
+pub const ADDRESS: &str = "address";
+pub const ROLE: &str = "role";
+/// Controller account running a remote lock msg for the owner
+pub const CONTROLLER: &str = "controller";
+pub const CHANNEL_ID: &str = "channel_id";
@@ -0,0 +1,161 @@
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, StdResult, Timestamp, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::msg::{ExecuteMsg, ReceiveMsg};
+
+/// Builds the msgs to a lockbox contract, for contracts and bots integrating with it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockboxContract(pub Addr);
+
+impl LockboxContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    pub fn call(&self, msg: ExecuteMsg, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr().into(),
+            msg: to_binary(&msg)?,
+            send: funds,
+        }
+        .into())
+    }
+
+    /// Sends amount of the cw20 token to the lockbox with msg
+    pub fn call_cw20(
+        &self,
+        token: impl Into<String>,
+        amount: Uint128,
+        msg: ReceiveMsg,
+    ) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: token.into(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: self.addr().into(),
+                amount,
+                msg: Some(to_binary(&msg)?),
+            })?,
+            send: vec![],
+        }
+        .into())
+    }
+
+    pub fn lock(
+        &self,
+        id: impl Into<String>,
+        expire: Option<Timestamp>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {
+        let msg = ExecuteMsg::Lock {
+            id: id.into(),
+            expire,
+            referrer: None,
+            amount: None,
+        };
+        self.call(msg, funds)
+    }
+
+    pub fn lock_cw20(
+        &self,
+        token: impl Into<String>,
+        amount: Uint128,
+        id: impl Into<String>,
+        expire: Option<Timestamp>,
+    ) -> StdResult<CosmosMsg> {
+        let msg = ReceiveMsg::Lock {
+            id: id.into(),
+            expire,
+            referrer: None,
+        };
+        self.call_cw20(token, amount, msg)
+    }
+
+    pub fn increase(&self, id: impl Into<String>, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        let msg = ExecuteMsg::IncreaseLock {
+            id: id.into(),
+            min_remaining: None,
+        };
+        self.call(msg, funds)
+    }
+
+    pub fn increase_cw20(
+        &self,
+        token: impl Into<String>,
+        amount: Uint128,
+        id: impl Into<String>,
+    ) -> StdResult<CosmosMsg> {
+        let msg = ReceiveMsg::IncreaseLock {
+            id: id.into(),
+            min_remaining: None,
+        };
+        self.call_cw20(token, amount, msg)
+    }
+
+    pub fn unlock(&self, id: impl Into<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Unlock { id: id.into() }, vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{coins, from_binary};
+
+    #[test]
+    fn lockbox_msgs() {
+        let lockbox = LockboxContract(Addr::unchecked("lockbox"));
+
+        let msg = lockbox.lock("1", None, coins(2, "token")).unwrap();
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                send,
+            }) => {
+                assert_eq!("lockbox", contract_addr);
+                assert_eq!(coins(2, "token"), send);
+                match from_binary(&msg).unwrap() {
+                    ExecuteMsg::Lock { id, expire, .. } => {
+                        assert_eq!("1", id);
+                        assert_eq!(None, expire);
+                    }
+                    _ => panic!("Must be a Lock msg"),
+                }
+            }
+            _ => panic!("Must be a WasmMsg::Execute"),
+        }
+
+        let msg = lockbox
+            .increase_cw20("token", Uint128::new(5), "1")
+            .unwrap();
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr, msg, ..
+            }) => {
+                assert_eq!("token", contract_addr);
+                match from_binary(&msg).unwrap() {
+                    Cw20ExecuteMsg::Send {
+                        contract,
+                        amount,
+                        msg,
+                    } => {
+                        assert_eq!("lockbox", contract);
+                        assert_eq!(Uint128::new(5), amount);
+                        let msg: ReceiveMsg = from_binary(&msg.unwrap()).unwrap();
+                        assert_eq!(
+                            ReceiveMsg::IncreaseLock {
+                                id: "1".into(),
+                                min_remaining: None,
+                            },
+                            msg
+                        );
+                    }
+                    _ => panic!("Must be a cw20 Send msg"),
+                }
+            }
+            _ => panic!("Must be a WasmMsg::Execute"),
+        }
+    }
+}
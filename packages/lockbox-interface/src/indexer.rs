@@ -0,0 +1,370 @@
+//! Decoder mapping the attributes emitted by the lock lifecycle msgs back into lock updates,
+//! for indexers. Built with the indexer feature
+use std::fmt;
+
+use cosmwasm_std::{Attribute, Timestamp, Uint128};
+
+use crate::events;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetAmount {
+    /// Native denom or cw20 token address
+    pub asset: String,
+    pub amount: Uint128,
+    /// Lock balance of the asset after a deposit, only set on created and increased
+    pub total: Option<Uint128>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LockUpdate {
+    pub owner: String,
+    pub id: String,
+    pub expire: Timestamp,
+    pub assets: Vec<AssetAmount>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LockEvent {
+    /// Lock or a remote lock, assets are the deposit
+    Created(LockUpdate),
+    /// IncreaseLock or a remote increase, assets are the deposit
+    Increased(LockUpdate),
+    /// Unlock, UnlockFor, RelayUnlock, a remote unlock or each lock of UnlockMany, the lock
+    /// was removed
+    Unlocked(LockUpdate),
+    /// ClaimAsset or ClaimNext, a single asset left the lock
+    AssetClaimed(LockUpdate),
+    /// WithdrawFrom, assets were sent to a spender
+    Withdrawn(LockUpdate),
+    /// Escheat, the lock was removed
+    Escheated(LockUpdate),
+    /// ProcessRollover, the lock was moved into the successor lockbox
+    RolledOver(LockUpdate),
+    /// BuyLock, the lock was removed from the seller
+    Sold(LockUpdate),
+    /// BuyLock, the lock was moved to the buyer under the same id
+    Bought(LockUpdate),
+    /// SeizeLock, the lock was moved to the lender under the same id
+    Seized { update: LockUpdate, lender: String },
+    /// AcceptExtension, expire is the extended one
+    Extended(LockUpdate),
+    /// CreditLpRewards, assets are the fee rewards added to the lock
+    RewardsCredited(LockUpdate),
+}
+
+impl LockEvent {
+    pub fn update(&self) -> &LockUpdate {
+        match self {
+            LockEvent::Created(update)
+            | LockEvent::Increased(update)
+            | LockEvent::Unlocked(update)
+            | LockEvent::AssetClaimed(update)
+            | LockEvent::Withdrawn(update)
+            | LockEvent::Escheated(update)
+            | LockEvent::RolledOver(update)
+            | LockEvent::Sold(update)
+            | LockEvent::Bought(update)
+            | LockEvent::Seized { update, .. }
+            | LockEvent::Extended(update)
+            | LockEvent::RewardsCredited(update) => update,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeError {
+    MissingAttribute { action: String, key: String },
+    InvalidValue { key: String, value: String },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::MissingAttribute { action, key } => {
+                write!(f, "{} event without {} attribute", action, key)
+            }
+            DecodeError::InvalidValue { key, value } => {
+                write!(f, "invalid {} value: {}", key, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Decodes the wasm attributes of a tx in emit order. A Batch concatenates the attributes of
+/// its msgs, so every action key starts a new event. Actions that don't change a single lock
+/// are skipped, unlock_many and claim_lp_rewards are followed by an event per lock
+pub fn decode_events(attributes: &[Attribute]) -> Result<Vec<LockEvent>, DecodeError> {
+    let mut decoded = vec![];
+    let mut rest = attributes;
+    while let Some(start) = rest.iter().position(|attr| attr.key == events::ACTION) {
+        let end = rest[start + 1..]
+            .iter()
+            .position(|attr| attr.key == events::ACTION)
+            .map_or(rest.len(), |pos| start + 1 + pos);
+        let action = rest[start].value.as_str();
+        let group = &rest[start + 1..end];
+        rest = &rest[end..];
+
+        let event: fn(LockUpdate) -> LockEvent = match action {
+            events::LOCK => LockEvent::Created,
+            events::INCREASE_LOCK => LockEvent::Increased,
+            events::UNLOCK => LockEvent::Unlocked,
+            events::CLAIM_ASSET => LockEvent::AssetClaimed,
+            events::WITHDRAW_FROM => LockEvent::Withdrawn,
+            events::ESCHEAT => LockEvent::Escheated,
+            events::ROLLOVER => LockEvent::RolledOver,
+            events::SELL_LOCK => LockEvent::Sold,
+            events::BUY_LOCK => LockEvent::Bought,
+            events::ACCEPT_EXTENSION => LockEvent::Extended,
+            events::CREDIT_LP_REWARDS => LockEvent::RewardsCredited,
+            events::SEIZE_LOCK => {
+                decoded.push(LockEvent::Seized {
+                    update: decode_update(action, group)?,
+                    lender: find_value(action, group, events::LENDER)?,
+                });
+                continue;
+            }
+            _ => continue,
+        };
+        decoded.push(event(decode_update(action, group)?));
+    }
+
+    Ok(decoded)
+}
+
+fn decode_update(action: &str, group: &[Attribute]) -> Result<LockUpdate, DecodeError> {
+    let value = |key: &str| find_value(action, group, key);
+
+    let mut assets: Vec<AssetAmount> = vec![];
+    for attr in group {
+        match attr.key.as_str() {
+            events::ASSET => assets.push(AssetAmount {
+                asset: attr.value.to_owned(),
+                amount: Uint128::zero(),
+                total: None,
+            }),
+            events::AMOUNT => {
+                if let Some(asset) = assets.last_mut() {
+                    asset.amount = parse_amount(attr)?;
+                }
+            }
+            events::TOTAL => {
+                if let Some(asset) = assets.last_mut() {
+                    asset.total = Some(parse_amount(attr)?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(LockUpdate {
+        owner: value(events::OWNER)?,
+        id: value(events::LOCK_ID)?,
+        expire: parse_expire(&value(events::EXPIRE)?)?,
+        assets,
+    })
+}
+
+fn find_value(action: &str, group: &[Attribute], key: &str) -> Result<String, DecodeError> {
+    group
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.to_owned())
+        .ok_or_else(|| DecodeError::MissingAttribute {
+            action: action.into(),
+            key: key.into(),
+        })
+}
+
+fn parse_amount(attr: &Attribute) -> Result<Uint128, DecodeError> {
+    attr.value
+        .parse::<u128>()
+        .map(Uint128::new)
+        .map_err(|_| invalid(events::AMOUNT, &attr.value))
+}
+
+/// Timestamp is emitted as seconds and 9 digit nanos, e.g. 1620000000.000000000
+fn parse_expire(value: &str) -> Result<Timestamp, DecodeError> {
+    let mut parts = value.splitn(2, '.');
+    let seconds = parts.next().and_then(|secs| secs.parse::<u64>().ok());
+    let nanos = match parts.next() {
+        Some(nanos) if nanos.len() == 9 => nanos.parse::<u64>().ok(),
+        Some(_) => None,
+        None => Some(0),
+    };
+    match (seconds, nanos) {
+        (Some(seconds), Some(nanos)) => seconds
+            .checked_mul(1_000_000_000)
+            .and_then(|secs| secs.checked_add(nanos))
+            .map(Timestamp::from_nanos)
+            .ok_or_else(|| invalid(events::EXPIRE, value)),
+        _ => Err(invalid(events::EXPIRE, value)),
+    }
+}
+
+fn invalid(key: &str, value: &str) -> DecodeError {
+    DecodeError::InvalidValue {
+        key: key.into(),
+        value: value.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::attr;
+
+    #[test]
+    fn decode_lock_events() {
+        let expire = Timestamp::from_seconds(100);
+        let attributes = vec![
+            // a batch with a lock and an increase, then an unlock_many
+            attr("action", "lock"),
+            attr("owner", "anyone"),
+            attr("lock_id", "1"),
+            attr("expire", expire),
+            attr("asset", "token"),
+            attr("amount", "2"),
+            attr("total", "2"),
+            attr("action", "increase_lock"),
+            attr("owner", "anyone"),
+            attr("lock_id", "1"),
+            attr("expire", expire),
+            attr("asset", "cw20"),
+            attr("amount", "5"),
+            attr("total", "5"),
+            attr("action", "unlock_many"),
+            attr("owner", "anyone"),
+            attr("count", "0"),
+            attr("action", "batch"),
+            attr("count", "3"),
+            attr("action", "unlock"),
+            attr("owner", "anyone"),
+            attr("lock_id", "1"),
+            attr("expire", expire),
+            attr("asset", "token"),
+            attr("amount", "2"),
+            attr("asset", "cw20"),
+            attr("amount", "5"),
+            attr("by", "keeper"),
+        ];
+
+        let decoded = decode_events(&attributes).unwrap();
+        let update = |assets: Vec<AssetAmount>| LockUpdate {
+            owner: "anyone".into(),
+            id: "1".into(),
+            expire,
+            assets,
+        };
+        let asset = |asset: &str, amount: u128, total: Option<u128>| AssetAmount {
+            asset: asset.into(),
+            amount: Uint128::new(amount),
+            total: total.map(Uint128::new),
+        };
+        assert_eq!(
+            vec![
+                LockEvent::Created(update(vec![asset("token", 2, Some(2))])),
+                LockEvent::Increased(update(vec![asset("cw20", 5, Some(5))])),
+                LockEvent::Unlocked(update(vec![
+                    asset("token", 2, None),
+                    asset("cw20", 5, None)
+                ])),
+            ],
+            decoded
+        );
+
+        let attributes = vec![attr("action", "lock"), attr("owner", "anyone")];
+        assert_eq!(
+            Err(DecodeError::MissingAttribute {
+                action: "lock".into(),
+                key: "lock_id".into(),
+            }),
+            decode_events(&attributes)
+        );
+        assert_eq!(
+            Err(invalid("expire", "1.5")),
+            parse_expire("1.5").map(|_| ())
+        );
+    }
+
+    #[test]
+    fn decode_lock_state_events() {
+        let expire = Timestamp::from_seconds(100);
+        let group = |action: &str, owner: &str, extra: Vec<Attribute>| {
+            let mut attributes = vec![
+                attr("action", action),
+                attr("owner", owner),
+                attr("lock_id", "1"),
+                attr("expire", expire),
+                attr("asset", "token"),
+                attr("amount", "2"),
+            ];
+            attributes.extend(extra);
+            attributes
+        };
+        let mut attributes = vec![
+            attr("action", "unlock_many"),
+            attr("owner", "anyone"),
+            attr("count", "1"),
+        ];
+        attributes.extend(group("unlock", "anyone", vec![]));
+        attributes.extend(group("claim_asset", "anyone", vec![]));
+        attributes.extend(group(
+            "withdraw_from",
+            "anyone",
+            vec![attr("spender", "shop")],
+        ));
+        attributes.extend(group("escheat", "anyone", vec![]));
+        attributes.extend(group("rollover", "anyone", vec![]));
+        attributes.extend(group("sell_lock", "anyone", vec![]));
+        attributes.extend(group("buy_lock", "buyer", vec![attr("price", "5")]));
+        attributes.extend(group("seize_lock", "buyer", vec![attr("lender", "bank")]));
+        attributes.extend(group("accept_extension", "bank", vec![attr("bonus", "1")]));
+        attributes.extend(vec![
+            attr("action", "claim_lp_rewards"),
+            attr("lp_token", "lp"),
+            attr("fee_share", "fee_share"),
+        ]);
+        attributes.extend(group("credit_lp_rewards", "bank", vec![]));
+
+        let update = |owner: &str| LockUpdate {
+            owner: owner.into(),
+            id: "1".into(),
+            expire,
+            assets: vec![AssetAmount {
+                asset: "token".into(),
+                amount: Uint128::new(2),
+                total: None,
+            }],
+        };
+        assert_eq!(
+            vec![
+                LockEvent::Unlocked(update("anyone")),
+                LockEvent::AssetClaimed(update("anyone")),
+                LockEvent::Withdrawn(update("anyone")),
+                LockEvent::Escheated(update("anyone")),
+                LockEvent::RolledOver(update("anyone")),
+                LockEvent::Sold(update("anyone")),
+                LockEvent::Bought(update("buyer")),
+                LockEvent::Seized {
+                    update: update("buyer"),
+                    lender: "bank".into(),
+                },
+                LockEvent::Extended(update("bank")),
+                LockEvent::RewardsCredited(update("bank")),
+            ],
+            decode_events(&attributes).unwrap()
+        );
+
+        let attributes = group("seize_lock", "buyer", vec![]);
+        assert_eq!(
+            Err(DecodeError::MissingAttribute {
+                action: "seize_lock".into(),
+                key: "lender".into(),
+            }),
+            decode_events(&attributes)
+        );
+    }
+}
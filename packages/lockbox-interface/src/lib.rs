@@ -0,0 +1,8 @@
+#[cfg(feature = "client")]
+pub mod client;
+pub mod events;
+pub mod helpers;
+#[cfg(feature = "indexer")]
+pub mod indexer;
+pub mod msg;
+pub mod querier;
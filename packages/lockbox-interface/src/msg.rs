@@ -0,0 +1,1817 @@
+use cosmwasm_std::{Attribute, Binary, Coin, CosmosMsg, Timestamp, Uint128};
+use cw20::{Cw20Coin, Cw20ReceiveMsg, Denom};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct InstantiateMsg {
+    /// Max lock time in seconds
+    pub max_lock_time: u64,
+    /// Max native coins per lock, defaults to DEFAULT_MAX_ASSETS
+    pub max_native_coins: Option<u32>,
+    /// Max cw20 tokens per lock, defaults to DEFAULT_MAX_ASSETS
+    pub max_cw20_tokens: Option<u32>,
+    /// Seconds after expire reserved to the lock owner before UnlockFor is allowed
+    pub grace_period: Option<u64>,
+    /// Max lock creations per owner per window, unlimited by default
+    pub rate_limit: Option<RateLimit>,
+    /// Max active locks in the contract, unlimited by default
+    pub max_active_locks: Option<u64>,
+    /// Lock time in seconds used when Lock omits expire, required by default
+    pub default_lock_time: Option<u64>,
+    /// Min amount of each asset deposited by IncreaseLock, no minimum by default
+    pub min_increase: Option<Uint128>,
+    /// Seconds between announcing and executing EmergencyWithdraw,
+    /// disabled for the contract lifetime if not set
+    pub emergency_delay: Option<u64>,
+    /// Disable every owner power for the contract lifetime, including EmergencyWithdraw
+    #[serde(default)]
+    pub immutable: bool,
+    /// Contracts can only receive lock funds as beneficiary, spender, swap router, deposit hook
+    /// or UnlockFor recipient if they are in the recipient allowlist
+    #[serde(default)]
+    pub restrict_contract_recipients: bool,
+    /// Cw20 token reported by the Balance query, which is disabled if not set
+    pub gating_token: Option<String>,
+    /// Reject cw20 increases of locks holding any other asset
+    #[serde(default)]
+    pub single_asset_mode: bool,
+    /// Locks can be released at their expire second, instead of only after it
+    #[serde(default)]
+    pub inclusive_expire: bool,
+    /// Handling of locks holding more than one asset, allowed by default
+    #[serde(default)]
+    pub multi_denom_policy: MultiDenomPolicy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MigrateMsg {
+    /// Switches locks to be released at their expire second, or back to only after it.
+    /// Keeps the current boundary if not set
+    #[serde(default)]
+    pub inclusive_expire: Option<bool>,
+    /// Replaces the multi denom policy if set
+    #[serde(default)]
+    pub multi_denom_policy: Option<MultiDenomPolicy>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MultiDenomPolicy {
+    /// Lock all the denoms together
+    Allow,
+    /// Fail the Lock, and any deposit adding a second asset to a lock
+    Reject,
+    /// Lock each denom apart, under the Lock id suffixed with -denom.
+    /// Other deposits adding a second asset to a lock fail
+    Split,
+}
+
+impl Default for MultiDenomPolicy {
+    fn default() -> Self {
+        MultiDenomPolicy::Allow
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateLimit {
+    pub max_locks: u32,
+    /// Window length in seconds, zero removes the limit
+    pub window: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Lock funds until expire timestamp, or for the default lock time if omitted.
+    /// The referrer, such as a partner frontend, is credited the lock in its stats.
+    /// If amount is declared, sent funds over it are refunded
+    Lock {
+        id: String,
+        expire: Option<Timestamp>,
+        referrer: Option<String>,
+        amount: Option<Vec<Coin>>,
+    },
+    /// Lock funds under an id derived from sender, expire, funds and a nonce, returned in LockData
+    LockDerived {
+        expire: Timestamp,
+    },
+    /// Lock funds on behalf of owner, only callable by whitelisted lockers
+    LockFor {
+        owner: String,
+        id: String,
+        expire: Timestamp,
+    },
+    /// Lock funds released only after threshold of owners approve the unlock
+    LockJoint {
+        id: String,
+        expire: Timestamp,
+        owners: Vec<String>,
+        threshold: u32,
+    },
+    /// Approve unlock of a joint lock, releases funds to owner once threshold is met after expire
+    ApproveUnlock {
+        owner: String,
+        id: String,
+    },
+    /// Increase previous lock, failing if it expires in less than min_remaining seconds
+    IncreaseLock {
+        id: String,
+        min_remaining: Option<u64>,
+    },
+    /// Unlock funds
+    Unlock {
+        id: String,
+    },
+    /// Unlock several expired locks, batching transfers of the same token
+    UnlockMany {
+        ids: Vec<String>,
+    },
+    /// Unlock an expired lock on behalf of its owner, allowed to anyone after the grace period
+    UnlockFor {
+        owner: String,
+        id: String,
+    },
+    /// Allow relayer to unlock the sender locks until expires, such as a frontend sponsoring gas
+    AuthorizeRelayer {
+        relayer: String,
+        expires: Option<Timestamp>,
+    },
+    /// Remove the relayer authorization of the sender
+    RevokeRelayer {
+        relayer: String,
+    },
+    /// Unlock an expired lock as its owner, only callable by an authorized relayer
+    RelayUnlock {
+        owner: String,
+        id: String,
+    },
+    /// Let controller, the local account of a Polytone voice proxy or an ICA host, manage the
+    /// sender locks with RemoteLock. A controller acts for a single owner
+    MapController {
+        controller: String,
+    },
+    /// Remove the controller mapping of the sender
+    UnmapController {
+        controller: String,
+    },
+    /// Run msg on the locks of the owner mapped to the sender controller. The LockData in the
+    /// response data is what the voice callback or the ICA ack returns to the home chain
+    RemoteLock {
+        msg: RemoteLockMsg,
+    },
+    /// Release a single asset of an unlockable lock, for locks holding too many assets at once
+    ClaimAsset {
+        id: String,
+        asset: Denom,
+    },
+    /// Release the next asset of an unlockable lock, by order or else by the lock asset order
+    ClaimNext {
+        id: String,
+        order: Option<Vec<Denom>>,
+    },
+    /// Set the order ClaimNext releases assets, such as stablecoins before governance tokens
+    SetAssetOrder {
+        id: String,
+        order: Vec<Denom>,
+    },
+    /// Let spender withdraw up to amount of asset once the lock expires, zero revokes it.
+    /// Joint and beneficiary locks can't grant allowances
+    SetWithdrawAllowance {
+        id: String,
+        spender: String,
+        asset: Denom,
+        amount: Uint128,
+    },
+    /// Withdraw part of an expired lock using an allowance granted by its owner
+    WithdrawFrom {
+        owner: String,
+        id: String,
+        asset: Denom,
+        amount: Uint128,
+    },
+    /// This accepts a properly-encoded ReceiveMsg from a cw20 contract
+    Receive(Cw20ReceiveMsg),
+    /// Update config, only callable by owner
+    UpdateConfig {
+        max_lock_time: Option<u64>,
+        max_native_coins: Option<u32>,
+        max_cw20_tokens: Option<u32>,
+        grace_period: Option<u64>,
+        /// A zero window removes the limit
+        rate_limit: Option<RateLimit>,
+        /// Zero is unlimited
+        max_active_locks: Option<u64>,
+        /// Zero requires expire on Lock
+        default_lock_time: Option<u64>,
+        /// Zero removes the minimum
+        min_increase: Option<Uint128>,
+    },
+    /// Replace the contract profile, only callable by owner
+    UpdateProfile {
+        name: Option<String>,
+        description: Option<String>,
+        website: Option<String>,
+        logo_url: Option<String>,
+    },
+    /// Add or remove contracts allowed to use LockFor, only callable by owner
+    UpdateLockers {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Add or remove locker contracts allowed to send MigrateIn, only callable by owner
+    UpdateLegacyLockers {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Add or remove lending contracts allowed to encumber locks, only callable by owner
+    UpdateLenders {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Reserve a lock id prefix such as "launchpad/", only callable by lockers.
+    /// Ids with the prefix can then only be locked by the sender, directly or with LockFor
+    ReserveIdPrefix {
+        prefix: String,
+    },
+    /// Add or remove owners allowed to lock over max_active_locks, only callable by owner
+    UpdateCapBypass {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Add or remove contracts allowed as beneficiary when restrict_contract_recipients is set,
+    /// only callable by owner
+    UpdateRecipientAllowlist {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Reject new locks and increases of the cw20 token, holding its unlocks too if set,
+    /// only callable by owner and operators
+    FreezeToken {
+        token: String,
+        hold_unlocks: bool,
+    },
+    /// Only callable by owner and operators
+    UnfreezeToken {
+        token: String,
+    },
+    /// Reject all msgs but the pause, role admin, index rebuild and emergency withdraw msgs,
+    /// resuming at until if set, only callable by owner and operators
+    Pause {
+        until: Option<Timestamp>,
+    },
+    /// Only callable by owner and operators
+    Unpause {},
+    /// Unlocks pay the old cw20 token through converter, which must pay at least as much of
+    /// the new token, starting MIGRATION_DELAY seconds later. Only callable by owner
+    RegisterTokenMigration {
+        old_token: String,
+        new_token: String,
+        /// Contract implementing ConverterExecuteMsg
+        converter: String,
+    },
+    /// Only callable by owner
+    RemoveTokenMigration {
+        old_token: String,
+    },
+    /// Grant address a role replacing its current one, only callable by owner
+    GrantRole {
+        address: String,
+        role: Role,
+    },
+    /// Only callable by owner
+    RevokeRole {
+        address: String,
+    },
+    /// Set or clear the display metadata of a native or IBC denom, only callable by owner
+    SetDenomMetadata {
+        denom: String,
+        metadata: Option<DenomMetadata>,
+    },
+    /// Cache the symbol and decimals of cw20 tokens, returned by Lock V2 with include_token_info
+    CacheTokenInfo {
+        tokens: Vec<String>,
+    },
+    /// Register a new airdrop stage funded with the attached token, only callable by owner
+    RegisterAirdrop {
+        /// Hex encoded sha256 merkle root
+        merkle_root: String,
+        /// Seconds a claimed allocation stays locked
+        lock_time: u64,
+    },
+    /// Claim an airdrop allocation into a new lock
+    ClaimAirdrop {
+        stage: u64,
+        amount: Uint128,
+        /// Hex encoded merkle proof
+        proof: Vec<String>,
+        /// Id of the created lock
+        id: String,
+    },
+    /// Stream the attached token to recipient at rate tokens per second from start,
+    /// which defaults to the block time
+    CreateStream {
+        recipient: String,
+        rate: Uint128,
+        start: Option<Timestamp>,
+    },
+    /// Withdraw the streamed tokens, all of them if amount is not set,
+    /// only callable by the recipient
+    WithdrawStream {
+        id: u64,
+        amount: Option<Uint128>,
+    },
+    /// Pay the streamed tokens to the recipient and refund the rest, only callable by the funder
+    CancelStream {
+        id: u64,
+    },
+    /// Add the attached token to the stream deposit, only callable by the funder
+    TopUpStream {
+        id: u64,
+    },
+    /// Change the stream rate, only callable by the funder.
+    /// A lower rate applies once the recipient accepts it
+    ChangeStreamRate {
+        id: u64,
+        rate: Uint128,
+    },
+    /// Accept the lower rate proposed by the funder, only callable by the recipient
+    AcceptStreamRate {
+        id: u64,
+    },
+    /// Fund a pool with the attached token, split among the schedule recipients.
+    /// Admin defaults to the sender
+    CreateVestingGroup {
+        admin: Option<String>,
+        schedules: Vec<VestingScheduleMsg>,
+    },
+    /// Allocate unallocated pool tokens to a new recipient, only callable by the group admin
+    AddVestingSchedule {
+        group: u64,
+        schedule: VestingScheduleMsg,
+    },
+    /// Claim the vested tokens, only callable by the recipient
+    ClaimVesting {
+        group: u64,
+    },
+    /// Stop the recipient schedule, returning its unvested tokens to the pool.
+    /// Only callable by the group admin
+    ClawbackVesting {
+        group: u64,
+        recipient: String,
+    },
+    /// Withdraw unallocated pool tokens, all of them if amount is not set.
+    /// Only callable by the group admin
+    WithdrawVestingPool {
+        group: u64,
+        amount: Option<Uint128>,
+    },
+    /// Set or clear the swap executed when the lock is unlocked, not allowed with a beneficiary
+    SetSwap {
+        id: String,
+        swap: Option<SwapMsg>,
+    },
+    /// Set or clear the contract deposit executed when the lock is unlocked,
+    /// not allowed with a beneficiary
+    SetDepositHook {
+        id: String,
+        hook: Option<DepositHookMsg>,
+    },
+    /// Set or clear the contract notified when the lock is unlocked, its failure is ignored
+    SetUnlockCallback {
+        id: String,
+        callback: Option<UnlockCallbackMsg>,
+    },
+    /// Set or clear the contract that must approve releasing the lock funds,
+    /// replacing or clearing a guardian needs its approval
+    SetGuardian {
+        id: String,
+        guardian: Option<String>,
+    },
+    /// Opt the lock in or out of being moved into the current successor lockbox once expired,
+    /// instead of paid out
+    SetRollover {
+        id: String,
+        rollover: bool,
+    },
+    /// Set or clear the lockbox locks opting in to rollover are moved into, only callable
+    /// by owner. Locks already opted in keep their successor. This contract must be a locker
+    /// of the successor
+    UpdateSuccessor {
+        successor: Option<String>,
+    },
+    /// Move an expired lock opted in to rollover into the successor, locked again for
+    /// its original duration, allowed to anyone
+    ProcessRollover {
+        owner: String,
+        id: String,
+    },
+    /// Set or clear the recurring cw20 deposit into the lock, pulled from the sender allowance
+    SetPlan {
+        id: String,
+        plan: Option<PlanMsg>,
+    },
+    /// Process the due plan deposit of a lock, callable by anyone
+    Crank {
+        owner: String,
+        id: String,
+    },
+    /// Set or clear the savings goal allowing unlock once reached. Before expire the goal
+    /// must be above the lock funds, and can only be raised
+    SetGoal {
+        id: String,
+        goal: Option<GoalMsg>,
+    },
+    /// Hide the lock balances from queries without the owner viewing key
+    SetPrivate {
+        id: String,
+        private: bool,
+    },
+    /// Reject deposits of any asset other than the only one the lock holds
+    SetSingleAsset {
+        id: String,
+        single_asset: bool,
+    },
+    /// Set the sender viewing key, required to query the balances of its private locks
+    SetViewingKey {
+        key: String,
+    },
+    /// Set or clear the secp256k1 public key signing the sender ownership challenges
+    SetOwnershipKey {
+        pubkey: Option<Binary>,
+    },
+    /// Set or clear the payload returned to notifiers by UpcomingExpirations, such as a webhook id
+    SetReminder {
+        id: String,
+        payload: Option<String>,
+    },
+    /// Set or clear an opaque note bound to the lock, such as an encrypted memo or document hash
+    SetNote {
+        id: String,
+        note: Option<Binary>,
+    },
+    /// Ask the contract owner for an early unlock, recording the reason on-chain
+    RequestHardshipUnlock {
+        id: String,
+        reason: String,
+    },
+    /// Approve a requested early unlock with the reason, only callable by owner
+    ApproveHardshipUnlock {
+        owner: String,
+        id: String,
+        reason: String,
+    },
+    /// Set the address receiving the funds on unlock, only if none is set yet and the lock
+    /// has no deposit hook or swap
+    SetBeneficiary {
+        id: String,
+        beneficiary: String,
+    },
+    /// Propose a new beneficiary, callable by the lock owner or the current beneficiary
+    ProposeBeneficiaryChange {
+        owner: String,
+        id: String,
+        beneficiary: String,
+    },
+    /// Accept the proposed beneficiary, callable by the party that did not propose it
+    AcceptBeneficiaryChange {
+        owner: String,
+        id: String,
+    },
+    /// Set or clear the yield adapter of a native denom no live lock holds, only callable by owner
+    RegisterYieldAdapter {
+        denom: String,
+        adapter: Option<String>,
+    },
+    /// Set whether yield goes to locks or to the collector, only callable by owner
+    UpdateYieldConfig {
+        to_lock: bool,
+        collector: Option<String>,
+    },
+    /// Send the yield accrued by the denom adapter to the collector
+    HarvestYield {
+        denom: String,
+    },
+    /// Set or clear the fee-share contract of an LP token, only callable by owner
+    RegisterFeeShare {
+        lp_token: String,
+        /// Contract implementing FeeShareExecuteMsg and FeeShareQueryMsg
+        fee_share: Option<String>,
+    },
+    /// Claim the fee rewards pending for the locked LP token, callable by anyone. Each lock
+    /// holding it accrues its share, credited with CreditLpRewards
+    ClaimLpRewards {
+        lp_token: String,
+    },
+    /// Credit the fee rewards accrued by a lock to its funds, callable by anyone. Locks that
+    /// can't hold them, or were released since, have them paid to their recipient
+    CreditLpRewards {
+        owner: String,
+        id: String,
+    },
+    /// No-op that fails if the contract is insolvent or its indexes are inconsistent. The locked
+    /// totals are checked limit owners at a time, pass the next_start_after attribute as
+    /// start_after to check the next owners
+    AssertInvariants {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Announce a drain of all contract funds to recipient, executable after emergency_delay.
+    /// Only callable by owner and only if enabled at instantiation
+    AnnounceEmergencyWithdraw {
+        recipient: String,
+    },
+    /// Cancel the announced emergency withdraw, only callable by owner
+    CancelEmergencyWithdraw {},
+    /// Send all contract funds to the announced recipient once the delay is over,
+    /// only callable by owner
+    EmergencyWithdraw {},
+    /// Set or clear the escheat of abandoned locks, only callable by owner
+    UpdateEscheatConfig {
+        config: Option<EscheatConfigMsg>,
+    },
+    /// Warn that a lock is abandoned, callable by anyone. Escheat requires several warnings
+    WarnAbandoned {
+        owner: String,
+        id: String,
+    },
+    /// Send the funds of a warned abandoned lock to the escheat destination, callable by anyone.
+    /// Joint and beneficiary locks are never escheated
+    Escheat {
+        owner: String,
+        id: String,
+    },
+    /// Set or clear the contract governing max_lock_time, only callable by owner
+    UpdateParamRegistry {
+        registry: Option<ParamRegistryMsg>,
+    },
+    /// Offer the attached token as a bonus to lockers extending their locks to min_expire
+    /// before deadline, only callable by owner
+    CreateExtensionCampaign {
+        /// Locked asset the bonus is computed on
+        asset: Denom,
+        /// Bonus in basis points of the locked asset amount
+        bonus_bps: u64,
+        min_expire: Timestamp,
+        deadline: Timestamp,
+    },
+    /// Extend a lock to expire, reserving its campaign bonus
+    AcceptExtension {
+        campaign: u64,
+        id: String,
+        expire: Timestamp,
+    },
+    /// Claim the bonus of an accepted extension once the extended expire is reached. The lock
+    /// must still be held, so the bonus is claimed before unlocking
+    ClaimExtensionBonus {
+        campaign: u64,
+        id: String,
+    },
+    /// Refund the unreserved campaign bonus after the deadline, only callable by owner
+    CloseExtensionCampaign {
+        campaign: u64,
+    },
+    /// List a lock for sale at price of asset. Private locks and locks binding other parties
+    /// (joint, plan, beneficiary, guardian, hardship, allowances, callback or deposit hook)
+    /// can't be listed
+    ListLock {
+        id: String,
+        asset: Denom,
+        price: Uint128,
+    },
+    DelistLock {
+        id: String,
+    },
+    /// Buy a listed lock paying its exact price in native coins, the lock keeps its id.
+    /// Cw20 prices are paid with ReceiveMsg::BuyLock
+    BuyLock {
+        owner: String,
+        id: String,
+    },
+    /// Allow a lender to encumber the lock, or clear the approval, only callable by the lock owner
+    ApproveLender {
+        id: String,
+        lender: Option<String>,
+    },
+    /// Mark a lock as loan collateral of the sender, blocking its release and sale until
+    /// released or seized. Only callable by lenders the lock owner approved
+    Encumber {
+        owner: String,
+        id: String,
+    },
+    /// Release the encumbrance, only callable by its lender
+    ReleaseEncumbrance {
+        owner: String,
+        id: String,
+    },
+    /// Move an encumbered lock to its lender, only callable by its lender
+    SeizeLock {
+        owner: String,
+        id: String,
+    },
+    /// Execute msgs atomically in order, attached funds are passed to the only msg taking funds.
+    /// Batch and Receive can't be nested
+    Batch {
+        msgs: Vec<ExecuteMsg>,
+    },
+    /// Index the next locks of the rebuild started by migrate, callable by anyone.
+    /// Other msgs fail until every lock is indexed
+    RebuildLockIndexes {
+        limit: Option<u32>,
+    },
+    /// Send a NotifyPacket over channel_id on each lock created or released, or stop sending
+    /// them if not set. The channel must be a connected channel of the notify protocol, only
+    /// callable by owner
+    SetNotifyChannel {
+        channel_id: Option<String>,
+    },
+}
+
+/// Lock msgs a controller can run for its owner, a failed msg is reverted in full and
+/// acknowledged as an error
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteLockMsg {
+    /// Lock the attached funds for the owner
+    Lock {
+        id: String,
+        expire: Option<Timestamp>,
+    },
+    /// Add the attached funds to a lock of the owner
+    IncreaseLock { id: String },
+    /// Unlock an expired lock of the owner, paying out to the owner
+    Unlock { id: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParamRegistryMsg {
+    /// Contract implementing ParamRegistryQueryMsg
+    pub contract: String,
+    /// Param holding max_lock_time in seconds
+    pub key: String,
+    /// Seconds a queried value is reused before querying the registry again
+    pub cache_ttl: u64,
+}
+
+/// Query expected from parameter registry contracts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamRegistryQueryMsg {
+    Param { key: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParamResponse {
+    pub value: u64,
+}
+
+/// Locks unclaimed for abandon_after seconds past expire can be escheated
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EscheatConfigMsg {
+    /// At least a year
+    pub abandon_after: u64,
+    /// Receives the funds, such as an auction contract or a community pool account
+    pub destination: String,
+    /// Warnings required before escheat, at least one
+    pub warnings: u32,
+    /// Min seconds between warnings and between the last warning and escheat
+    pub warning_interval: u64,
+}
+
+/// Interface expected from yield adapters, one per native denom.
+/// Deposit is sent with the coins attached, Withdraw returns amount to the sender
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum YieldAdapterExecuteMsg {
+    Deposit {},
+    Withdraw { amount: Uint128 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum YieldAdapterQueryMsg {
+    /// Returns the deposits of address including yield
+    Balance { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct YieldBalanceResponse {
+    pub amount: Uint128,
+}
+
+/// Interface expected from fee-share contracts paying protocol fees to LP token holders.
+/// Claim sends the pending rewards to the sender
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeShareExecuteMsg {
+    Claim {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeShareQueryMsg {
+    /// Returns the native rewards claimable by address
+    Pending { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeSharePendingResponse {
+    pub rewards: Vec<Coin>,
+}
+
+/// Interface expected from token converters, sent as Send payload with the old token.
+/// The converter pays at least the same amount of the new token to recipient
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConverterExecuteMsg {
+    Convert { recipient: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GoalMsg {
+    pub denom: Denom,
+    pub amount: Uint128,
+    /// Unlock requires both expire and goal, otherwise either is enough
+    pub require_expire: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PlanMsg {
+    /// Cw20 token pulled with TransferFrom, requires an allowance to this contract
+    pub token: String,
+    pub amount: Uint128,
+    /// Seconds between deposits
+    pub interval: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositHookMsg {
+    /// Contract implementing DepositHookQueryMsg
+    pub contract: String,
+    /// Locked token deposited into contract
+    pub denom: Denom,
+    /// JSON execute msg, "{{amount}}" is replaced with the deposited amount
+    pub msg: String,
+}
+
+/// Query expected from deposit hook contracts, used to validate the msg when the hook is set
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositHookQueryMsg {
+    SimulateDeposit {
+        denom: Denom,
+        amount: Uint128,
+        msg: Binary,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateDepositResponse {
+    pub accepted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnlockCallbackMsg {
+    /// Contract implementing LockCallbackMsg
+    pub contract: String,
+    /// Payload passed back in LockCallbackMsg::LockUnlocked
+    pub msg: Binary,
+}
+
+/// Query expected from guardian contracts, such as a session key or 2FA verifier
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardianQueryMsg {
+    /// Asked before Unlock, UnlockMany, ClaimAsset and WithdrawFrom release the lock funds
+    ApproveUnlock { owner: String, id: String },
+    /// Asked before SetGuardian replaces or clears the guardian
+    ApproveGuardianChange {
+        owner: String,
+        id: String,
+        guardian: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GuardianApprovalResponse {
+    pub approved: bool,
+}
+
+/// Execute msg sent to unlock callback contracts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LockCallbackMsg {
+    LockUnlocked {
+        owner: String,
+        id: String,
+        msg: Binary,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapMsg {
+    /// Router contract implementing RouterExecuteMsg
+    pub router: String,
+    /// Locked token sent to the router
+    pub offer: Denom,
+    /// Token to receive
+    pub ask: Denom,
+    /// Slippage protection, enforced by the router
+    pub min_receive: Uint128,
+}
+
+/// Interface expected from swap routers. Native offers are attached to the
+/// execute message, cw20 offers are sent with this message as Send payload
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RouterExecuteMsg {
+    Swap {
+        ask: Denom,
+        min_receive: Uint128,
+        recipient: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    Lock {
+        id: String,
+        expire: Option<Timestamp>,
+        referrer: Option<String>,
+    },
+    LockDerived {
+        expire: Timestamp,
+    },
+    LockFor {
+        owner: String,
+        id: String,
+        expire: Timestamp,
+    },
+    IncreaseLock {
+        id: String,
+        min_remaining: Option<u64>,
+    },
+    LockJoint {
+        id: String,
+        expire: Timestamp,
+        owners: Vec<String>,
+        threshold: u32,
+    },
+    RegisterAirdrop {
+        merkle_root: String,
+        lock_time: u64,
+    },
+    CreateStream {
+        recipient: String,
+        rate: Uint128,
+        start: Option<Timestamp>,
+    },
+    TopUpStream {
+        id: u64,
+    },
+    CreateVestingGroup {
+        admin: Option<String>,
+        schedules: Vec<VestingScheduleMsg>,
+    },
+    CreateExtensionCampaign {
+        asset: Denom,
+        bonus_bps: u64,
+        min_expire: Timestamp,
+        deadline: Timestamp,
+    },
+    /// Recreates a lock moved out of a legacy locker contract, sent by that contract
+    MigrateIn {
+        owner: String,
+        id: String,
+        create: Timestamp,
+        expire: Timestamp,
+    },
+    BuyLock {
+        owner: String,
+        id: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the contract profile
+    Profile {},
+    /// Versioned queries, responses may differ from the unversioned ones
+    V2(QueryMsgV2),
+    /// Returns the lock info, balances of private locks are empty without the owner viewing key
+    Lock {
+        address: String,
+        id: String,
+        viewing_key: Option<String>,
+    },
+    /// Returns the locks by address, all of them unless limit is set
+    AllLocks {
+        address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the contracts allowed to use LockFor
+    Lockers {},
+    /// Returns the locker contracts allowed to send MigrateIn
+    LegacyLockers {},
+    /// Returns the lending contracts allowed to encumber locks
+    Lenders {},
+    /// Returns the contracts allowed as beneficiary
+    RecipientAllowlist {},
+    /// Returns the frozen cw20 tokens
+    FrozenTokens {},
+    /// Returns the pause in effect at the block time, if any
+    Pause {},
+    /// Returns the lockbox locks opted in to rollover are moved into
+    Successor {},
+    /// Returns the cw20 tokens converted on unlock
+    TokenMigrations {},
+    /// Returns the addresses granted a role
+    Roles {},
+    /// Returns the relayers authorized by owner
+    Relayers { owner: String },
+    /// Returns the owner mapped to controller, if any
+    Controller { controller: String },
+    /// Returns the locks created through the referrer address and their funds
+    Referrer { address: String },
+    /// Returns the locker holding the id prefix
+    IdPrefix { prefix: String },
+    /// Returns the number of active locks
+    ActiveLocks {},
+    /// Returns the number of locks held by address
+    LockCount { address: String },
+    /// Returns the addresses holding at least one lock, ordered by address
+    Owners {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the deposits made into the lock after creation,
+    /// empty for a private lock without the owner viewing key
+    Deposits {
+        address: String,
+        id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        viewing_key: Option<String>,
+    },
+    /// Returns the lock creations, deposits and withdrawals of address's locks
+    /// from from_time (inclusive) to to_time (exclusive),
+    /// private locks are left out without the owner viewing key
+    ActivityReport {
+        address: String,
+        from_time: Timestamp,
+        to_time: Timestamp,
+        viewing_key: Option<String>,
+    },
+    /// Returns the lock funds released at time t (defaults to block time),
+    /// compatible with cw-vesting style consumers,
+    /// empty for a private lock without the owner viewing key
+    Vested {
+        address: String,
+        id: String,
+        t: Option<Timestamp>,
+        viewing_key: Option<String>,
+    },
+    /// Returns the lock funds still locked at time t (defaults to block time),
+    /// empty for a private lock without the owner viewing key
+    Unvested {
+        address: String,
+        id: String,
+        t: Option<Timestamp>,
+        viewing_key: Option<String>,
+    },
+    /// Returns what would be claimable and what would remain locked at_time,
+    /// empty for a private lock without the owner viewing key
+    ReleasePreview {
+        owner: String,
+        id: String,
+        at_time: Timestamp,
+        viewing_key: Option<String>,
+    },
+    /// Returns if the lock can be unlocked now, by expire, goal or hardship, and joint approval
+    CanUnlock { owner: String, id: String },
+    /// Returns the locked funds by expire day, for the days starting from the day of from
+    /// up to to, exclusive, private locks left out
+    UnlockSchedule { from: Timestamp, to: Timestamp },
+    /// Returns the Lock rules failed by expire and the native coins to send, if any,
+    /// and the allowed expire range at the current block time
+    ValidateLockParams {
+        expire: Option<Timestamp>,
+        amount_hints: Option<Vec<Coin>>,
+    },
+    /// Returns the total funds locked by address at the beginning of height,
+    /// empty if address holds a private lock and the viewing key is missing
+    LockedAtHeight {
+        address: String,
+        height: u64,
+        viewing_key: Option<String>,
+    },
+    /// Returns the airdrop stage
+    Airdrop { stage: u64 },
+    /// Returns whether address claimed the airdrop stage
+    AirdropClaimed { stage: u64, address: String },
+    /// Returns the total gating token locked by address, same as the cw20 Balance query
+    Balance { address: String },
+    /// Returns the payload the lock owner signs to prove its ownership off-chain
+    OwnershipChallenge {
+        address: String,
+        id: String,
+        nonce: String,
+    },
+    /// Returns whether signature is the owner key signature of the challenge hash
+    VerifyOwnership {
+        address: String,
+        id: String,
+        nonce: String,
+        signature: Binary,
+    },
+    /// Returns the stream and the tokens streamed by at_time, the block time by default
+    Stream { id: u64, at_time: Option<Timestamp> },
+    /// Returns the vesting group and its unallocated tokens
+    VestingGroup { group: u64 },
+    /// Returns the recipient schedule and the tokens vested by at_time, the block time by default
+    VestingSchedule {
+        group: u64,
+        recipient: String,
+        at_time: Option<Timestamp>,
+    },
+    /// Returns the locks with a reminder expiring within the next hours, ordered by expire
+    UpcomingExpirations {
+        hours: u64,
+        start_after: Option<ExpirationCursor>,
+        limit: Option<u32>,
+    },
+    /// Returns the locks of any owner expiring before time, ordered by expire
+    LocksExpiringBefore {
+        time: Timestamp,
+        start_after: Option<ExpirationCursor>,
+        limit: Option<u32>,
+    },
+    /// Returns the highest block time seen by lock and unlock
+    TimeHighWater {},
+    /// Returns if an execution is in progress, which is never the case between transactions
+    ExecutionGuard {},
+    /// Returns the yield adapter of a native denom
+    YieldAdapter { denom: String },
+    /// Returns the fee-share contract of an LP token
+    FeeShare { lp_token: String },
+    /// Returns per asset the funds owed to locks, airdrops, streams, vesting groups and
+    /// extension campaigns vs. the contract balance
+    Solvency {},
+    /// Returns the announced emergency withdraw, if any
+    EmergencyWithdrawal {},
+    /// Returns the escheat config, if enabled
+    EscheatConfig {},
+    /// Returns the param registry, if set, and the max lock time at the block time
+    ParamRegistry {},
+    /// Returns an extension campaign with its unreserved bonus
+    ExtensionCampaign { campaign: u64 },
+    /// Returns the locks listed for sale by owner, ordered by id
+    Listings {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Runs the execute msg without saving its changes, returning its msgs or its error
+    Simulate {
+        sender: String,
+        #[serde(default)]
+        funds: Vec<Coin>,
+        msg: ExecuteMsg,
+    },
+    /// Returns the channel lock notifications are sent over, if any
+    NotifyChannel {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsgV2 {
+    /// Returns LockInfoV2
+    Lock {
+        address: String,
+        id: String,
+        /// Owner viewing key, required for the balances of private locks
+        viewing_key: Option<String>,
+        /// Include the unlock swap, deposit hook, plan and pending beneficiary change
+        #[serde(default)]
+        include_config: bool,
+        /// Include the symbol and decimals of the cw20 tokens
+        #[serde(default)]
+        include_token_info: bool,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub max_lock_time: u64,
+    pub owner: String,
+    /// Max native coins per lock
+    pub max_native_coins: u32,
+    /// Max cw20 tokens per lock
+    pub max_cw20_tokens: u32,
+    /// Seconds after expire reserved to the lock owner
+    pub grace_period: u64,
+    /// Max lock creations per owner per window
+    pub rate_limit: Option<RateLimit>,
+    /// Max active locks in the contract, zero is unlimited
+    pub max_active_locks: u64,
+    /// Lock time used when Lock omits expire, zero requires expire
+    pub default_lock_time: u64,
+    /// Min amount of each asset deposited by IncreaseLock
+    pub min_increase: Uint128,
+    /// Seconds between announcing and executing EmergencyWithdraw, none disables it for good
+    pub emergency_delay: Option<u64>,
+    /// Owner powers are disabled for the contract lifetime
+    pub immutable: bool,
+    /// Contracts must be in the recipient allowlist to receive lock funds
+    pub restrict_contract_recipients: bool,
+    /// Cw20 token reported by the Balance query
+    pub gating_token: Option<String>,
+    /// Cw20 increases must use the only asset the lock holds
+    pub single_asset_mode: bool,
+    /// Locks are expired at their expire second instead of after it
+    pub inclusive_expire: bool,
+    /// Handling of locks holding more than one asset
+    pub multi_denom_policy: MultiDenomPolicy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockInfo {
+    pub id: String,
+    pub create: Timestamp,
+    pub expire: Timestamp,
+    /// Funds in native tokens
+    pub native_balance: Vec<Coin>,
+    /// Funds in cw20 tokens
+    pub cw20_balance: Vec<Cw20Coin>,
+}
+
+/// LockInfo extended with the optional lock features, new fields are only added here
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockInfoV2 {
+    pub owner: String,
+    pub id: String,
+    pub create: Timestamp,
+    pub expire: Timestamp,
+    /// Funds in native tokens
+    pub native_balance: Vec<Coin>,
+    /// Funds in cw20 tokens
+    pub cw20_balance: Vec<Cw20Coin>,
+    /// Receives the funds on unlock instead of the owner
+    pub beneficiary: Option<String>,
+    /// Savings goal progress
+    pub goal: Option<GoalInfo>,
+    /// Joint owners approving the unlock
+    pub joint: Option<JointInfo>,
+    /// Opaque note set by the owner
+    pub note: Option<Binary>,
+    /// Balances are empty unless queried with the owner viewing key
+    pub private: bool,
+    /// Deposits of any asset other than the one held are rejected
+    pub single_asset: bool,
+    /// Early unlock requested by the owner
+    pub hardship: Option<HardshipRequest>,
+    /// Display metadata of the native denoms with registered metadata
+    pub denom_metadata: Vec<DenomMetadataInfo>,
+    /// Lock terms, only returned with include_config
+    pub config: Option<LockConfig>,
+    /// Symbol and decimals of the cw20 tokens, only returned with include_token_info
+    pub token_info: Option<Vec<DenomMetadataInfo>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct HardshipRequest {
+    pub reason: String,
+    pub requested_at: Timestamp,
+    /// Contract owner reason, the lock can be unlocked once set
+    pub approval: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LockConfig {
+    /// Swap executed on unlock
+    pub swap: Option<SwapMsg>,
+    /// Deposit into another contract on unlock
+    pub deposit_hook: Option<DepositHookMsg>,
+    /// Contract notified on unlock
+    pub unlock_callback: Option<UnlockCallbackMsg>,
+    /// Contract approving the release of the lock funds
+    pub guardian: Option<String>,
+    /// Moved into the successor lockbox once expired
+    pub rollover: bool,
+    /// Lending contract holding the lock as collateral
+    pub encumbrance: Option<String>,
+    /// Recurring cw20 deposits into the lock
+    pub plan: Option<PlanInfo>,
+    /// Beneficiary change waiting for the other party approval
+    pub beneficiary_proposal: Option<BeneficiaryProposalInfo>,
+    /// Amounts spenders can withdraw once the lock expires
+    pub allowances: Vec<AllowanceInfo>,
+    /// Order ClaimNext releases assets
+    pub asset_order: Vec<Denom>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PlanInfo {
+    pub token: String,
+    pub amount: Uint128,
+    /// Seconds between deposits
+    pub interval: u64,
+    /// Next deposit is due at this time
+    pub next: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AllowanceInfo {
+    pub spender: String,
+    pub asset: Denom,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BeneficiaryProposalInfo {
+    pub beneficiary: String,
+    /// Owner or current beneficiary
+    pub proposer: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct JointInfo {
+    pub owners: Vec<String>,
+    pub threshold: u32,
+    pub approvals: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct GoalInfo {
+    pub denom: Denom,
+    pub amount: Uint128,
+    pub require_expire: bool,
+    /// Amount of denom currently locked
+    pub current: Uint128,
+    pub reached: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct AllLocksResponse {
+    pub locks: Vec<String>,
+    /// Pass as start_after to get the next page, none on the last page
+    pub next_start_after: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct LockersResponse {
+    pub lockers: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct LegacyLockersResponse {
+    pub lockers: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct LendersResponse {
+    pub lenders: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct RecipientAllowlistResponse {
+    pub contracts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct FrozenTokensResponse {
+    pub tokens: Vec<FrozenToken>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FrozenToken {
+    pub token: String,
+    /// Unlocks of locks holding the token are rejected
+    pub hold_unlocks: bool,
+}
+
+/// Roles granted by the owner to its staff
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Can freeze and unfreeze tokens, but not change the config nor withdraw funds
+    Operator,
+    /// No execute powers, known to off-chain tooling as watch-only staff
+    Viewer,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Role::Operator => "operator",
+            Role::Viewer => "viewer",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct RolesResponse {
+    pub roles: Vec<RoleInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RoleInfo {
+    pub address: String,
+    pub role: Role,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct RelayersResponse {
+    pub relayers: Vec<RelayerInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RelayerInfo {
+    pub relayer: String,
+    /// Authorization never expires if not set
+    pub expires: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct ControllerResponse {
+    pub owner: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct NotifyChannelResponse {
+    pub channel_id: Option<String>,
+}
+
+/// Packet of the notify protocol, sent to the counterparty contract of the notify channel
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyPacket {
+    /// A lock was created holding the balances
+    LockCreated {
+        owner: String,
+        id: String,
+        expire: Timestamp,
+        native_balance: Vec<Coin>,
+        cw20_balance: Vec<Cw20Coin>,
+    },
+    /// The balances left a lock, which is removed once empty
+    LockReleased {
+        owner: String,
+        id: String,
+        native_balance: Vec<Coin>,
+        cw20_balance: Vec<Cw20Coin>,
+    },
+}
+
+/// Acknowledgement of the notify protocol, a failed notification isn't sent again
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyAck {
+    Result(Binary),
+    Error(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DenomMetadata {
+    /// Display symbol, such as ATOM for its IBC voucher
+    pub symbol: String,
+    /// Decimals between the base denom and the display symbol
+    pub decimals: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DenomMetadataInfo {
+    pub denom: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct TokenMigrationsResponse {
+    pub migrations: Vec<TokenMigrationInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenMigrationInfo {
+    pub old_token: String,
+    pub new_token: String,
+    pub converter: String,
+    /// Releases go through the converter from this time on
+    pub active_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct ReferrerResponse {
+    pub count: u64,
+    /// Locked volume in native tokens
+    pub native_volume: Vec<Coin>,
+    /// Locked volume in cw20 tokens
+    pub cw20_volume: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct IdPrefixResponse {
+    pub locker: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DepositsResponse {
+    pub deposits: Vec<DepositInfo>,
+    /// Pass as start_after to get the next page, none on the last page
+    pub next_start_after: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DepositInfo {
+    pub index: u64,
+    /// Depositor, the cw20 sender for cw20 deposits
+    pub from: String,
+    /// Cw20 contract relaying the deposit, none for native deposits
+    pub token_contract: Option<String>,
+    pub time: Timestamp,
+    /// Funds in native tokens
+    pub native_balance: Vec<Coin>,
+    /// Funds in cw20 tokens
+    pub cw20_balance: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ActivityReportResponse {
+    pub address: String,
+    pub from_time: Timestamp,
+    pub to_time: Timestamp,
+    /// Locks created and the funds they locked at creation
+    pub created: ActivitySummary,
+    /// Deposits into existing locks
+    pub deposited: ActivitySummary,
+    /// Unlocks, claims and other releases of lock funds
+    pub withdrawn: ActivitySummary,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ActivitySummary {
+    pub count: u64,
+    /// Funds in native tokens
+    pub native_balance: Vec<Coin>,
+    /// Funds in cw20 tokens
+    pub cw20_balance: Vec<Cw20Coin>,
+}
+
+/// Last expiration of the previous UpcomingExpirations or LocksExpiringBefore page
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ExpirationCursor {
+    pub expire: Timestamp,
+    pub owner: String,
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct UpcomingExpirationsResponse {
+    pub expirations: Vec<UpcomingExpiration>,
+    /// Pass as start_after to get the next page, none on the last page
+    pub next_start_after: Option<ExpirationCursor>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ExpiringLocksResponse {
+    pub locks: Vec<LockData>,
+    /// Pass as start_after to get the next page, none on the last page
+    pub next_start_after: Option<ExpirationCursor>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct UpcomingExpiration {
+    pub expire: Timestamp,
+    pub owner: String,
+    pub id: String,
+    /// Reminder payload set by the owner
+    pub payload: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ActiveLocksResponse {
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LockCountResponse {
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OwnersResponse {
+    pub owners: Vec<OwnerInfo>,
+    /// Pass as start_after to get the next page, none on the last page
+    pub next_start_after: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OwnerInfo {
+    pub address: String,
+    pub lock_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct VestingResponse {
+    /// Funds in native tokens
+    pub native_balance: Vec<Coin>,
+    /// Funds in cw20 tokens
+    pub cw20_balance: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ValidateLockParamsResponse {
+    /// A Lock with the params would pass every rule
+    pub ok: bool,
+    pub violations: Vec<LockParamsViolation>,
+    /// Expire must be after min_expire
+    pub min_expire: Timestamp,
+    /// Expire must be before max_expire
+    pub max_expire: Timestamp,
+}
+
+/// Lock rule failed, named after the error returned by Lock
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LockParamsViolation {
+    MissingExpire {},
+    LowExpired {},
+    HighExpired {},
+    EmptyBalance {},
+    TooManyNativeCoins { max: u32 },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct UnlockScheduleResponse {
+    /// Days holding locked funds, by start time
+    pub days: Vec<ScheduledUnlock>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ScheduledUnlock {
+    /// Start of the day, the funds of the locks expiring that day
+    pub start: Timestamp,
+    pub native_balance: Vec<Coin>,
+    pub cw20_balance: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CanUnlockResponse {
+    pub can_unlock: bool,
+    pub expire: Timestamp,
+    /// Locks are releasable at their expire second if true, only after it otherwise
+    pub inclusive_expire: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ReleasePreviewResponse {
+    pub at_time: Timestamp,
+    /// Funds are released all at once after expire
+    pub expire: Timestamp,
+    /// Native tokens claimable at_time
+    pub claimable_native: Vec<Coin>,
+    /// Cw20 tokens claimable at_time
+    pub claimable_cw20: Vec<Cw20Coin>,
+    /// Native tokens still locked at_time
+    pub locked_native: Vec<Coin>,
+    /// Cw20 tokens still locked at_time
+    pub locked_cw20: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LockedAtHeightResponse {
+    pub height: u64,
+    /// Funds in native tokens
+    pub native_balance: Vec<Coin>,
+    /// Funds in cw20 tokens
+    pub cw20_balance: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AirdropResponse {
+    pub stage: u64,
+    pub merkle_root: String,
+    pub lock_time: u64,
+    pub denom: Denom,
+    pub remaining: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AirdropClaimedResponse {
+    pub claimed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OwnershipChallengeResponse {
+    pub payload: Binary,
+    /// Sha256 of the payload, signed with the key set by SetOwnershipKey
+    pub hash: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VerifyOwnershipResponse {
+    pub valid: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StreamResponse {
+    pub id: u64,
+    pub funder: String,
+    pub recipient: String,
+    pub denom: Denom,
+    /// Total amount streamed once the stream ends
+    pub deposit: Uint128,
+    /// Tokens per second
+    pub rate: Uint128,
+    pub start: Timestamp,
+    pub withdrawn: Uint128,
+    /// Lower rate waiting for the recipient consent
+    pub pending_rate: Option<Uint128>,
+    /// Time the streamed and withdrawable amounts are computed at
+    pub time: Timestamp,
+    /// Streamed by time, including the withdrawn tokens
+    pub streamed: Uint128,
+    /// Streamed by time and not withdrawn yet
+    pub withdrawable: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingScheduleMsg {
+    pub recipient: String,
+    pub amount: Uint128,
+    pub start: Timestamp,
+    /// Nothing vests before the cliff, then the amount vests linearly from start to end
+    pub cliff: Option<Timestamp>,
+    pub end: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VestingGroupResponse {
+    pub id: u64,
+    pub admin: String,
+    pub denom: Denom,
+    /// Tokens held for the group
+    pub deposit: Uint128,
+    /// Tokens owed to the recipients and not claimed yet
+    pub allocated: Uint128,
+    /// Tokens withdrawable by the admin
+    pub available: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct VestingScheduleResponse {
+    pub group: u64,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub start: Timestamp,
+    pub cliff: Option<Timestamp>,
+    pub end: Timestamp,
+    pub claimed: Uint128,
+    /// Time the vested and claimable amounts are computed at
+    pub time: Timestamp,
+    /// Vested by time, including the claimed tokens
+    pub vested: Uint128,
+    /// Vested by time and not claimed yet
+    pub claimable: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TimeHighWaterResponse {
+    pub time: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ExecutionGuardResponse {
+    pub entered: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct FeeShareResponse {
+    pub fee_share: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct YieldAdapterResponse {
+    pub adapter: Option<String>,
+    /// Locked amount deposited into the adapter
+    pub principal: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SuccessorResponse {
+    pub successor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PauseResponse {
+    pub paused: bool,
+    pub paused_at: Option<Timestamp>,
+    /// Contract resumes on its own at this time
+    pub until: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct EmergencyWithdrawalResponse {
+    pub recipient: Option<String>,
+    pub executable_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct EscheatConfigResponse {
+    pub config: Option<EscheatConfigMsg>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ExtensionCampaignResponse {
+    pub campaign: u64,
+    pub asset: Denom,
+    pub bonus_bps: u64,
+    pub min_expire: Timestamp,
+    pub deadline: Timestamp,
+    pub bonus_denom: Denom,
+    /// Bonus not reserved by accepted extensions
+    pub remaining: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ListingsResponse {
+    pub listings: Vec<ListingInfo>,
+    /// Pass as start_after to get the next page, none on the last page
+    pub next_start_after: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ListingInfo {
+    pub id: String,
+    pub asset: Denom,
+    pub price: Uint128,
+    /// Lock expire when listed
+    pub expire: Timestamp,
+    /// Lock funds when listed, in native tokens. Empty once the lock is private
+    pub native_balance: Vec<Coin>,
+    /// Lock funds when listed, in cw20 tokens. Empty once the lock is private
+    pub cw20_balance: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ParamRegistryResponse {
+    pub registry: Option<ParamRegistryMsg>,
+    pub max_lock_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct SimulateResponse {
+    pub ok: bool,
+    pub error: Option<SimulateError>,
+    /// Msgs sent by the contract, including the swaps executed as submessages
+    pub messages: Vec<CosmosMsg>,
+    pub attributes: Vec<Attribute>,
+    pub data: Option<Binary>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SimulateError {
+    /// ContractError variant, such as LockNotExpired, or the StdError variant for std errors
+    pub kind: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SolvencyResponse {
+    pub assets: Vec<AssetSolvency>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AssetSolvency {
+    pub denom: Denom,
+    /// Owed to active locks and airdrops
+    pub liabilities: Uint128,
+    /// Held by the contract, including yield adapter deposits
+    pub balance: Uint128,
+}
+
+/// Returned in Response.data by Lock, IncreaseLock and Unlock
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockData {
+    pub owner: String,
+    pub id: String,
+    pub expire: Timestamp,
+    /// Tokens sent by Unlock, UnlockMany, ClaimAsset and WithdrawFrom, by receiving address
+    #[serde(default)]
+    pub payouts: Vec<PayoutInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayoutInfo {
+    /// Receiver of the transfer, a router or deposit hook contract forwards the tokens it gets
+    /// on behalf of the lock. Migrated tokens are reported as the old token
+    pub recipient: String,
+    pub native: Vec<Coin>,
+    pub cw20: Vec<Cw20Coin>,
+}
@@ -0,0 +1,99 @@
+use cosmwasm_std::{QuerierWrapper, StdResult};
+
+use crate::msg::{AllLocksResponse, ConfigResponse, LockInfo, QueryMsg};
+
+/// Typed queries to a lockbox contract, for contracts integrating with it
+pub struct LockboxQuerier<'a> {
+    querier: &'a QuerierWrapper<'a>,
+    contract: String,
+}
+
+impl<'a> LockboxQuerier<'a> {
+    pub fn new(querier: &'a QuerierWrapper<'a>, contract: impl Into<String>) -> Self {
+        LockboxQuerier {
+            querier,
+            contract: contract.into(),
+        }
+    }
+
+    /// Balances of private locks are empty without the owner viewing key
+    pub fn lock(
+        &self,
+        owner: impl Into<String>,
+        id: impl Into<String>,
+        viewing_key: Option<String>,
+    ) -> StdResult<LockInfo> {
+        self.query(&QueryMsg::Lock {
+            address: owner.into(),
+            id: id.into(),
+            viewing_key,
+        })
+    }
+
+    pub fn all_locks(
+        &self,
+        owner: impl Into<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AllLocksResponse> {
+        self.query(&QueryMsg::AllLocks {
+            address: owner.into(),
+            start_after,
+            limit,
+        })
+    }
+
+    pub fn config(&self) -> StdResult<ConfigResponse> {
+        self.query(&QueryMsg::Config {})
+    }
+
+    fn query<T: serde::de::DeserializeOwned>(&self, msg: &QueryMsg) -> StdResult<T> {
+        self.querier.query_wasm_smart(&self.contract, msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+    use cosmwasm_std::{
+        coins, from_binary, to_binary, ContractResult, SystemResult, Timestamp, WasmQuery,
+    };
+
+    #[test]
+    fn lockbox_querier() {
+        let mut deps = mock_dependencies(&[]);
+        deps.querier.update_wasm(|query| {
+            let msg = match query {
+                WasmQuery::Smart { contract_addr, msg } => {
+                    assert_eq!("lockbox", contract_addr);
+                    msg
+                }
+                _ => panic!("Unexpected query"),
+            };
+            let res = match from_binary(msg).unwrap() {
+                QueryMsg::Lock { address, id, .. } => to_binary(&LockInfo {
+                    id,
+                    create: Timestamp::from_seconds(0),
+                    expire: Timestamp::from_seconds(100),
+                    native_balance: coins(2, address),
+                    cw20_balance: vec![],
+                }),
+                QueryMsg::AllLocks { .. } => to_binary(&AllLocksResponse {
+                    locks: vec!["1".into()],
+                    next_start_after: None,
+                }),
+                _ => panic!("Unexpected query"),
+            };
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        });
+
+        let querier = deps.as_ref().querier;
+        let lockbox = LockboxQuerier::new(&querier, "lockbox");
+        let lock = lockbox.lock("anyone", "1", None).unwrap();
+        assert_eq!("1", lock.id);
+        assert_eq!(coins(2, "anyone"), lock.native_balance);
+        let locks = lockbox.all_locks("anyone", None, None).unwrap();
+        assert_eq!(vec!["1".to_string()], locks.locks);
+    }
+}
@@ -0,0 +1,261 @@
+use std::convert::TryInto;
+
+use cosmwasm_std::{attr, Addr, Deps, DepsMut, Empty, Env, Response, StdResult, Uint128};
+use cw20::Balance;
+use cw_storage_plus::U64Key;
+use sha2::Digest;
+
+use crate::balance::{denom_balance, single_token};
+use crate::contract::try_lock;
+use crate::error::ContractError;
+use crate::msg::{AirdropClaimedResponse, AirdropResponse};
+use crate::params::load_max_lock_time;
+use crate::state::{Airdrop, AIRDROPS, AIRDROP_CLAIMS, AIRDROP_STAGE, STATE};
+
+pub fn try_register_airdrop(
+    deps: DepsMut,
+    env: &Env,
+    balance: Balance,
+    sender: &Addr,
+    merkle_root: String,
+    lock_time: u64,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let max_lock_time = load_max_lock_time(deps.storage, &deps.querier, env, &state)?;
+    if lock_time >= max_lock_time {
+        return Err(ContractError::HighExpired {});
+    }
+
+    // check merkle root length
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(&merkle_root, &mut root_buf)?;
+
+    let (denom, amount) = match single_token(&balance) {
+        Some(token) if !token.1.is_zero() => token,
+        _ => return Err(ContractError::InvalidAirdropFunds {}),
+    };
+
+    let stage = AIRDROP_STAGE.may_load(deps.storage)?.unwrap_or_default() + 1;
+    let airdrop = Airdrop {
+        merkle_root: merkle_root.clone(),
+        lock_time,
+        denom,
+        remaining: amount,
+    };
+    AIRDROPS.save(deps.storage, U64Key::from(stage), &airdrop)?;
+    AIRDROP_STAGE.save(deps.storage, &stage)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "register_airdrop"),
+            attr("stage", stage),
+            attr("merkle_root", merkle_root),
+            attr("amount", amount),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn try_claim_airdrop(
+    deps: DepsMut,
+    env: Env,
+    sender: &Addr,
+    stage: u64,
+    amount: Uint128,
+    proof: Vec<String>,
+    id: String,
+) -> Result<Response, ContractError> {
+    let mut airdrop = AIRDROPS.load(deps.storage, U64Key::from(stage))?;
+
+    let claimed = AIRDROP_CLAIMS.may_load(deps.storage, (U64Key::from(stage), sender))?;
+    if claimed.is_some() {
+        return Err(ContractError::AirdropClaimed {});
+    }
+
+    verify_proof(&airdrop.merkle_root, sender, amount, proof)?;
+
+    airdrop.remaining = airdrop
+        .remaining
+        .checked_sub(amount)
+        .map_err(|_| ContractError::AirdropExhausted {})?;
+    AIRDROPS.save(deps.storage, U64Key::from(stage), &airdrop)?;
+    AIRDROP_CLAIMS.save(deps.storage, (U64Key::from(stage), sender), &Empty {})?;
+
+    // claimed allocation is locked instead of paid out
+    let balance = denom_balance(&airdrop.denom, amount);
+    let expire = env.block.time.plus_seconds(airdrop.lock_time);
+    let mut res = try_lock(deps, env, balance, sender, id, expire)?;
+    res.attributes.push(attr("airdrop_stage", stage));
+
+    Ok(res)
+}
+
+fn verify_proof(
+    merkle_root: &str,
+    sender: &Addr,
+    amount: Uint128,
+    proof: Vec<String>,
+) -> Result<(), ContractError> {
+    let user_input = format!("{}{}", sender, amount);
+    let hash: [u8; 32] = sha2::Sha256::digest(user_input.as_bytes())
+        .as_slice()
+        .try_into()
+        .map_err(|_| ContractError::WrongLength {})?;
+
+    let hash = proof.into_iter().try_fold(hash, |hash, p| {
+        let mut proof_buf = [0; 32];
+        hex::decode_to_slice(p, &mut proof_buf)?;
+        let mut hashes = [hash, proof_buf];
+        hashes.sort_unstable();
+        sha2::Sha256::digest(&hashes.concat())
+            .as_slice()
+            .try_into()
+            .map_err(|_| ContractError::WrongLength {})
+    })?;
+
+    let mut root_buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(merkle_root, &mut root_buf)?;
+    if root_buf != hash {
+        return Err(ContractError::VerificationFailed {});
+    }
+
+    Ok(())
+}
+
+pub fn query_airdrop(deps: Deps, stage: u64) -> StdResult<AirdropResponse> {
+    let airdrop = AIRDROPS.load(deps.storage, U64Key::from(stage))?;
+
+    Ok(AirdropResponse {
+        stage,
+        merkle_root: airdrop.merkle_root,
+        lock_time: airdrop.lock_time,
+        denom: airdrop.denom,
+        remaining: airdrop.remaining,
+    })
+}
+
+pub fn query_airdrop_claimed(
+    deps: Deps,
+    stage: u64,
+    address: String,
+) -> StdResult<AirdropClaimedResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let claimed = AIRDROP_CLAIMS.may_load(deps.storage, (U64Key::from(stage), &addr))?;
+
+    Ok(AirdropClaimedResponse {
+        claimed: claimed.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, LockInfo, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, Timestamp};
+    use cw20::Denom;
+
+    const MERKLE_ROOT: &str = "c99fa94f44ea9a0290b4984b1e694e120611520ad45c965979932123580596f7";
+    const ALICE_PROOF: &str = "40e64ee37a25a29db3f15f8de8cf7d3d781740f3f5ae82c76ffcb8aac4696740";
+
+    #[test]
+    fn claim_airdrop() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // only owner registers airdrops
+        let msg = ExecuteMsg::RegisterAirdrop {
+            merkle_root: MERKLE_ROOT.into(),
+            lock_time: 1000,
+        };
+        let info = mock_info("anyone", &coins(300, "token"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        // single token funds
+        let mut funds = coins(300, "token");
+        funds.extend(coins(300, "earth"));
+        let info = mock_info("creator", &funds);
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+        match res {
+            Err(ContractError::InvalidAirdropFunds {}) => {}
+            _ => panic!("Must return InvalidAirdropFunds error"),
+        }
+
+        let info = mock_info("creator", &coins(300, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // invalid proof
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::ClaimAirdrop {
+            stage: 1,
+            amount: Uint128::new(200),
+            proof: vec![ALICE_PROOF.into()],
+            id: "airdrop".into(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg);
+        match res {
+            Err(ContractError::VerificationFailed {}) => {}
+            _ => panic!("Must return VerificationFailed error"),
+        }
+
+        // claim creates lock
+        let msg = ExecuteMsg::ClaimAirdrop {
+            stage: 1,
+            amount: Uint128::new(100),
+            proof: vec![ALICE_PROOF.into()],
+            id: "airdrop".into(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        );
+        assert_eq!(0, res.unwrap().messages.len());
+
+        let query_msg = QueryMsg::Lock {
+            address: "alice".into(),
+            id: "airdrop".into(),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let value: LockInfo = from_binary(&res).unwrap();
+        assert_eq!(coins(100, "token"), value.native_balance);
+        assert_eq!(1000, value.expire.seconds());
+
+        // cannot claim twice
+        let res = execute(deps.as_mut(), env, mock_info("alice", &[]), msg);
+        match res {
+            Err(ContractError::AirdropClaimed {}) => {}
+            _ => panic!("Must return AirdropClaimed error"),
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Airdrop { stage: 1 }).unwrap();
+        let value: AirdropResponse = from_binary(&res).unwrap();
+        assert_eq!(Denom::Native("token".into()), value.denom);
+        assert_eq!(Uint128::new(200), value.remaining);
+
+        let msg = QueryMsg::AirdropClaimed {
+            stage: 1,
+            address: "alice".into(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: AirdropClaimedResponse = from_binary(&res).unwrap();
+        assert!(value.claimed);
+    }
+}
@@ -0,0 +1,211 @@
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, StdError, Uint128};
+use cw20::Denom;
+
+use crate::allowlist::check_recipient;
+use crate::balance::{denom_balance, GenericBalance};
+use crate::contract::{load_lock, load_releasable_lock, payout_data, release_funds};
+use crate::error::ContractError;
+use crate::events::{self, asset_attrs, lock_attrs};
+use crate::guardian::check_guardian;
+use crate::state::{locks, Lock, WithdrawAllowance, STATE};
+
+/// Sets how much of asset spender can withdraw once the lock expires, zero removes it.
+/// Joint owners and beneficiaries didn't agree to pay spenders, so their locks are refused
+pub fn try_set_withdraw_allowance(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    spender: String,
+    asset: Denom,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    let spender = deps.api.addr_validate(&spender)?;
+    if !amount.is_zero() {
+        check_allowable(&lock)?;
+        check_recipient(deps.as_ref(), &spender)?;
+    }
+    lock.allowances
+        .retain(|allowance| allowance.spender != spender || allowance.asset != asset);
+    if !amount.is_zero() {
+        lock.allowances.push(WithdrawAllowance {
+            spender: spender.clone(),
+            asset,
+            amount,
+        });
+    }
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_withdraw_allowance"),
+            attr("from", info.sender),
+            attr("id", id),
+            attr("spender", spender),
+            attr("amount", amount),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Sends amount of asset from an expired lock to the spender, decrementing its allowance.
+/// The lock is removed once empty
+pub fn try_withdraw_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+    asset: Denom,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let mut lock = load_releasable_lock(deps.storage, &env, &owner, id.to_owned())?;
+    check_guardian(&deps.querier, &owner, &id, &lock)?;
+    check_allowable(&lock)?;
+    check_recipient(deps.as_ref(), &info.sender)?;
+    let state = STATE.load(deps.storage)?;
+    if !lock.is_expired(env.block.time, state.inclusive_expire) {
+        return Err(ContractError::LockNotExpired {});
+    }
+    if matches!(&lock.swap, Some(swap) if swap.offer == asset) {
+        return Err(ContractError::SwapNotBatchable { id });
+    }
+
+    let allowance = lock
+        .allowances
+        .iter_mut()
+        .find(|allowance| allowance.spender == info.sender && allowance.asset == asset)
+        .ok_or(ContractError::AllowanceExceeded {
+            allowance: Uint128::zero(),
+        })?;
+    if amount.is_zero() || amount > allowance.amount {
+        return Err(ContractError::AllowanceExceeded {
+            allowance: allowance.amount,
+        });
+    }
+    allowance.amount = allowance
+        .amount
+        .checked_sub(amount)
+        .map_err(StdError::from)?;
+    lock.allowances
+        .retain(|allowance| !allowance.amount.is_zero());
+
+    let available = lock.funds.amount_of(&asset);
+    if amount > available {
+        return Err(ContractError::InsufficientLockBalance { available });
+    }
+    let funds = GenericBalance::from(denom_balance(&asset, amount));
+    lock.funds.sub_balance(&funds)?;
+    let mut attributes = lock_attrs(events::WITHDRAW_FROM, &owner, &id, lock.expire);
+    attributes.extend(asset_attrs(&funds));
+    attributes.push(attr("spender", &info.sender));
+
+    let release = release_funds(deps, &env, &owner, &id, &mut lock, funds, &info.sender)?
+        .send(&info.sender)?;
+
+    Ok(Response {
+        submessages: release.submessages,
+        messages: release.messages,
+        attributes,
+        data: Some(payout_data(&owner, id, lock.expire, release.payouts)?),
+    })
+}
+
+/// A beneficiary set after the allowance also blocks it
+fn check_allowable(lock: &Lock) -> Result<(), ContractError> {
+    if lock.joint.is_some() || lock.beneficiary.is_some() {
+        return Err(ContractError::AllowanceUnsupported {});
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, Addr, BankMsg, Timestamp};
+
+    #[test]
+    fn withdraw_allowance() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(10, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let token = Denom::Native("token".into());
+        let msg = ExecuteMsg::SetWithdrawAllowance {
+            id: "1".into(),
+            spender: "merchant".into(),
+            asset: token.clone(),
+            amount: Uint128::new(6),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+
+        let withdraw = |amount: u128| ExecuteMsg::WithdrawFrom {
+            owner: "anyone".into(),
+            id: "1".into(),
+            asset: token.clone(),
+            amount: Uint128::new(amount),
+        };
+        let merchant = mock_info("merchant", &[]);
+        let res = execute(deps.as_mut(), env.clone(), merchant.clone(), withdraw(4));
+        match res {
+            Err(ContractError::LockNotExpired {}) => {}
+            _ => panic!("Must return LockNotExpired error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(101);
+        let res = execute(deps.as_mut(), env.clone(), merchant.clone(), withdraw(4)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "merchant".into(),
+                amount: coins(4, "token"),
+            }
+            .into()]
+        );
+        let res = execute(deps.as_mut(), env.clone(), merchant.clone(), withdraw(3));
+        match res {
+            Err(ContractError::AllowanceExceeded { allowance }) => {
+                assert_eq!(Uint128::new(2), allowance)
+            }
+            _ => panic!("Must return AllowanceExceeded error"),
+        }
+
+        // the beneficiary is paid the rest of the lock
+        let msg = ExecuteMsg::SetBeneficiary {
+            id: "1".into(),
+            beneficiary: "heir".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+        let res = execute(deps.as_mut(), env, merchant, withdraw(2));
+        match res {
+            Err(ContractError::AllowanceUnsupported {}) => {}
+            _ => panic!("Must return AllowanceUnsupported error"),
+        }
+
+        let owner = Addr::unchecked("anyone");
+        let lock = locks().load(&deps.storage, (&owner, "1".into())).unwrap();
+        assert_eq!(coins(6, "token"), lock.funds.native);
+    }
+}
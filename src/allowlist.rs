@@ -0,0 +1,221 @@
+use cosmwasm_std::{
+    attr, Addr, ContractInfoResponse, Deps, DepsMut, Empty, MessageInfo, Order, QueryRequest,
+    Response, StdResult, WasmQuery,
+};
+
+use crate::error::ContractError;
+use crate::msg::RecipientAllowlistResponse;
+use crate::state::{RECIPIENT_ALLOWLIST, STATE};
+
+pub fn try_update_recipient_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for contract in add {
+        let addr = deps.api.addr_validate(&contract)?;
+        RECIPIENT_ALLOWLIST.save(deps.storage, &addr, &Empty {})?;
+    }
+    for contract in remove {
+        let addr = deps.api.addr_validate(&contract)?;
+        RECIPIENT_ALLOWLIST.remove(deps.storage, &addr);
+    }
+
+    Ok(Response {
+        attributes: vec![attr("action", "update_recipient_allowlist")],
+        ..Response::default()
+    })
+}
+
+/// Rejects contracts outside the allowlist when the deployment restricts contract recipients,
+/// wallets are always allowed
+pub fn check_recipient(deps: Deps, recipient: &Addr) -> Result<(), ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.restrict_contract_recipients
+        || RECIPIENT_ALLOWLIST
+            .may_load(deps.storage, recipient)?
+            .is_some()
+    {
+        return Ok(());
+    }
+
+    // Only contracts have a contract info
+    let request = QueryRequest::Wasm(WasmQuery::ContractInfo {
+        contract_addr: recipient.to_string(),
+    });
+    let info: StdResult<ContractInfoResponse> = deps.querier.query(&request);
+    if info.is_ok() {
+        return Err(ContractError::RecipientNotAllowed {
+            recipient: recipient.to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn query_recipient_allowlist(deps: Deps) -> StdResult<RecipientAllowlistResponse> {
+    let contracts: Result<Vec<_>, _> = RECIPIENT_ALLOWLIST
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(String::from_utf8)
+        .collect();
+
+    Ok(RecipientAllowlistResponse {
+        contracts: contracts?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::mock_querier::mock_dependencies_with_wasm;
+    use crate::msg::{DepositHookMsg, ExecuteMsg, InstantiateMsg, SwapMsg};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, Timestamp, Uint128};
+    use cw20::Denom;
+
+    #[test]
+    fn restricted_contract_recipients() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        deps.querier.register_contract("router");
+        deps.querier.register_contract("phishing");
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            restrict_contract_recipients: true,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let update = ExecuteMsg::UpdateRecipientAllowlist {
+            add: vec!["router".into()],
+            remove: vec![],
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, update).unwrap();
+        let value = query_recipient_allowlist(deps.as_ref()).unwrap();
+        assert_eq!(vec!["router".to_string()], value.contracts);
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let set = |beneficiary: &str| ExecuteMsg::SetBeneficiary {
+            id: "1".into(),
+            beneficiary: beneficiary.into(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), set("phishing"));
+        match res {
+            Err(ContractError::RecipientNotAllowed { recipient }) => {
+                assert_eq!("phishing", recipient)
+            }
+            _ => panic!("Must return RecipientNotAllowed error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), set("router")).unwrap();
+
+        let msg = ExecuteMsg::ProposeBeneficiaryChange {
+            owner: "anyone".into(),
+            id: "1".into(),
+            beneficiary: "wallet".into(),
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn restricted_payout_contracts() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        deps.querier.register_contract("phishing");
+        deps.querier.register_contract("vault");
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            restrict_contract_recipients: true,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let lock = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), lock.clone()).unwrap();
+
+        let msg = ExecuteMsg::SetSwap {
+            id: "1".into(),
+            swap: Some(SwapMsg {
+                router: "phishing".into(),
+                offer: Denom::Native("token".into()),
+                ask: Denom::Native("earth".into()),
+                min_receive: Uint128::new(1),
+            }),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::RecipientNotAllowed { recipient }) => {
+                assert_eq!("phishing", recipient)
+            }
+            _ => panic!("Must return RecipientNotAllowed error"),
+        }
+        let msg = ExecuteMsg::SetDepositHook {
+            id: "1".into(),
+            hook: Some(DepositHookMsg {
+                contract: "phishing".into(),
+                denom: Denom::Native("token".into()),
+                msg: r#"{"deposit":{"amount":"{{amount}}"}}"#.into(),
+            }),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg);
+        match res {
+            Err(ContractError::RecipientNotAllowed { recipient }) => {
+                assert_eq!("phishing", recipient)
+            }
+            _ => panic!("Must return RecipientNotAllowed error"),
+        }
+
+        // a contract owned lock is only unlocked for it once allowlisted
+        let vault = mock_info("vault", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), vault, lock).unwrap();
+        env.block.time = Timestamp::from_seconds(101);
+        let unlock_for = ExecuteMsg::UnlockFor {
+            owner: "vault".into(),
+            id: "1".into(),
+        };
+        let keeper = mock_info("keeper", &[]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            keeper.clone(),
+            unlock_for.clone(),
+        );
+        match res {
+            Err(ContractError::RecipientNotAllowed { recipient }) => {
+                assert_eq!("vault", recipient)
+            }
+            _ => panic!("Must return RecipientNotAllowed error"),
+        }
+        let update = ExecuteMsg::UpdateRecipientAllowlist {
+            add: vec!["vault".into()],
+            remove: vec![],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), owner, update).unwrap();
+        let _res = execute(deps.as_mut(), env, keeper, unlock_for).unwrap();
+    }
+}
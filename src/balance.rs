@@ -1,8 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Coin;
-use cw20::{Balance, Cw20CoinVerified};
+use cosmwasm_std::{Coin, StdError, StdResult, Uint128};
+use cw20::{Balance, Cw20CoinVerified, Denom};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
 pub struct GenericBalance {
@@ -43,6 +43,89 @@ impl GenericBalance {
             }
         };
     }
+
+    /// Merges add into the balance, one entry per denom and token
+    pub fn add_balance(&mut self, add: GenericBalance) {
+        self.add_tokens(Balance::from(add.native));
+        for token in add.cw20 {
+            self.add_tokens(Balance::Cw20(token));
+        }
+    }
+
+    /// Returns the amount of denom in the balance
+    pub fn amount_of(&self, denom: &Denom) -> Uint128 {
+        match denom {
+            Denom::Native(denom) => self
+                .native
+                .iter()
+                .find(|exist| exist.denom == *denom)
+                .map(|coin| coin.amount),
+            Denom::Cw20(address) => self
+                .cw20
+                .iter()
+                .find(|exist| exist.address == *address)
+                .map(|token| token.amount),
+        }
+        .unwrap_or_default()
+    }
+
+    /// Same amount of every denom and token, regardless of order
+    pub fn same_amounts(&self, other: &GenericBalance) -> bool {
+        self.native.len() == other.native.len()
+            && self.cw20.len() == other.cw20.len()
+            && self
+                .native
+                .iter()
+                .all(|coin| other.amount_of(&Denom::Native(coin.denom.clone())) == coin.amount)
+            && self
+                .cw20
+                .iter()
+                .all(|token| other.amount_of(&Denom::Cw20(token.address.clone())) == token.amount)
+    }
+
+    /// Removes denom from the balance, returning its amount if present
+    pub fn remove_denom(&mut self, denom: &Denom) -> Option<Uint128> {
+        match denom {
+            Denom::Native(denom) => {
+                let idx = self.native.iter().position(|exist| exist.denom == *denom)?;
+                Some(self.native.remove(idx).amount)
+            }
+            Denom::Cw20(address) => {
+                let idx = self
+                    .cw20
+                    .iter()
+                    .position(|exist| exist.address == *address)?;
+                Some(self.cw20.remove(idx).amount)
+            }
+        }
+    }
+
+    /// Subtracts every token in sub, removing entries that reach zero
+    pub fn sub_balance(&mut self, sub: &GenericBalance) -> StdResult<()> {
+        for token in &sub.native {
+            let idx = self
+                .native
+                .iter()
+                .position(|exist| exist.denom == token.denom)
+                .ok_or_else(|| StdError::generic_err(format!("No {} balance", token.denom)))?;
+            self.native[idx].amount = self.native[idx].amount.checked_sub(token.amount)?;
+            if self.native[idx].amount.is_zero() {
+                self.native.remove(idx);
+            }
+        }
+        for token in &sub.cw20 {
+            let idx = self
+                .cw20
+                .iter()
+                .position(|exist| exist.address == token.address)
+                .ok_or_else(|| StdError::generic_err(format!("No {} balance", token.address)))?;
+            self.cw20[idx].amount = self.cw20[idx].amount.checked_sub(token.amount)?;
+            if self.cw20[idx].amount.is_zero() {
+                self.cw20.remove(idx);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl From<Balance> for GenericBalance {
@@ -59,3 +142,28 @@ impl From<Balance> for GenericBalance {
         }
     }
 }
+
+/// Returns the denom and amount of a balance holding exactly one token
+pub fn single_token(balance: &Balance) -> Option<(Denom, Uint128)> {
+    match balance {
+        Balance::Native(native) => match native.0.as_slice() {
+            [coin] => Some((Denom::Native(coin.denom.clone()), coin.amount)),
+            _ => None,
+        },
+        Balance::Cw20(token) => Some((Denom::Cw20(token.address.clone()), token.amount)),
+    }
+}
+
+/// Builds a balance of amount tokens of denom
+pub fn denom_balance(denom: &Denom, amount: Uint128) -> Balance {
+    match denom {
+        Denom::Native(denom) => Balance::from(vec![Coin {
+            denom: denom.clone(),
+            amount,
+        }]),
+        Denom::Cw20(address) => Balance::Cw20(Cw20CoinVerified {
+            address: address.clone(),
+            amount,
+        }),
+    }
+}
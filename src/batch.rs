@@ -0,0 +1,165 @@
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+use crate::contract::dispatch;
+use crate::error::ContractError;
+use crate::msg::ExecuteMsg;
+use crate::swap::SWAP_REPLY_ID;
+
+/// Executes msgs in order merging their responses, any error reverts the whole batch.
+/// Attached funds are passed to the only msg taking funds
+pub fn try_batch(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<ExecuteMsg>,
+) -> Result<Response, ContractError> {
+    let nested = msgs
+        .iter()
+        .any(|msg| matches!(msg, ExecuteMsg::Batch { .. } | ExecuteMsg::Receive(_)));
+    if msgs.is_empty() || nested {
+        return Err(ContractError::InvalidBatchMsg {});
+    }
+
+    if !info.funds.is_empty() && msgs.iter().filter(|msg| takes_funds(msg)).count() != 1 {
+        return Err(ContractError::AmbiguousBatchFunds {});
+    }
+
+    let count = msgs.len();
+    let mut funds = info.funds;
+    let mut res = Response::default();
+    for msg in msgs {
+        let info = MessageInfo {
+            sender: info.sender.clone(),
+            funds: if takes_funds(&msg) {
+                std::mem::take(&mut funds)
+            } else {
+                vec![]
+            },
+        };
+        let mut sub_res = dispatch(deps.branch(), env.clone(), info, msg)?;
+        res.submessages.append(&mut sub_res.submessages);
+        res.messages.append(&mut sub_res.messages);
+        res.attributes.append(&mut sub_res.attributes);
+        res.data = sub_res.data.or(res.data);
+    }
+    // a single swap can be pending its reply
+    let swaps = res.submessages.iter().filter(|sub| sub.id == SWAP_REPLY_ID);
+    if swaps.count() > 1 {
+        return Err(ContractError::TooManyBatchSwaps {});
+    }
+    res.attributes.push(attr("action", "batch"));
+    res.attributes.push(attr("count", count));
+
+    Ok(res)
+}
+
+/// Whether dispatch deposits the attached funds for msg
+fn takes_funds(msg: &ExecuteMsg) -> bool {
+    matches!(
+        msg,
+        ExecuteMsg::Lock { .. }
+            | ExecuteMsg::LockDerived { .. }
+            | ExecuteMsg::LockFor { .. }
+            | ExecuteMsg::IncreaseLock { .. }
+            | ExecuteMsg::LockJoint { .. }
+            | ExecuteMsg::RegisterAirdrop { .. }
+            | ExecuteMsg::CreateStream { .. }
+            | ExecuteMsg::TopUpStream { .. }
+            | ExecuteMsg::CreateVestingGroup { .. }
+            | ExecuteMsg::CreateExtensionCampaign { .. }
+            | ExecuteMsg::BuyLock { .. }
+            | ExecuteMsg::RemoteLock { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{InstantiateMsg, LockData, LockInfoV2, QueryMsg, QueryMsgV2};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, Timestamp};
+
+    #[test]
+    fn batch() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let lock = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let set_beneficiary = ExecuteMsg::SetBeneficiary {
+            id: "1".into(),
+            beneficiary: "heir".into(),
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+
+        let msg = ExecuteMsg::Batch {
+            msgs: vec![
+                lock.clone(),
+                ExecuteMsg::Batch {
+                    msgs: vec![set_beneficiary.clone()],
+                },
+            ],
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::InvalidBatchMsg {}) => {}
+            _ => panic!("Must return InvalidBatchMsg error"),
+        }
+
+        // funds can't be split between msgs taking them
+        let increase = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        let msg = ExecuteMsg::Batch {
+            msgs: vec![lock.clone(), increase.clone()],
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::AmbiguousBatchFunds {}) => {}
+            _ => panic!("Must return AmbiguousBatchFunds error"),
+        }
+
+        let msg = ExecuteMsg::Batch {
+            msgs: vec![lock, set_beneficiary],
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let data: LockData = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!("1", data.id);
+
+        // the funds go to the msg taking them even if it isn't the first
+        let set_note = ExecuteMsg::SetNote {
+            id: "1".into(),
+            note: None,
+        };
+        let msg = ExecuteMsg::Batch {
+            msgs: vec![set_note, increase],
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let msg = QueryMsg::V2(QueryMsgV2::Lock {
+            address: "anyone".into(),
+            id: "1".into(),
+            viewing_key: None,
+            include_config: false,
+            include_token_info: false,
+        });
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LockInfoV2 = from_binary(&res).unwrap();
+        assert_eq!(Some("heir".into()), value.beneficiary);
+        assert_eq!(coins(4, "token"), value.native_balance);
+    }
+}
@@ -0,0 +1,221 @@
+use cosmwasm_std::{attr, Addr, DepsMut, MessageInfo, Response};
+
+use crate::allowlist::check_recipient;
+use crate::contract::load_lock;
+use crate::error::ContractError;
+use crate::state::{locks, BeneficiaryProposal};
+
+/// Sets the payout address of a lock, once set it can only be rotated with co-approval
+pub fn try_set_beneficiary(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    beneficiary: String,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    if lock.beneficiary.is_some() {
+        return Err(ContractError::BeneficiaryAlreadySet {});
+    }
+    if lock.deposit_hook.is_some() || lock.swap.is_some() {
+        return Err(ContractError::BeneficiaryPayout {});
+    }
+    let beneficiary = deps.api.addr_validate(&beneficiary)?;
+    check_recipient(deps.as_ref(), &beneficiary)?;
+    lock.beneficiary = Some(beneficiary.clone());
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_beneficiary"),
+            attr("from", info.sender),
+            attr("id", id),
+            attr("beneficiary", beneficiary),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Proposes a new beneficiary, callable by the lock owner or the current beneficiary
+pub fn try_propose_beneficiary_change(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+    beneficiary: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let key = (&owner, id.to_owned());
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+
+    let current = lock
+        .beneficiary
+        .as_ref()
+        .ok_or(ContractError::NoBeneficiary {})?;
+    if info.sender != owner && info.sender != *current {
+        return Err(ContractError::LockUnauthorized { id });
+    }
+
+    let beneficiary = deps.api.addr_validate(&beneficiary)?;
+    check_recipient(deps.as_ref(), &beneficiary)?;
+    lock.beneficiary_proposal = Some(BeneficiaryProposal {
+        beneficiary: beneficiary.clone(),
+        proposer: info.sender.clone(),
+    });
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "propose_beneficiary_change"),
+            attr("from", info.sender),
+            attr("id", id),
+            attr("beneficiary", beneficiary),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Accepts the proposed beneficiary, callable by the party that did not propose it
+pub fn try_accept_beneficiary_change(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let key = (&owner, id.to_owned());
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+
+    let proposal = lock
+        .beneficiary_proposal
+        .take()
+        .ok_or(ContractError::NoBeneficiaryProposal {})?;
+    let current = lock
+        .beneficiary
+        .as_ref()
+        .ok_or(ContractError::NoBeneficiary {})?;
+    let counterparty: &Addr = if proposal.proposer == owner {
+        current
+    } else {
+        &owner
+    };
+    if info.sender != *counterparty {
+        return Err(ContractError::LockUnauthorized { id });
+    }
+
+    lock.beneficiary = Some(proposal.beneficiary.clone());
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "accept_beneficiary_change"),
+            attr("from", info.sender),
+            attr("id", id),
+            attr("beneficiary", proposal.beneficiary),
+        ],
+        ..Response::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contract::{execute, instantiate};
+    use crate::error::ContractError;
+    use crate::msg::{ExecuteMsg, InstantiateMsg, SwapMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, BankMsg, CosmosMsg, Timestamp, Uint128};
+    use cw20::Denom;
+
+    #[test]
+    fn beneficiary_change() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("employer", &coins(2, "token")),
+            msg,
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::SetBeneficiary {
+            id: "1".into(),
+            beneficiary: "employee".into(),
+        };
+        let info = mock_info("employer", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+        // no unilateral change
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::BeneficiaryAlreadySet {}) => {}
+            _ => panic!("Must return BeneficiaryAlreadySet error"),
+        }
+        // nor a router receiving the payout
+        let msg = ExecuteMsg::SetSwap {
+            id: "1".into(),
+            swap: Some(SwapMsg {
+                router: "router".into(),
+                offer: Denom::Native("token".into()),
+                ask: Denom::Native("earth".into()),
+                min_receive: Uint128::new(1),
+            }),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::BeneficiaryPayout {}) => {}
+            _ => panic!("Must return BeneficiaryPayout error"),
+        }
+
+        let msg = ExecuteMsg::ProposeBeneficiaryChange {
+            owner: "employer".into(),
+            id: "1".into(),
+            beneficiary: "new_wallet".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("employee", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::AcceptBeneficiaryChange {
+            owner: "employer".into(),
+            id: "1".into(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("employee", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::LockUnauthorized { id }) => assert_eq!("1", id),
+            _ => panic!("Must return LockUnauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // funds are paid to the beneficiary
+        env.block.time = Timestamp::from_seconds(401);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "new_wallet".into(),
+                amount: coins(2, "token"),
+            })]
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use cosmwasm_std::{
+    attr, to_binary, Addr, ContractResult, DepsMut, MessageInfo, ReplyOn, Response, StdResult,
+    SubMsg, SubcallResponse, WasmMsg,
+};
+
+use crate::contract::load_lock;
+use crate::error::ContractError;
+use crate::msg::{LockCallbackMsg, UnlockCallbackMsg};
+use crate::state::{locks, UnlockCallback};
+
+pub const CALLBACK_REPLY_ID: u64 = 2;
+/// Max callback msg size in bytes, it is loaded with the lock on every access
+pub const MAX_CALLBACK_MSG_SIZE: usize = 1024;
+/// Gas available to the callback, so it can't run the unlock out of gas
+pub const CALLBACK_GAS_LIMIT: u64 = 200_000;
+
+pub fn try_set_unlock_callback(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    callback: Option<UnlockCallbackMsg>,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    lock.unlock_callback = match callback {
+        Some(callback) => {
+            if callback.msg.len() > MAX_CALLBACK_MSG_SIZE {
+                return Err(ContractError::CallbackTooLarge {
+                    max: MAX_CALLBACK_MSG_SIZE,
+                });
+            }
+            Some(UnlockCallback {
+                contract: deps.api.addr_validate(&callback.contract)?,
+                msg: callback.msg,
+            })
+        }
+        None => None,
+    };
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_unlock_callback"),
+            attr("from", info.sender),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Notifies the callback contract of the unlock, replying only on error so a failing
+/// callback is reverted alone. Submessages run before the unlocked tokens are sent
+pub fn callback_submsg(owner: &Addr, id: &str, callback: &UnlockCallback) -> StdResult<SubMsg> {
+    let msg = LockCallbackMsg::LockUnlocked {
+        owner: owner.into(),
+        id: id.into(),
+        msg: callback.msg.clone(),
+    };
+
+    Ok(SubMsg {
+        id: CALLBACK_REPLY_ID,
+        msg: WasmMsg::Execute {
+            contract_addr: callback.contract.to_string(),
+            msg: to_binary(&msg)?,
+            send: vec![],
+        }
+        .into(),
+        gas_limit: Some(CALLBACK_GAS_LIMIT),
+        reply_on: ReplyOn::Error,
+    })
+}
+
+pub fn reply_callback(result: ContractResult<SubcallResponse>) -> Response {
+    let error = match result {
+        ContractResult::Ok(_) => return Response::default(),
+        ContractResult::Err(err) => err,
+    };
+
+    Response {
+        attributes: vec![
+            attr("action", "unlock_callback_failed"),
+            attr("error", error),
+        ],
+        ..Response::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, reply};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, BankMsg, Binary, Reply, Timestamp};
+
+    #[test]
+    fn unlock_callback() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let set_callback = |msg: Binary| ExecuteMsg::SetUnlockCallback {
+            id: "1".into(),
+            callback: Some(UnlockCallbackMsg {
+                contract: "airdrop".into(),
+                msg,
+            }),
+        };
+        let info = mock_info("anyone", &[]);
+        let msg = set_callback(Binary(vec![0; MAX_CALLBACK_MSG_SIZE + 1]));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::CallbackTooLarge { max }) => assert_eq!(MAX_CALLBACK_MSG_SIZE, max),
+            _ => panic!("Must return CallbackTooLarge error"),
+        }
+        let msg = set_callback(Binary(b"claim".to_vec()));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(101);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            vec![SubMsg {
+                id: CALLBACK_REPLY_ID,
+                msg: WasmMsg::Execute {
+                    contract_addr: "airdrop".into(),
+                    msg: to_binary(&LockCallbackMsg::LockUnlocked {
+                        owner: "anyone".into(),
+                        id: "1".into(),
+                        msg: Binary(b"claim".to_vec()),
+                    })
+                    .unwrap(),
+                    send: vec![],
+                }
+                .into(),
+                gas_limit: Some(CALLBACK_GAS_LIMIT),
+                reply_on: ReplyOn::Error,
+            }],
+            res.submessages
+        );
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(2, "token"),
+            }
+            .into()]
+        );
+
+        // failure is recorded without reverting the unlock
+        let msg = Reply {
+            id: CALLBACK_REPLY_ID,
+            result: ContractResult::Err("airdrop ended".into()),
+        };
+        let res = reply(deps.as_mut(), env, msg).unwrap();
+        assert_eq!(
+            vec![
+                attr("action", "unlock_callback_failed"),
+                attr("error", "airdrop ended"),
+            ],
+            res.attributes
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use cosmwasm_std::{attr, coins, DepsMut, Env, MessageInfo, Response};
+use cw20::{Balance, Cw20CoinVerified, Denom};
+
+use crate::balance::GenericBalance;
+use crate::contract::{load_lock, load_releasable_lock, payout_data, release_funds};
+use crate::error::ContractError;
+use crate::events::{self, lock_attrs};
+use crate::guardian::check_guardian;
+use crate::state::{locks, Lock, STATE};
+
+/// Releases a single asset of a releasable lock, the lock is removed once empty
+pub fn try_claim_asset(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    asset: Denom,
+) -> Result<Response, ContractError> {
+    let owner = &info.sender;
+    let mut lock = load_releasable_lock(deps.storage, &env, owner, id.to_owned())?;
+    check_guardian(&deps.querier, owner, &id, &lock)?;
+
+    // claiming the goal asset would lock the rest again until expire
+    let state = STATE.load(deps.storage)?;
+    if lock.goal.is_some() && !lock.is_expired(env.block.time, state.inclusive_expire) {
+        return Err(ContractError::LockNotExpired {});
+    }
+    if matches!(&lock.swap, Some(swap) if swap.offer == asset) {
+        return Err(ContractError::SwapNotBatchable { id });
+    }
+
+    let amount = lock
+        .funds
+        .remove_denom(&asset)
+        .ok_or(ContractError::AssetNotLocked {})?;
+    let mut funds = GenericBalance::default();
+    funds.add_tokens(match &asset {
+        Denom::Native(denom) => Balance::from(coins(amount.u128(), denom)),
+        Denom::Cw20(address) => Balance::Cw20(Cw20CoinVerified {
+            address: address.clone(),
+            amount,
+        }),
+    });
+    let recipient = lock.recipient(owner).clone();
+    let release =
+        release_funds(deps, &env, owner, &id, &mut lock, funds, &recipient)?.send(&recipient)?;
+
+    let denom = match asset {
+        Denom::Native(denom) => denom,
+        Denom::Cw20(address) => address.into(),
+    };
+    let mut attributes = lock_attrs(events::CLAIM_ASSET, owner, &id, lock.expire);
+    attributes.push(attr(events::ASSET, denom));
+    attributes.push(attr(events::AMOUNT, amount));
+    Ok(Response {
+        submessages: release.submessages,
+        messages: release.messages,
+        attributes,
+        data: Some(payout_data(owner, id, lock.expire, release.payouts)?),
+    })
+}
+
+/// Releases the first locked asset by the per call order, falling back to the lock order
+/// and then to the lock funds order
+pub fn try_claim_next(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    order: Option<Vec<Denom>>,
+) -> Result<Response, ContractError> {
+    let lock = load_lock(deps.storage, &info.sender, &id)?;
+    let asset = next_asset(&lock, order.as_ref().unwrap_or(&lock.asset_order))
+        .ok_or(ContractError::AssetNotLocked {})?;
+
+    try_claim_asset(deps, env, info, id, asset)
+}
+
+/// Sets the order try_claim_next releases the lock assets, unlisted assets go last
+pub fn try_set_asset_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    order: Vec<Denom>,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    lock.asset_order = order;
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_asset_order"),
+            attr("from", info.sender),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+fn next_asset(lock: &Lock, order: &[Denom]) -> Option<Denom> {
+    let native = lock
+        .funds
+        .native
+        .iter()
+        .map(|coin| Denom::Native(coin.denom.clone()));
+    let cw20 = lock
+        .funds
+        .cw20
+        .iter()
+        .map(|coin| Denom::Cw20(coin.address.clone()));
+    // the swap offer is only released by unlock
+    let claimable = |asset: &Denom| {
+        !lock.funds.amount_of(asset).is_zero()
+            && !matches!(&lock.swap, Some(swap) if swap.offer == *asset)
+    };
+
+    order
+        .iter()
+        .cloned()
+        .chain(native.chain(cw20))
+        .find(claimable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        ExecuteMsg, InstantiateMsg, ListingsResponse, LockInfo, QueryMsg, ReceiveMsg,
+        UnlockCallbackMsg,
+    };
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{
+        from_binary, to_binary, Addr, BankMsg, Binary, CosmosMsg, StdError, Timestamp, Uint128,
+    };
+    use cw20::Cw20ReceiveMsg;
+
+    #[test]
+    fn claim_asset() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "anyone".into(),
+            amount: Uint128(5),
+            msg: to_binary(&ReceiveMsg::IncreaseLock {
+                id: "1".into(),
+                min_remaining: None,
+            })
+            .unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("cw20", &[]), msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let claim = |denom: Denom| ExecuteMsg::ClaimAsset {
+            id: "1".into(),
+            asset: denom,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            claim(Denom::Native("token".into())),
+        );
+        match res {
+            Err(ContractError::LockNotExpired {}) => {}
+            _ => panic!("Must return LockNotExpired error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(101);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            claim(Denom::Native("other".into())),
+        );
+        match res {
+            Err(ContractError::AssetNotLocked {}) => {}
+            _ => panic!("Must return AssetNotLocked error"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            claim(Denom::Native("token".into())),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(2, "token"),
+            }
+            .into()]
+        );
+
+        // the cw20 balance is still locked
+        let msg = QueryMsg::Lock {
+            address: "anyone".into(),
+            id: "1".into(),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg.clone()).unwrap();
+        let value: LockInfo = from_binary(&res).unwrap();
+        assert!(value.native_balance.is_empty());
+        assert_eq!(1, value.cw20_balance.len());
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            info,
+            claim(Denom::Cw20(Addr::unchecked("cw20"))),
+        )
+        .unwrap();
+        assert_eq!(1, res.messages.len());
+        match query(deps.as_ref(), mock_env(), msg) {
+            Err(StdError::NotFound { .. }) => {}
+            _ => panic!("Must return StdError::NotFound error"),
+        }
+    }
+
+    #[test]
+    fn claim_next_asset() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let mut funds = coins(2, "gov");
+        funds.extend(coins(3, "stable"));
+        funds.extend(coins(4, "token"));
+        let info = mock_info("anyone", &funds);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetAssetOrder {
+            id: "1".into(),
+            order: vec![
+                Denom::Native("stable".into()),
+                Denom::Native("token".into()),
+            ],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(101);
+        let claim = |order: Option<Vec<Denom>>| ExecuteMsg::ClaimNext {
+            id: "1".into(),
+            order,
+        };
+        let sent = |res: Response| match &res.messages[0] {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. }) => amount.clone(),
+            _ => panic!("Must send native coins"),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), claim(None)).unwrap();
+        assert_eq!(coins(3, "stable"), sent(res));
+
+        // the per call order takes precedence
+        let order = Some(vec![Denom::Native("gov".into())]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), claim(order)).unwrap();
+        assert_eq!(coins(2, "gov"), sent(res));
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), claim(None)).unwrap();
+        assert_eq!(coins(4, "token"), sent(res));
+
+        let res = execute(deps.as_mut(), env, info, claim(None));
+        match res {
+            Err(ContractError::LockNotFound { .. }) => {}
+            _ => panic!("Must return LockNotFound error"),
+        }
+    }
+
+    #[test]
+    fn claim_last_asset() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::ListLock {
+            id: "1".into(),
+            asset: Denom::Native("stable".into()),
+            price: Uint128(10),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetUnlockCallback {
+            id: "1".into(),
+            callback: Some(UnlockCallbackMsg {
+                contract: "airdrop".into(),
+                msg: Binary::default(),
+            }),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // emptying the lock closes it like an unlock
+        env.block.time = Timestamp::from_seconds(101);
+        let msg = ExecuteMsg::ClaimAsset {
+            id: "1".into(),
+            asset: Denom::Native("token".into()),
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(1, res.submessages.len());
+
+        let msg = QueryMsg::Listings {
+            owner: "anyone".into(),
+            start_after: None,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ListingsResponse = from_binary(&res).unwrap();
+        assert!(value.listings.is_empty());
+    }
+}
@@ -1,23 +1,146 @@
 use cosmwasm_std::{
-    attr, entry_point, from_binary, to_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut,
-    Env, MessageInfo, Order, Response, StdResult, Timestamp, WasmMsg,
+    attr, entry_point, from_binary, to_binary, Addr, Attribute, BankMsg, Binary, CosmosMsg, Deps,
+    DepsMut, Empty, Env, MessageInfo, Order, Reply, Response, StdResult, Storage, SubMsg,
+    Timestamp, Uint128, WasmMsg,
 };
 
+use crate::airdrop::{
+    query_airdrop, query_airdrop_claimed, try_claim_airdrop, try_register_airdrop,
+};
+use crate::allowance::{try_set_withdraw_allowance, try_withdraw_from};
+use crate::allowlist::{
+    check_recipient, query_recipient_allowlist, try_update_recipient_allowlist,
+};
 use crate::balance::GenericBalance;
+use crate::batch::try_batch;
+use crate::beneficiary::{
+    try_accept_beneficiary_change, try_propose_beneficiary_change, try_set_beneficiary,
+};
+use crate::callback::{
+    callback_submsg, reply_callback, try_set_unlock_callback, CALLBACK_REPLY_ID,
+};
+use crate::claim::{try_claim_asset, try_claim_next, try_set_asset_order};
+use crate::emergency::{
+    query_emergency_withdrawal, try_announce_emergency_withdraw, try_cancel_emergency_withdraw,
+    try_emergency_withdraw,
+};
 use crate::error::ContractError;
-use crate::msg::{AllLocksResponse, ExecuteMsg, InstantiateMsg, LockInfo, QueryMsg, ReceiveMsg};
-use crate::state::{Lock, State, LOCKS, STATE};
+use crate::escheat::{
+    query_escheat_config, try_escheat, try_update_escheat_config, try_warn_abandoned,
+};
+use crate::events::{self, asset_attrs, lock_attrs};
+use crate::excess::{refund_msg, split_excess};
+use crate::expiring::query_locks_expiring_before;
+use crate::extension::{
+    query_extension_campaign, try_accept_extension, try_claim_extension_bonus,
+    try_close_extension_campaign, try_create_extension_campaign,
+};
+use crate::fee_share::{
+    query_fee_share, sync_lp_rewards, try_claim_lp_rewards, try_credit_lp_rewards,
+    try_register_fee_share,
+};
+use crate::freeze::{
+    check_deposit, check_release, query_frozen_tokens, try_freeze_token, try_unfreeze_token,
+};
+use crate::gating::query_balance;
+use crate::goal::{to_goal_info, try_set_goal};
+use crate::guardian::{check_guardian, try_set_guardian};
+use crate::hardship::{try_approve_hardship_unlock, try_request_hardship_unlock};
+use crate::history::{query_activity_report, query_deposits, record_activity, record_deposit};
+use crate::hook::{deposit_msg, try_set_deposit_hook};
+use crate::interchain::{
+    query_controller, try_map_controller, try_remote_lock, try_unmap_controller,
+};
+use crate::joint::{try_approve_unlock, try_lock_joint};
+use crate::legacy::{query_legacy_lockers, try_migrate_in, try_update_legacy_lockers};
+use crate::lending::{
+    check_unencumbered, query_lenders, try_approve_lender, try_encumber, try_release_encumbrance,
+    try_seize_lock, try_update_lenders,
+};
+use crate::lock_id::try_lock_derived;
+use crate::lock_params::query_validate_lock_params;
+use crate::market::{query_listings, try_buy_lock, try_delist_lock, try_list_lock};
+use crate::metadata::{
+    coins_metadata, tokens_metadata, try_cache_token_info, try_set_denom_metadata,
+};
+use crate::migration::{
+    convert_submsgs, query_token_migrations, reply_convert, try_register_token_migration,
+    try_remove_token_migration, CONVERT_REPLY_ID,
+};
+use crate::msg::{
+    ActiveLocksResponse, AllLocksResponse, AllowanceInfo, BeneficiaryProposalInfo,
+    CanUnlockResponse, ConfigResponse, DepositHookMsg, ExecuteMsg, InstantiateMsg, JointInfo,
+    LockConfig, LockCountResponse, LockData, LockInfo, LockInfoV2, LockedAtHeightResponse,
+    LockersResponse, MigrateMsg, OwnerInfo, OwnersResponse, PayoutInfo, PlanInfo, QueryMsg,
+    QueryMsgV2, RateLimit, ReceiveMsg, ReleasePreviewResponse, SwapMsg, UnlockCallbackMsg,
+    VestingResponse,
+};
+use crate::multi_denom::{check_denom_policy, try_lock_denoms};
+use crate::namespace::{check_id_prefix, query_id_prefix, try_reserve_id_prefix};
+use crate::note::try_set_note;
+use crate::notify::{
+    created_packet, notify_msgs, query_notify_channel, released_packet, try_set_notify_channel,
+};
+use crate::ownership::{query_ownership_challenge, query_verify_ownership, try_set_ownership_key};
+use crate::params::{load_max_lock_time, query_param_registry, try_update_param_registry};
+use crate::pause::{check_not_paused, query_pause, try_pause, try_unpause};
+use crate::plan::{try_crank, try_set_plan};
+use crate::privacy::{
+    can_view, has_private_locks, is_viewing_key, try_set_private, try_set_viewing_key,
+};
+use crate::referral::{query_referrer, try_lock_referred};
+use crate::relayer::{query_relayers, try_authorize_relayer, try_relay_unlock, try_revoke_relayer};
+use crate::reminder::{query_upcoming_expirations, try_set_reminder};
+use crate::roles::{query_roles, try_grant_role, try_revoke_role};
+use crate::rollover::{
+    query_successor, try_process_rollover, try_set_rollover, try_update_successor,
+};
+use crate::safety::{
+    check_block_time, enter_execution, exit_execution, query_execution_guard, query_time_high_water,
+};
+use crate::schedule::query_unlock_schedule;
+use crate::simulate::query_simulate;
+use crate::single_asset::{check_single_asset, try_set_single_asset};
+use crate::solvency::{query_solvency, try_assert_invariants};
+use crate::state::{
+    locks, rebuild_lock_indexes, start_index_rebuild, ActivityKind, Lock, Profile, RateWindow,
+    State, ACTIVE_LOCKS, CAP_BYPASS, DEFAULT_MAX_ASSETS, INDEX_REBUILD, LISTINGS, LOCKED_SUM,
+    LOCKED_TOTALS, LOCKERS, LOCK_COUNTS, LOCK_RATE, PROFILE, STATE,
+};
+use crate::stream::{
+    query_stream, try_accept_stream_rate, try_cancel_stream, try_change_stream_rate,
+    try_create_stream, try_top_up_stream, try_withdraw_stream,
+};
+use crate::swap::{reply_swap, swap_submsg, try_set_swap, SWAP_REPLY_ID};
+use crate::vesting_group::{
+    query_vesting_group, query_vesting_schedule, try_add_vesting_schedule, try_claim_vesting,
+    try_clawback_vesting, try_create_vesting_group, try_withdraw_vesting_pool,
+};
+use crate::yield_adapter::{
+    clear_yield_flows, deposit_msgs, query_yield_adapter, try_harvest_yield,
+    try_register_yield_adapter, try_update_yield_config, withdraw_msgs,
+};
 
-use cw2::set_contract_version;
-use cw20::{Balance, Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Balance, Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg, Denom};
+use cw_storage_plus::Bound;
+
+// max page size of AllLocks when a limit is set, and of Owners
+const MAX_LIMIT: u32 = 30;
+// page size of Owners when no limit is set
+const DEFAULT_LIMIT: u32 = 10;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw-lockbox";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// first version storing the lock indexes, older deployments rebuild them on migrate
+const INDEXES_VERSION: &str = "0.3.0";
+// locks indexed by migrate, and by RebuildLockIndexes when no limit is set
+const REBUILD_LIMIT: u32 = 100;
 
 // Note, you can use StdResult in some functions where you do not
 // make use of the custom errors
-#[entry_point]
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
@@ -29,37 +152,441 @@ pub fn instantiate(
     let state = State {
         max_lock_time: msg.max_lock_time,
         owner: info.sender,
+        max_native_coins: msg.max_native_coins.unwrap_or(DEFAULT_MAX_ASSETS),
+        max_cw20_tokens: msg.max_cw20_tokens.unwrap_or(DEFAULT_MAX_ASSETS),
+        grace_period: msg.grace_period.unwrap_or_default(),
+        rate_limit: msg.rate_limit.filter(|limit| limit.window > 0),
+        max_active_locks: msg.max_active_locks.unwrap_or_default(),
+        default_lock_time: msg.default_lock_time.unwrap_or_default(),
+        min_increase: msg.min_increase.unwrap_or_default(),
+        emergency_delay: msg.emergency_delay.filter(|_| !msg.immutable),
+        immutable: msg.immutable,
+        restrict_contract_recipients: msg.restrict_contract_recipients,
+        gating_token: msg
+            .gating_token
+            .map(|token| deps.api.addr_validate(&token))
+            .transpose()?,
+        single_asset_mode: msg.single_asset_mode,
+        inclusive_expire: msg.inclusive_expire,
+        multi_denom_policy: msg.multi_denom_policy,
     };
     STATE.save(deps.storage, &state)?;
 
     Ok(Response::default())
 }
 
+/// Indexes the locks stored before they were kept in an IndexedMap
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage).map(|info| info.version);
+    if version_before(&stored.unwrap_or_default(), INDEXES_VERSION) {
+        start_index_rebuild(deps.storage)?;
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let count = rebuild_lock_indexes(deps.storage, env.block.height, REBUILD_LIMIT as usize)?;
+    if let Some(inclusive_expire) = msg.inclusive_expire {
+        STATE.update(deps.storage, |mut state| -> StdResult<_> {
+            state.inclusive_expire = inclusive_expire;
+            Ok(state)
+        })?;
+    }
+    if let Some(policy) = msg.multi_denom_policy {
+        STATE.update(deps.storage, |mut state| -> StdResult<_> {
+            state.multi_denom_policy = policy;
+            Ok(state)
+        })?;
+    }
+
+    Ok(Response {
+        attributes: vec![attr("action", "migrate"), attr("locks", count)],
+        ..Response::default()
+    })
+}
+
+/// Compares dotted numeric versions, such as 0.2.0 before 0.10.0
+fn version_before(version: &str, other: &str) -> bool {
+    let parse = |version: &str| -> Vec<u64> {
+        version
+            .split('.')
+            .map(|part| part.parse().unwrap_or_default())
+            .collect()
+    };
+    parse(version) < parse(other)
+}
+
 // And declare a custom Error variant for the ones where you will want to make use of it
-#[entry_point]
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    enter_execution(deps.storage)?;
+    // shifted once here, Batch passes this env on to dispatch
+    #[cfg(feature = "testing")]
+    let env = crate::time_travel::shifted_env(deps.storage, env)?;
+    let res = dispatch(deps.branch(), env, info, msg)?;
+    clear_yield_flows(deps.storage)?;
+    exit_execution(deps.storage);
+
+    Ok(res)
+}
+
+/// Runs msg inside the execution guard, Batch dispatches its msgs from here
+pub(crate) fn dispatch(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    if INDEX_REBUILD.may_load(deps.storage)?.is_some()
+        && !matches!(msg, ExecuteMsg::RebuildLockIndexes { .. })
+    {
+        return Err(ContractError::IndexRebuildPending {});
+    }
+    check_not_paused(deps.storage, &env, &msg)?;
+
     match msg {
-        ExecuteMsg::Lock { id, expire } => try_lock(
+        ExecuteMsg::Lock {
+            id,
+            expire,
+            referrer,
+            amount,
+        } => {
+            let expire = expire_or_default(deps.storage, &env, expire)?;
+            let (funds, excess) = split_excess(info.funds, amount)?;
+            let mut res = try_lock_denoms(deps, env, funds, &info.sender, id, expire, referrer)?;
+            res.messages.extend(refund_msg(&info.sender, excess));
+            Ok(res)
+        }
+        ExecuteMsg::LockDerived { expire } => {
+            try_lock_derived(deps, env, Balance::from(info.funds), &info.sender, expire)
+        }
+        ExecuteMsg::LockFor { owner, id, expire } => try_lock_for(
+            deps,
+            env,
+            Balance::from(info.funds),
+            &info.sender,
+            owner,
+            id,
+            expire,
+        ),
+        ExecuteMsg::IncreaseLock { id, min_remaining } => try_increase_lock(
+            deps,
+            env,
+            Balance::from(info.funds),
+            &info.sender,
+            id,
+            min_remaining,
+        ),
+        ExecuteMsg::LockJoint {
+            id,
+            expire,
+            owners,
+            threshold,
+        } => try_lock_joint(
             deps,
             env,
             Balance::from(info.funds),
             &info.sender,
             id,
             expire,
+            owners,
+            threshold,
         ),
-        ExecuteMsg::IncreaseLock { id } => {
-            try_increase_lock(deps, env, Balance::from(info.funds), &info.sender, id)
+        ExecuteMsg::ApproveUnlock { owner, id } => {
+            try_approve_unlock(deps, env, &info.sender, owner, id)
         }
         ExecuteMsg::Unlock { id } => try_unlock(deps, env, info, id),
+        ExecuteMsg::UnlockMany { ids } => try_unlock_many(deps, env, info, ids),
+        ExecuteMsg::UnlockFor { owner, id } => try_unlock_for(deps, env, info, owner, id),
+        ExecuteMsg::AuthorizeRelayer { relayer, expires } => {
+            try_authorize_relayer(deps, env, info, relayer, expires)
+        }
+        ExecuteMsg::RevokeRelayer { relayer } => try_revoke_relayer(deps, info, relayer),
+        ExecuteMsg::RelayUnlock { owner, id } => try_relay_unlock(deps, env, info, owner, id),
+        ExecuteMsg::MapController { controller } => try_map_controller(deps, info, controller),
+        ExecuteMsg::UnmapController { controller } => try_unmap_controller(deps, info, controller),
+        ExecuteMsg::RemoteLock { msg } => try_remote_lock(deps, env, info, msg),
+        ExecuteMsg::ClaimAsset { id, asset } => try_claim_asset(deps, env, info, id, asset),
+        ExecuteMsg::ClaimNext { id, order } => try_claim_next(deps, env, info, id, order),
+        ExecuteMsg::SetAssetOrder { id, order } => try_set_asset_order(deps, info, id, order),
+        ExecuteMsg::SetWithdrawAllowance {
+            id,
+            spender,
+            asset,
+            amount,
+        } => try_set_withdraw_allowance(deps, info, id, spender, asset, amount),
+        ExecuteMsg::WithdrawFrom {
+            owner,
+            id,
+            asset,
+            amount,
+        } => try_withdraw_from(deps, env, info, owner, id, asset, amount),
         ExecuteMsg::Receive(msg) => try_recive(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig {
+            max_lock_time,
+            max_native_coins,
+            max_cw20_tokens,
+            grace_period,
+            rate_limit,
+            max_active_locks,
+            default_lock_time,
+            min_increase,
+        } => try_update_config(
+            deps,
+            info,
+            max_lock_time,
+            max_native_coins,
+            max_cw20_tokens,
+            grace_period,
+            rate_limit,
+            max_active_locks,
+            default_lock_time,
+            min_increase,
+        ),
+        ExecuteMsg::UpdateProfile {
+            name,
+            description,
+            website,
+            logo_url,
+        } => try_update_profile(
+            deps,
+            info,
+            Profile {
+                name,
+                description,
+                website,
+                logo_url,
+            },
+        ),
+        ExecuteMsg::UpdateLockers { add, remove } => try_update_lockers(deps, info, add, remove),
+        ExecuteMsg::UpdateLegacyLockers { add, remove } => {
+            try_update_legacy_lockers(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdateLenders { add, remove } => try_update_lenders(deps, info, add, remove),
+        ExecuteMsg::ReserveIdPrefix { prefix } => try_reserve_id_prefix(deps, info, prefix),
+        ExecuteMsg::UpdateCapBypass { add, remove } => {
+            try_update_cap_bypass(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdateRecipientAllowlist { add, remove } => {
+            try_update_recipient_allowlist(deps, info, add, remove)
+        }
+        ExecuteMsg::FreezeToken {
+            token,
+            hold_unlocks,
+        } => try_freeze_token(deps, info, token, hold_unlocks),
+        ExecuteMsg::UnfreezeToken { token } => try_unfreeze_token(deps, info, token),
+        ExecuteMsg::Pause { until } => try_pause(deps, env, info, until),
+        ExecuteMsg::Unpause {} => try_unpause(deps, info),
+        ExecuteMsg::RegisterTokenMigration {
+            old_token,
+            new_token,
+            converter,
+        } => try_register_token_migration(deps, env, info, old_token, new_token, converter),
+        ExecuteMsg::RemoveTokenMigration { old_token } => {
+            try_remove_token_migration(deps, info, old_token)
+        }
+        ExecuteMsg::GrantRole { address, role } => try_grant_role(deps, info, address, role),
+        ExecuteMsg::RevokeRole { address } => try_revoke_role(deps, info, address),
+        ExecuteMsg::SetDenomMetadata { denom, metadata } => {
+            try_set_denom_metadata(deps, info, denom, metadata)
+        }
+        ExecuteMsg::CacheTokenInfo { tokens } => try_cache_token_info(deps, tokens),
+        ExecuteMsg::RegisterAirdrop {
+            merkle_root,
+            lock_time,
+        } => try_register_airdrop(
+            deps,
+            &env,
+            Balance::from(info.funds),
+            &info.sender,
+            merkle_root,
+            lock_time,
+        ),
+        ExecuteMsg::ClaimAirdrop {
+            stage,
+            amount,
+            proof,
+            id,
+        } => try_claim_airdrop(deps, env, &info.sender, stage, amount, proof, id),
+        ExecuteMsg::CreateStream {
+            recipient,
+            rate,
+            start,
+        } => try_create_stream(
+            deps,
+            env,
+            Balance::from(info.funds),
+            &info.sender,
+            recipient,
+            rate,
+            start,
+        ),
+        ExecuteMsg::WithdrawStream { id, amount } => {
+            try_withdraw_stream(deps, env, &info.sender, id, amount)
+        }
+        ExecuteMsg::CancelStream { id } => try_cancel_stream(deps, env, &info.sender, id),
+        ExecuteMsg::TopUpStream { id } => {
+            try_top_up_stream(deps, env, Balance::from(info.funds), &info.sender, id)
+        }
+        ExecuteMsg::ChangeStreamRate { id, rate } => {
+            try_change_stream_rate(deps, env, &info.sender, id, rate)
+        }
+        ExecuteMsg::AcceptStreamRate { id } => try_accept_stream_rate(deps, env, &info.sender, id),
+        ExecuteMsg::CreateVestingGroup { admin, schedules } => try_create_vesting_group(
+            deps,
+            Balance::from(info.funds),
+            &info.sender,
+            admin,
+            schedules,
+        ),
+        ExecuteMsg::AddVestingSchedule { group, schedule } => {
+            try_add_vesting_schedule(deps, info, group, schedule)
+        }
+        ExecuteMsg::ClaimVesting { group } => try_claim_vesting(deps, env, info, group),
+        ExecuteMsg::ClawbackVesting { group, recipient } => {
+            try_clawback_vesting(deps, env, info, group, recipient)
+        }
+        ExecuteMsg::WithdrawVestingPool { group, amount } => {
+            try_withdraw_vesting_pool(deps, info, group, amount)
+        }
+        ExecuteMsg::SetSwap { id, swap } => try_set_swap(deps, info, id, swap),
+        ExecuteMsg::SetDepositHook { id, hook } => try_set_deposit_hook(deps, info, id, hook),
+        ExecuteMsg::SetUnlockCallback { id, callback } => {
+            try_set_unlock_callback(deps, info, id, callback)
+        }
+        ExecuteMsg::SetGuardian { id, guardian } => try_set_guardian(deps, info, id, guardian),
+        ExecuteMsg::SetRollover { id, rollover } => try_set_rollover(deps, info, id, rollover),
+        ExecuteMsg::UpdateSuccessor { successor } => try_update_successor(deps, info, successor),
+        ExecuteMsg::ProcessRollover { owner, id } => {
+            try_process_rollover(deps, env, info, owner, id)
+        }
+        ExecuteMsg::SetPlan { id, plan } => try_set_plan(deps, env, info, id, plan),
+        ExecuteMsg::Crank { owner, id } => try_crank(deps, env, info, owner, id),
+        ExecuteMsg::SetGoal { id, goal } => try_set_goal(deps, env, info, id, goal),
+        ExecuteMsg::SetPrivate { id, private } => try_set_private(deps, info, id, private),
+        ExecuteMsg::SetSingleAsset { id, single_asset } => {
+            try_set_single_asset(deps, info, id, single_asset)
+        }
+        ExecuteMsg::SetViewingKey { key } => try_set_viewing_key(deps, info, key),
+        ExecuteMsg::SetOwnershipKey { pubkey } => try_set_ownership_key(deps, info, pubkey),
+        ExecuteMsg::SetReminder { id, payload } => try_set_reminder(deps, info, id, payload),
+        ExecuteMsg::SetNote { id, note } => try_set_note(deps, info, id, note),
+        ExecuteMsg::RequestHardshipUnlock { id, reason } => {
+            try_request_hardship_unlock(deps, env, info, id, reason)
+        }
+        ExecuteMsg::ApproveHardshipUnlock { owner, id, reason } => {
+            try_approve_hardship_unlock(deps, info, owner, id, reason)
+        }
+        ExecuteMsg::SetBeneficiary { id, beneficiary } => {
+            try_set_beneficiary(deps, info, id, beneficiary)
+        }
+        ExecuteMsg::ProposeBeneficiaryChange {
+            owner,
+            id,
+            beneficiary,
+        } => try_propose_beneficiary_change(deps, info, owner, id, beneficiary),
+        ExecuteMsg::AcceptBeneficiaryChange { owner, id } => {
+            try_accept_beneficiary_change(deps, info, owner, id)
+        }
+        ExecuteMsg::RegisterYieldAdapter { denom, adapter } => {
+            try_register_yield_adapter(deps, info, denom, adapter)
+        }
+        ExecuteMsg::UpdateYieldConfig { to_lock, collector } => {
+            try_update_yield_config(deps, info, to_lock, collector)
+        }
+        ExecuteMsg::HarvestYield { denom } => try_harvest_yield(deps, &env.contract.address, denom),
+        ExecuteMsg::RegisterFeeShare {
+            lp_token,
+            fee_share,
+        } => try_register_fee_share(deps, info, lp_token, fee_share),
+        ExecuteMsg::ClaimLpRewards { lp_token } => try_claim_lp_rewards(deps, env, lp_token),
+        ExecuteMsg::CreditLpRewards { owner, id } => try_credit_lp_rewards(deps, env, owner, id),
+        ExecuteMsg::AssertInvariants { start_after, limit } => {
+            try_assert_invariants(deps.as_ref(), env, start_after, limit)
+        }
+        ExecuteMsg::AnnounceEmergencyWithdraw { recipient } => {
+            try_announce_emergency_withdraw(deps, env, info, recipient)
+        }
+        ExecuteMsg::CancelEmergencyWithdraw {} => try_cancel_emergency_withdraw(deps, info),
+        ExecuteMsg::EmergencyWithdraw {} => try_emergency_withdraw(deps, env, info),
+        ExecuteMsg::UpdateEscheatConfig { config } => try_update_escheat_config(deps, info, config),
+        ExecuteMsg::WarnAbandoned { owner, id } => try_warn_abandoned(deps, env, owner, id),
+        ExecuteMsg::Escheat { owner, id } => try_escheat(deps, env, owner, id),
+        ExecuteMsg::UpdateParamRegistry { registry } => {
+            try_update_param_registry(deps, info, registry)
+        }
+        ExecuteMsg::CreateExtensionCampaign {
+            asset,
+            bonus_bps,
+            min_expire,
+            deadline,
+        } => try_create_extension_campaign(
+            deps,
+            &env,
+            Balance::from(info.funds),
+            &info.sender,
+            asset,
+            bonus_bps,
+            min_expire,
+            deadline,
+        ),
+        ExecuteMsg::AcceptExtension {
+            campaign,
+            id,
+            expire,
+        } => try_accept_extension(deps, env, info, campaign, id, expire),
+        ExecuteMsg::ClaimExtensionBonus { campaign, id } => {
+            try_claim_extension_bonus(deps, env, info, campaign, id)
+        }
+        ExecuteMsg::CloseExtensionCampaign { campaign } => {
+            try_close_extension_campaign(deps, env, info, campaign)
+        }
+        ExecuteMsg::ListLock { id, asset, price } => try_list_lock(deps, info, id, asset, price),
+        ExecuteMsg::DelistLock { id } => try_delist_lock(deps, info, id),
+        ExecuteMsg::BuyLock { owner, id } => try_buy_lock(
+            deps,
+            env,
+            Balance::from(info.funds),
+            &info.sender,
+            owner,
+            id,
+        ),
+        ExecuteMsg::ApproveLender { id, lender } => try_approve_lender(deps, info, id, lender),
+        ExecuteMsg::Encumber { owner, id } => try_encumber(deps, info, owner, id),
+        ExecuteMsg::ReleaseEncumbrance { owner, id } => {
+            try_release_encumbrance(deps, info, owner, id)
+        }
+        ExecuteMsg::SeizeLock { owner, id } => try_seize_lock(deps, env, info, owner, id),
+        ExecuteMsg::Batch { msgs } => try_batch(deps, env, info, msgs),
+        ExecuteMsg::RebuildLockIndexes { limit } => try_rebuild_lock_indexes(deps, env, limit),
+        ExecuteMsg::SetNotifyChannel { channel_id } => {
+            try_set_notify_channel(deps, info, channel_id)
+        }
     }
 }
 
+pub fn try_rebuild_lock_indexes(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let limit = limit.unwrap_or(REBUILD_LIMIT).max(1) as usize;
+    let count = rebuild_lock_indexes(deps.storage, env.block.height, limit)?;
+    let done = INDEX_REBUILD.may_load(deps.storage)?.is_none();
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "rebuild_lock_indexes"),
+            attr("locks", count),
+            attr("done", done),
+        ],
+        ..Response::default()
+    })
+}
+
 pub fn try_lock(
     deps: DepsMut,
     env: Env,
@@ -67,6 +594,35 @@ pub fn try_lock(
     sender: &Addr,
     id: String,
     expire: Timestamp,
+) -> Result<Response, ContractError> {
+    check_id_prefix(deps.storage, &id, sender)?;
+    create_lock(deps, env, balance, sender, id, expire)
+}
+
+/// Block time plus the configured default lock time when expire is omitted
+pub(crate) fn expire_or_default(
+    storage: &dyn Storage,
+    env: &Env,
+    expire: Option<Timestamp>,
+) -> Result<Timestamp, ContractError> {
+    if let Some(expire) = expire {
+        return Ok(expire);
+    }
+    let state = STATE.load(storage)?;
+    if state.default_lock_time == 0 {
+        return Err(ContractError::MissingExpire {});
+    }
+
+    Ok(env.block.time.plus_seconds(state.default_lock_time))
+}
+
+pub(crate) fn create_lock(
+    mut deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    sender: &Addr,
+    id: String,
+    expire: Timestamp,
 ) -> Result<Response, ContractError> {
     if balance.is_empty() {
         return Err(ContractError::EmptyBalance {});
@@ -78,57 +634,166 @@ pub fn try_lock(
     }
 
     let state = STATE.load(deps.storage)?;
+    let max_lock_time = load_max_lock_time(deps.storage, &deps.querier, &env, &state)?;
     let diff = expire.minus_seconds(current_time.seconds());
-    if diff.seconds().ge(&state.max_lock_time) {
+    if diff.seconds().ge(&max_lock_time) {
         return Err(ContractError::HighExpired {});
     }
 
-    let lock = Lock {
+    let funds: GenericBalance = balance.clone().into();
+    check_funds_size(&state, &funds)?;
+    check_denom_policy(&state, &funds)?;
+    check_deposit(deps.storage, &balance)?;
+    check_block_time(deps.storage, &env)?;
+    check_rate_limit(deps.storage, &state, sender, current_time)?;
+    add_active_lock(deps.storage, &state, sender)?;
+
+    let mut attributes = lock_attrs(events::LOCK, sender, &id, expire);
+    attributes.extend(deposit_attrs(&balance, &funds));
+    let mut messages = sync_lp_rewards(deps.branch(), &env, &funds)?;
+    messages.extend(notify_msgs(
+        deps.storage,
+        &env,
+        created_packet(sender, &id, expire, &funds),
+    )?);
+    let mut lock = Lock {
         create: env.block.time,
         expire,
-        funds: balance.into(),
+        funds,
+        swap: None,
+        deposit_hook: None,
+        unlock_callback: None,
+        guardian: None,
+        rollover: false,
+        rollover_successor: None,
+        encumbrance: None,
+        approved_lender: None,
+        joint: None,
+        plan: None,
+        goal: None,
+        beneficiary: None,
+        beneficiary_proposal: None,
+        note: None,
+        private: false,
+        reminder: None,
+        hardship: None,
+        single_asset: false,
+        allowances: vec![],
+        asset_order: vec![],
+        abandon_warnings: None,
+        yield_principal: vec![],
     };
     let key = (sender, id.to_owned());
+    messages.append(&mut deposit_msgs(deps.storage, &mut lock, &balance)?);
 
     // try to store it, fail if the id was already in use
-    LOCKS.update(deps.storage, key, |existing| match existing {
+    locks().update(deps.storage, key, |existing| match existing {
         None => Ok(lock),
         Some(_) => Err(ContractError::AlreadyInUse {}),
     })?;
+    record_activity(
+        deps.storage,
+        sender,
+        &id,
+        ActivityKind::Create,
+        balance.clone().into(),
+        env.block.time,
+    )?;
+    add_locked_total(deps.storage, sender, env.block.height, balance)?;
 
     Ok(Response {
-        attributes: vec![attr("action", "lock"), attr("from", sender), attr("id", id)],
+        messages,
+        attributes,
+        data: Some(lock_data(sender, id, expire)?),
         ..Response::default()
     })
 }
 
-pub fn try_increase_lock(
+pub fn try_lock_for(
     deps: DepsMut,
     env: Env,
     balance: Balance,
     sender: &Addr,
+    owner: String,
+    id: String,
+    expire: Timestamp,
+) -> Result<Response, ContractError> {
+    if LOCKERS.may_load(deps.storage, sender)?.is_none() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the locker, not the owner, must hold the id prefix
+    check_id_prefix(deps.storage, &id, sender)?;
+    let owner = deps.api.addr_validate(&owner)?;
+    let mut res = create_lock(deps, env, balance, &owner, id, expire)?;
+    res.attributes.push(attr("locker", sender));
+
+    Ok(res)
+}
+
+pub fn try_increase_lock(
+    mut deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    sender: &Addr,
     id: String,
+    min_remaining: Option<u64>,
 ) -> Result<Response, ContractError> {
     if balance.is_empty() {
         return Err(ContractError::EmptyBalance {});
     }
 
     let key = (sender, id.to_owned());
-    let mut lock = LOCKS.load(deps.storage, key.clone())?;
+    let mut lock = load_lock(deps.storage, sender, &id)?;
+    let state = STATE.load(deps.storage)?;
 
-    if env.block.time.gt(&lock.expire) {
+    if lock.is_expired(env.block.time, state.inclusive_expire) {
         return Err(ContractError::LockExpired {});
     }
+    // protects depositors from topping up a lock about to be released
+    let remaining = lock.expire.seconds() - env.block.time.seconds();
+    if remaining < min_remaining.unwrap_or_default() {
+        return Err(ContractError::RemainingTooLow { remaining });
+    }
 
-    lock.funds.add_tokens(balance);
-    LOCKS.save(deps.storage, key, &lock)?;
+    check_min_increase(&state, &balance)?;
+    let cw20 = matches!(balance, Balance::Cw20(_));
+    if lock.single_asset || (state.single_asset_mode && cw20) {
+        check_single_asset(&lock.funds, &balance)?;
+    }
+    let mut messages = sync_lp_rewards(deps.branch(), &env, &balance.clone().into())?;
+    lock.funds.add_tokens(balance.clone());
+    check_funds_size(&state, &lock.funds)?;
+    check_denom_policy(&state, &lock.funds)?;
+    check_deposit(deps.storage, &balance)?;
+    messages.append(&mut deposit_msgs(deps.storage, &mut lock, &balance)?);
+    locks().save(deps.storage, key, &lock)?;
+    let balance_attrs = balance.clone();
+    record_deposit(
+        deps.storage,
+        sender,
+        &id,
+        sender,
+        balance.clone(),
+        env.block.time,
+    )?;
+    record_activity(
+        deps.storage,
+        sender,
+        &id,
+        ActivityKind::Deposit,
+        balance.clone().into(),
+        env.block.time,
+    )?;
+    add_locked_total(deps.storage, sender, env.block.height, balance)?;
+
+    let mut attributes = lock_attrs(events::INCREASE_LOCK, sender, &id, lock.expire);
+    attributes.extend(deposit_attrs(&balance_attrs, &lock.funds));
 
     Ok(Response {
-        attributes: vec![
-            attr("action", "increase_lock"),
-            attr("from", sender),
-            attr("id", id),
-        ],
+        messages,
+        attributes,
+        data: Some(lock_data(sender, id, lock.expire)?),
         ..Response::default()
     })
 }
@@ -139,367 +804,2689 @@ pub fn try_unlock(
     info: MessageInfo,
     id: String,
 ) -> Result<Response, ContractError> {
-    let key = (&info.sender, id);
-    let lock = LOCKS.load(deps.storage, key.clone())?;
+    unlock(deps, env, &info.sender, id)
+}
 
-    if env.block.time.le(&lock.expire) {
-        return Err(ContractError::LockNotExpired {});
+/// Permissionless unlock paying out to the owner, available once the grace period is over
+pub fn try_unlock_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let state = STATE.load(deps.storage)?;
+    let lock = load_lock(deps.storage, &owner, &id)?;
+
+    // a grace period ending past the last timestamp never ends
+    let grace_end = state
+        .grace_period
+        .checked_mul(1_000_000_000)
+        .and_then(|grace| lock.expire.nanos().checked_add(grace))
+        .map(Timestamp::from_nanos);
+    let over = grace_end.map_or(false, |end| {
+        if state.inclusive_expire {
+            env.block.time.ge(&end)
+        } else {
+            env.block.time.gt(&end)
+        }
+    });
+    if !over {
+        return Err(ContractError::GracePeriodActive {});
     }
+    check_recipient(deps.as_ref(), lock.recipient(&owner))?;
+
+    let mut res = unlock(deps, env, &owner, id)?;
+    res.attributes.push(attr("by", info.sender));
+
+    Ok(res)
+}
 
-    // unlock all tokens
-    let messages = send_tokens(&info.sender, &lock.funds)?;
+pub(crate) fn unlock(
+    deps: DepsMut,
+    env: Env,
+    owner: &Addr,
+    id: String,
+) -> Result<Response, ContractError> {
+    let mut lock = load_releasable_lock(deps.storage, &env, owner, id.to_owned())?;
+    check_guardian(&deps.querier, owner, &id, &lock)?;
 
-    // remove lock
-    LOCKS.remove(deps.storage, key);
+    // unlock all tokens, swapping or depositing first if requested
+    let funds = std::mem::take(&mut lock.funds);
+    let mut attributes = lock_attrs(events::UNLOCK, owner, &id, lock.expire);
+    attributes.extend(asset_attrs(&funds));
+    let recipient = lock.recipient(owner).clone();
+    let release =
+        release_funds(deps, &env, owner, &id, &mut lock, funds, &recipient)?.send(&recipient)?;
 
     let res = Response {
-        messages,
-        attributes: vec![attr("action", "unlock"), attr("from", info.sender)],
-        ..Response::default()
+        submessages: release.submessages,
+        messages: release.messages,
+        attributes,
+        data: Some(payout_data(owner, id, lock.expire, release.payouts)?),
     };
 
     Ok(res)
 }
 
-pub fn try_recive(
-    deps: DepsMut,
+pub fn try_unlock_many(
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    wrapper: Cw20ReceiveMsg,
+    ids: Vec<String>,
 ) -> Result<Response, ContractError> {
-    let msg: ReceiveMsg = from_binary(&wrapper.msg)?;
-    let balance = Balance::Cw20(Cw20CoinVerified {
-        address: info.sender,
-        amount: wrapper.amount,
-    });
-    let api = deps.api;
-    let sender = &api.addr_validate(&wrapper.sender)?;
-    match msg {
-        ReceiveMsg::Lock { id, expire } => try_lock(deps, env, balance, sender, id, expire),
-        ReceiveMsg::IncreaseLock { id } => try_increase_lock(deps, env, balance, sender, id),
+    let mut funds = GenericBalance::default();
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut submessages = vec![];
+    let mut unlocked = vec![];
+    let mut lock_attributes = vec![];
+
+    for id in ids {
+        let mut lock = load_releasable_lock(deps.storage, &env, &info.sender, id.to_owned())?;
+        check_guardian(&deps.querier, &info.sender, &id, &lock)?;
+        if lock.swap.is_some() {
+            return Err(ContractError::SwapNotBatchable { id });
+        }
+
+        let lock_funds = std::mem::take(&mut lock.funds);
+        lock_attributes.extend(lock_attrs(events::UNLOCK, &info.sender, &id, lock.expire));
+        lock_attributes.extend(asset_attrs(&lock_funds));
+        let recipient = lock.recipient(&info.sender).clone();
+        let mut release = release_funds(
+            deps.branch(),
+            &env,
+            &info.sender,
+            &id,
+            &mut lock,
+            lock_funds,
+            &recipient,
+        )?;
+        if lock.beneficiary.is_some() {
+            release = release.send(&recipient)?;
+        } else {
+            // the owner share is sent along with the other locks, but listed per lock
+            push_payout(&mut release.payouts, &recipient, release.funds.clone());
+            funds.add_balance(std::mem::take(&mut release.funds));
+        }
+        messages.append(&mut release.messages);
+        submessages.append(&mut release.submessages);
+        unlocked.push(LockData {
+            owner: info.sender.to_string(),
+            id,
+            expire: lock.expire,
+            payouts: release.payouts,
+        });
     }
+
+    // a single transfer per denom and cw20 token
+    messages.append(&mut send_tokens(&info.sender, &funds)?);
+
+    // followed by the attributes of an unlock per lock
+    let mut attributes = vec![
+        attr(events::ACTION, events::UNLOCK_MANY),
+        attr(events::OWNER, &info.sender),
+        attr("count", unlocked.len()),
+    ];
+    attributes.append(&mut lock_attributes);
+    Ok(Response {
+        submessages,
+        messages,
+        attributes,
+        data: Some(to_binary(&unlocked)?),
+    })
 }
 
-fn send_tokens(to: &Addr, balance: &GenericBalance) -> StdResult<Vec<CosmosMsg>> {
-    let native_balance = &balance.native;
-    let mut msgs: Vec<CosmosMsg> = if native_balance.is_empty() {
-        vec![]
-    } else {
-        vec![BankMsg::Send {
-            to_address: to.into(),
-            amount: native_balance.to_vec(),
-        }
-        .into()]
-    };
+/// Removes an expired lock from storage, returning it so its funds can be sent
+pub(crate) fn remove_expired_lock(
+    storage: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    id: String,
+) -> Result<Lock, ContractError> {
+    let lock = load_releasable_lock(storage, env, owner, id.to_owned())?;
+    check_release(storage, &lock.funds)?;
+
+    remove_lock(storage, owner, &id)?;
+    sub_locked_total(storage, owner, env.block.height, &lock.funds)?;
+    record_activity(
+        storage,
+        owner,
+        &id,
+        ActivityKind::Withdraw,
+        lock.funds.clone(),
+        env.block.time,
+    )?;
+
+    Ok(lock)
+}
 
-    let cw20_balance = &balance.cw20;
-    let cw20_msgs: StdResult<Vec<_>> = cw20_balance
-        .iter()
-        .map(|c| {
-            let msg = Cw20ExecuteMsg::Transfer {
-                recipient: to.into(),
-                amount: c.amount,
-            };
-            let exec = WasmMsg::Execute {
-                contract_addr: c.address.to_string(),
-                msg: to_binary(&msg)?,
-                send: vec![],
-            };
-            Ok(exec.into())
-        })
-        .collect();
-    msgs.append(&mut cw20_msgs?);
-    Ok(msgs)
+/// Messages releasing funds taken out of a lock
+pub(crate) struct Release {
+    pub messages: Vec<CosmosMsg>,
+    pub submessages: Vec<SubMsg>,
+    pub payouts: Vec<PayoutInfo>,
+    /// Left to send to the recipient after the swap, deposit hook and token conversion
+    pub funds: GenericBalance,
 }
 
-#[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Lock { address, id } => to_binary(&query_lock(deps, address, id)?),
-        QueryMsg::AllLocks { address } => to_binary(&query_locks(deps, address)?),
+impl Release {
+    /// Sends the funds left to recipient
+    pub fn send(mut self, recipient: &Addr) -> StdResult<Self> {
+        self.messages
+            .append(&mut send_tokens(recipient, &self.funds)?);
+        push_payout(
+            &mut self.payouts,
+            recipient,
+            std::mem::take(&mut self.funds),
+        );
+        Ok(self)
     }
 }
 
-fn query_lock(deps: Deps, address: String, id: String) -> StdResult<LockInfo> {
-    let key = (&deps.api.addr_validate(&address)?, id.to_owned());
-    let lock = LOCKS.load(deps.storage, key)?;
+/// Releases funds taken out of the owner lock to recipient, withdrawing them from the yield
+/// adapter and going through the lock swap, deposit hook and token migrations.
+/// The lock is saved, or removed along with its listing once empty, notifying the unlock
+/// callback
+pub(crate) fn release_funds(
+    mut deps: DepsMut,
+    env: &Env,
+    owner: &Addr,
+    id: &str,
+    lock: &mut Lock,
+    mut funds: GenericBalance,
+    recipient: &Addr,
+) -> Result<Release, ContractError> {
+    check_release(deps.storage, &funds)?;
+    sub_locked_total(deps.storage, owner, env.block.height, &funds)?;
+    record_activity(
+        deps.storage,
+        owner,
+        id,
+        ActivityKind::Withdraw,
+        funds.clone(),
+        env.block.time,
+    )?;
+
+    // the released LP token takes its share of the pending fee rewards along
+    let mut messages = sync_lp_rewards(deps.branch(), env, &funds)?;
+    messages.extend(notify_msgs(
+        deps.storage,
+        env,
+        released_packet(owner, id, &funds),
+    )?);
+    messages.append(&mut withdraw_msgs(
+        deps.storage,
+        &deps.querier,
+        &env.contract.address,
+        lock,
+        &mut funds,
+    )?);
+    let emptied = lock.funds.native.is_empty() && lock.funds.cw20.is_empty();
+    if emptied {
+        remove_lock(deps.storage, owner, id)?;
+    } else {
+        locks().save(deps.storage, (owner, id.to_owned()), lock)?;
+    }
+
+    let mut payouts = vec![];
+    let mut submessages: Vec<SubMsg> = match &lock.swap {
+        Some(swap) => swap_submsg(deps.storage, swap, &mut funds, recipient, &mut payouts)?
+            .into_iter()
+            .collect(),
+        None => vec![],
+    };
+    if let Some(hook) = &lock.deposit_hook {
+        messages.extend(deposit_msg(hook, &mut funds, &mut payouts)?);
+    }
+    submessages.append(&mut convert_submsgs(
+        deps.storage,
+        &deps.querier,
+        env,
+        &mut funds,
+        recipient,
+        &mut payouts,
+    )?);
+    if let Some(callback) = lock.unlock_callback.as_ref().filter(|_| emptied) {
+        submessages.push(callback_submsg(owner, id, callback)?);
+    }
 
-    to_lock_info(lock, id)
+    Ok(Release {
+        messages,
+        submessages,
+        payouts,
+        funds,
+    })
 }
 
-fn query_locks(deps: Deps, address: String) -> StdResult<AllLocksResponse> {
-    let owner_addr = &deps.api.addr_validate(&address)?;
+/// Removes the lock along with its market listing
+pub(crate) fn remove_lock(storage: &mut dyn Storage, owner: &Addr, id: &str) -> StdResult<()> {
+    locks().remove(storage, (owner, id.to_owned()))?;
+    LISTINGS.remove(storage, (owner, id.to_owned()));
+    remove_active_lock(storage, owner)
+}
 
-    let locks_id: Result<Vec<_>, _> = LOCKS
-        .prefix(&owner_addr)
-        .keys(deps.storage, None, None, Order::Ascending)
-        .map(String::from_utf8)
-        .collect();
+/// Loads the lock of owner, telling a missing lock apart from other storage errors
+pub(crate) fn load_lock(
+    storage: &dyn Storage,
+    owner: &Addr,
+    id: &str,
+) -> Result<Lock, ContractError> {
+    locks()
+        .may_load(storage, (owner, id.to_owned()))?
+        .ok_or_else(|| ContractError::LockNotFound {
+            owner: owner.to_string(),
+            id: id.to_owned(),
+        })
+}
+
+/// Loads a lock whose funds can be released, either by expire, goal or joint approval
+pub(crate) fn load_releasable_lock(
+    storage: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    id: String,
+) -> Result<Lock, ContractError> {
+    check_block_time(storage, env)?;
 
-    Ok(AllLocksResponse { locks: locks_id? })
+    let lock = load_lock(storage, owner, &id)?;
+    check_unencumbered(&lock)?;
+
+    let state = STATE.load(storage)?;
+    if !lock.is_releasable(env.block.time, state.inclusive_expire) {
+        return Err(ContractError::LockNotExpired {});
+    }
+    if let Some(joint) = &lock.joint {
+        if !joint.is_approved() {
+            return Err(ContractError::ThresholdNotMet {});
+        }
+    }
+
+    Ok(lock)
 }
 
-fn to_lock_info(lock: Lock, id: String) -> StdResult<LockInfo> {
-    // transform tokens
-    let native_balance = lock.funds.native;
-    let cw20_balance: StdResult<Vec<_>> = lock
-        .funds
-        .cw20
+pub(crate) fn remove_active_lock(storage: &mut dyn Storage, owner: &Addr) -> StdResult<()> {
+    let count = ACTIVE_LOCKS.may_load(storage)?.unwrap_or_default();
+    ACTIVE_LOCKS.save(storage, &count.saturating_sub(1))?;
+
+    match LOCK_COUNTS.may_load(storage, owner)?.unwrap_or_default() {
+        0 | 1 => LOCK_COUNTS.remove(storage, owner),
+        count => LOCK_COUNTS.save(storage, owner, &(count - 1))?,
+    }
+    Ok(())
+}
+
+pub(crate) fn sub_locked_total(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    height: u64,
+    funds: &GenericBalance,
+) -> StdResult<()> {
+    LOCKED_TOTALS.update(storage, owner, height, |totals| -> StdResult<_> {
+        let mut totals = totals.unwrap_or_default();
+        totals.sub_balance(funds)?;
+        Ok(totals)
+    })?;
+    let mut sum = LOCKED_SUM.may_load(storage)?.unwrap_or_default();
+    sum.sub_balance(funds)?;
+    LOCKED_SUM.save(storage, &sum)?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_lock_time: Option<u64>,
+    max_native_coins: Option<u32>,
+    max_cw20_tokens: Option<u32>,
+    grace_period: Option<u64>,
+    rate_limit: Option<RateLimit>,
+    max_active_locks: Option<u64>,
+    default_lock_time: Option<u64>,
+    min_increase: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(max_lock_time) = max_lock_time {
+        state.max_lock_time = max_lock_time;
+    }
+    if let Some(max_native_coins) = max_native_coins {
+        state.max_native_coins = max_native_coins;
+    }
+    if let Some(max_cw20_tokens) = max_cw20_tokens {
+        state.max_cw20_tokens = max_cw20_tokens;
+    }
+    if let Some(grace_period) = grace_period {
+        state.grace_period = grace_period;
+    }
+    if let Some(rate_limit) = rate_limit {
+        state.rate_limit = Some(rate_limit).filter(|limit| limit.window > 0);
+    }
+    if let Some(max_active_locks) = max_active_locks {
+        state.max_active_locks = max_active_locks;
+    }
+    if let Some(default_lock_time) = default_lock_time {
+        state.default_lock_time = default_lock_time;
+    }
+    if let Some(min_increase) = min_increase {
+        state.min_increase = min_increase;
+    }
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response {
+        attributes: vec![attr("action", "update_config")],
+        ..Response::default()
+    })
+}
+
+pub fn try_update_profile(
+    deps: DepsMut,
+    info: MessageInfo,
+    profile: Profile,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    PROFILE.save(deps.storage, &profile)?;
+
+    Ok(Response {
+        attributes: vec![attr("action", "update_profile")],
+        ..Response::default()
+    })
+}
+
+pub fn try_update_lockers(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for locker in add {
+        let addr = deps.api.addr_validate(&locker)?;
+        LOCKERS.save(deps.storage, &addr, &Empty {})?;
+    }
+    for locker in remove {
+        let addr = deps.api.addr_validate(&locker)?;
+        LOCKERS.remove(deps.storage, &addr);
+    }
+
+    Ok(Response {
+        attributes: vec![attr("action", "update_lockers")],
+        ..Response::default()
+    })
+}
+
+pub fn try_update_cap_bypass(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for owner in add {
+        let addr = deps.api.addr_validate(&owner)?;
+        CAP_BYPASS.save(deps.storage, &addr, &Empty {})?;
+    }
+    for owner in remove {
+        let addr = deps.api.addr_validate(&owner)?;
+        CAP_BYPASS.remove(deps.storage, &addr);
+    }
+
+    Ok(Response {
+        attributes: vec![attr("action", "update_cap_bypass")],
+        ..Response::default()
+    })
+}
+
+pub fn try_recive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let msg: ReceiveMsg = from_binary(&wrapper.msg)?;
+    let balance = Balance::Cw20(Cw20CoinVerified {
+        address: info.sender,
+        amount: wrapper.amount,
+    });
+    let api = deps.api;
+    let sender = &api.addr_validate(&wrapper.sender)?;
+    match msg {
+        ReceiveMsg::Lock {
+            id,
+            expire,
+            referrer,
+        } => {
+            let expire = expire_or_default(deps.storage, &env, expire)?;
+            try_lock_referred(deps, env, balance, sender, id, expire, referrer)
+        }
+        ReceiveMsg::LockDerived { expire } => try_lock_derived(deps, env, balance, sender, expire),
+        ReceiveMsg::LockFor { owner, id, expire } => {
+            try_lock_for(deps, env, balance, sender, owner, id, expire)
+        }
+        ReceiveMsg::IncreaseLock { id, min_remaining } => {
+            try_increase_lock(deps, env, balance, sender, id, min_remaining)
+        }
+        ReceiveMsg::LockJoint {
+            id,
+            expire,
+            owners,
+            threshold,
+        } => try_lock_joint(deps, env, balance, sender, id, expire, owners, threshold),
+        ReceiveMsg::RegisterAirdrop {
+            merkle_root,
+            lock_time,
+        } => try_register_airdrop(deps, &env, balance, sender, merkle_root, lock_time),
+        ReceiveMsg::CreateStream {
+            recipient,
+            rate,
+            start,
+        } => try_create_stream(deps, env, balance, sender, recipient, rate, start),
+        ReceiveMsg::TopUpStream { id } => try_top_up_stream(deps, env, balance, sender, id),
+        ReceiveMsg::CreateVestingGroup { admin, schedules } => {
+            try_create_vesting_group(deps, balance, sender, admin, schedules)
+        }
+        ReceiveMsg::CreateExtensionCampaign {
+            asset,
+            bonus_bps,
+            min_expire,
+            deadline,
+        } => try_create_extension_campaign(
+            deps, &env, balance, sender, asset, bonus_bps, min_expire, deadline,
+        ),
+        ReceiveMsg::MigrateIn {
+            owner,
+            id,
+            create,
+            expire,
+        } => try_migrate_in(deps, env, balance, sender, owner, id, create, expire),
+        ReceiveMsg::BuyLock { owner, id } => try_buy_lock(deps, env, balance, sender, owner, id),
+    }
+}
+
+pub(crate) fn add_locked_total(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    height: u64,
+    balance: Balance,
+) -> StdResult<()> {
+    add_locked_balance(storage, owner, height, &balance.into())
+}
+
+pub(crate) fn add_locked_balance(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    height: u64,
+    funds: &GenericBalance,
+) -> StdResult<()> {
+    LOCKED_TOTALS.update(storage, owner, height, |totals| -> StdResult<_> {
+        let mut totals = totals.unwrap_or_default();
+        totals.add_balance(funds.clone());
+        Ok(totals)
+    })?;
+    let mut sum = LOCKED_SUM.may_load(storage)?.unwrap_or_default();
+    sum.add_balance(funds.clone());
+    LOCKED_SUM.save(storage, &sum)?;
+    Ok(())
+}
+
+/// Denom (or cw20 address), deposited amount and new lock total of each deposited asset
+fn deposit_attrs(balance: &Balance, funds: &GenericBalance) -> Vec<Attribute> {
+    let deposits: Vec<(Denom, Uint128)> = match balance {
+        Balance::Native(native) => native
+            .0
+            .iter()
+            .map(|coin| (Denom::Native(coin.denom.clone()), coin.amount))
+            .collect(),
+        Balance::Cw20(token) => vec![(Denom::Cw20(token.address.clone()), token.amount)],
+    };
+
+    deposits
         .into_iter()
-        .map(|token| {
-            Ok(Cw20Coin {
-                address: token.address.into(),
-                amount: token.amount,
+        .flat_map(|(denom, amount)| {
+            let total = funds.amount_of(&denom);
+            let denom = match denom {
+                Denom::Native(denom) => denom,
+                Denom::Cw20(address) => address.into(),
+            };
+            vec![
+                attr(events::ASSET, denom),
+                attr(events::AMOUNT, amount),
+                attr(events::TOTAL, total),
+            ]
+        })
+        .collect()
+}
+
+/// Counts the new lock against max_active_locks unless owner bypasses the cap
+pub(crate) fn add_active_lock(
+    storage: &mut dyn Storage,
+    state: &State,
+    owner: &Addr,
+) -> Result<(), ContractError> {
+    let count = ACTIVE_LOCKS.may_load(storage)?.unwrap_or_default();
+    if state.max_active_locks > 0
+        && count >= state.max_active_locks
+        && CAP_BYPASS.may_load(storage, owner)?.is_none()
+    {
+        return Err(ContractError::TooManyLocks {
+            max: state.max_active_locks,
+        });
+    }
+    ACTIVE_LOCKS.save(storage, &(count + 1))?;
+    LOCK_COUNTS.update(storage, owner, |count| -> StdResult<_> {
+        Ok(count.unwrap_or_default() + 1)
+    })?;
+
+    Ok(())
+}
+
+/// Counts the lock creation in the owner fixed window
+fn check_rate_limit(
+    storage: &mut dyn Storage,
+    state: &State,
+    owner: &Addr,
+    time: Timestamp,
+) -> Result<(), ContractError> {
+    let limit = match &state.rate_limit {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+
+    let mut window = match LOCK_RATE.may_load(storage, owner)? {
+        Some(window) if time < window.start.plus_seconds(limit.window) => window,
+        _ => RateWindow {
+            start: time,
+            count: 0,
+        },
+    };
+    if window.count >= limit.max_locks {
+        return Err(ContractError::RateLimited {
+            retry_after: window.start.plus_seconds(limit.window),
+        });
+    }
+    window.count += 1;
+    LOCK_RATE.save(storage, owner, &window)?;
+
+    Ok(())
+}
+
+/// Bounds the assets held per lock, every save and load pays for them
+pub(crate) fn check_funds_size(state: &State, funds: &GenericBalance) -> Result<(), ContractError> {
+    if funds.native.len() > state.max_native_coins as usize {
+        return Err(ContractError::TooManyNativeCoins {
+            max: state.max_native_coins,
+        });
+    }
+    if funds.cw20.len() > state.max_cw20_tokens as usize {
+        return Err(ContractError::TooManyCw20Tokens {
+            max: state.max_cw20_tokens,
+        });
+    }
+    Ok(())
+}
+
+/// Each deposited asset must reach min_increase, dust entries bloat the lock storage
+fn check_min_increase(state: &State, balance: &Balance) -> Result<(), ContractError> {
+    let min = state.min_increase;
+    let dust = match balance {
+        Balance::Native(balance) => balance.0.iter().any(|coin| coin.amount < min),
+        Balance::Cw20(token) => token.amount < min,
+    };
+    if dust {
+        return Err(ContractError::IncreaseTooSmall { min });
+    }
+    Ok(())
+}
+
+pub(crate) fn lock_data(owner: &Addr, id: String, expire: Timestamp) -> StdResult<Binary> {
+    payout_data(owner, id, expire, vec![])
+}
+
+/// LockData listing the tokens sent while releasing the lock
+pub(crate) fn payout_data(
+    owner: &Addr,
+    id: String,
+    expire: Timestamp,
+    payouts: Vec<PayoutInfo>,
+) -> StdResult<Binary> {
+    to_binary(&LockData {
+        owner: owner.into(),
+        id,
+        expire,
+        payouts,
+    })
+}
+
+/// Records the funds sent to recipient, skipping empty balances as nothing is sent for them
+pub(crate) fn push_payout(payouts: &mut Vec<PayoutInfo>, recipient: &Addr, funds: GenericBalance) {
+    if funds.native.is_empty() && funds.cw20.is_empty() {
+        return;
+    }
+    payouts.push(PayoutInfo {
+        recipient: recipient.into(),
+        native: funds.native,
+        cw20: to_cw20_coins(funds.cw20),
+    });
+}
+
+pub fn send_tokens(to: &Addr, balance: &GenericBalance) -> StdResult<Vec<CosmosMsg>> {
+    let native_balance = &balance.native;
+    let mut msgs: Vec<CosmosMsg> = if native_balance.is_empty() {
+        vec![]
+    } else {
+        vec![BankMsg::Send {
+            to_address: to.into(),
+            amount: native_balance.to_vec(),
+        }
+        .into()]
+    };
+
+    let cw20_balance = &balance.cw20;
+    let cw20_msgs: StdResult<Vec<_>> = cw20_balance
+        .iter()
+        .map(|c| {
+            let msg = Cw20ExecuteMsg::Transfer {
+                recipient: to.into(),
+                amount: c.amount,
+            };
+            let exec = WasmMsg::Execute {
+                contract_addr: c.address.to_string(),
+                msg: to_binary(&msg)?,
+                send: vec![],
+            };
+            Ok(exec.into())
+        })
+        .collect();
+    msgs.append(&mut cw20_msgs?);
+    Ok(msgs)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        SWAP_REPLY_ID => reply_swap(deps, msg.result),
+        CALLBACK_REPLY_ID => Ok(reply_callback(msg.result)),
+        CONVERT_REPLY_ID => reply_convert(deps, env),
+        id => Err(ContractError::UnknownReply { id }),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    // simulated msgs are shifted by execute
+    #[cfg(feature = "testing")]
+    let env = match msg {
+        QueryMsg::Simulate { .. } => env,
+        _ => crate::time_travel::shifted_env(deps.storage, env)?,
+    };
+
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::V2(msg) => query_v2(deps, msg),
+        QueryMsg::Profile {} => to_binary(&PROFILE.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::Lock {
+            address,
+            id,
+            viewing_key,
+        } => to_binary(&query_lock(deps, address, id, viewing_key)?),
+        QueryMsg::AllLocks {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&query_locks(deps, address, start_after, limit)?),
+        QueryMsg::Lockers {} => to_binary(&query_lockers(deps)?),
+        QueryMsg::LegacyLockers {} => to_binary(&query_legacy_lockers(deps)?),
+        QueryMsg::Lenders {} => to_binary(&query_lenders(deps)?),
+        QueryMsg::RecipientAllowlist {} => to_binary(&query_recipient_allowlist(deps)?),
+        QueryMsg::FrozenTokens {} => to_binary(&query_frozen_tokens(deps)?),
+        QueryMsg::Pause {} => to_binary(&query_pause(deps, env)?),
+        QueryMsg::Successor {} => to_binary(&query_successor(deps)?),
+        QueryMsg::TokenMigrations {} => to_binary(&query_token_migrations(deps)?),
+        QueryMsg::Relayers { owner } => to_binary(&query_relayers(deps, owner)?),
+        QueryMsg::Controller { controller } => to_binary(&query_controller(deps, controller)?),
+        QueryMsg::Roles {} => to_binary(&query_roles(deps)?),
+        QueryMsg::Referrer { address } => to_binary(&query_referrer(deps, address)?),
+        QueryMsg::IdPrefix { prefix } => to_binary(&query_id_prefix(deps, prefix)?),
+        QueryMsg::Deposits {
+            address,
+            id,
+            start_after,
+            limit,
+            viewing_key,
+        } => to_binary(&query_deposits(
+            deps,
+            address,
+            id,
+            start_after,
+            limit,
+            viewing_key,
+        )?),
+        QueryMsg::ActivityReport {
+            address,
+            from_time,
+            to_time,
+            viewing_key,
+        } => to_binary(&query_activity_report(
+            deps,
+            address,
+            from_time,
+            to_time,
+            viewing_key,
+        )?),
+        QueryMsg::ActiveLocks {} => to_binary(&ActiveLocksResponse {
+            count: ACTIVE_LOCKS.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::LockCount { address } => to_binary(&query_lock_count(deps, address)?),
+        QueryMsg::Owners { start_after, limit } => {
+            to_binary(&query_owners(deps, start_after, limit)?)
+        }
+        QueryMsg::Vested {
+            address,
+            id,
+            t,
+            viewing_key,
+        } => to_binary(&query_vested(deps, env, address, id, t, viewing_key)?),
+        QueryMsg::Unvested {
+            address,
+            id,
+            t,
+            viewing_key,
+        } => to_binary(&query_unvested(deps, env, address, id, t, viewing_key)?),
+        QueryMsg::ReleasePreview {
+            owner,
+            id,
+            at_time,
+            viewing_key,
+        } => to_binary(&query_release_preview(
+            deps,
+            owner,
+            id,
+            at_time,
+            viewing_key,
+        )?),
+        QueryMsg::CanUnlock { owner, id } => to_binary(&query_can_unlock(deps, env, owner, id)?),
+        QueryMsg::UnlockSchedule { from, to } => to_binary(&query_unlock_schedule(deps, from, to)?),
+        QueryMsg::ValidateLockParams {
+            expire,
+            amount_hints,
+        } => to_binary(&query_validate_lock_params(
+            deps,
+            env,
+            expire,
+            amount_hints,
+        )?),
+        QueryMsg::LockedAtHeight {
+            address,
+            height,
+            viewing_key,
+        } => to_binary(&query_locked_at_height(deps, address, height, viewing_key)?),
+        QueryMsg::Airdrop { stage } => to_binary(&query_airdrop(deps, stage)?),
+        QueryMsg::AirdropClaimed { stage, address } => {
+            to_binary(&query_airdrop_claimed(deps, stage, address)?)
+        }
+        QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
+        QueryMsg::OwnershipChallenge { address, id, nonce } => {
+            to_binary(&query_ownership_challenge(deps, env, address, id, nonce)?)
+        }
+        QueryMsg::VerifyOwnership {
+            address,
+            id,
+            nonce,
+            signature,
+        } => to_binary(&query_verify_ownership(
+            deps, env, address, id, nonce, signature,
+        )?),
+        QueryMsg::Stream { id, at_time } => to_binary(&query_stream(deps, env, id, at_time)?),
+        QueryMsg::VestingGroup { group } => to_binary(&query_vesting_group(deps, group)?),
+        QueryMsg::VestingSchedule {
+            group,
+            recipient,
+            at_time,
+        } => to_binary(&query_vesting_schedule(
+            deps, env, group, recipient, at_time,
+        )?),
+        QueryMsg::UpcomingExpirations {
+            hours,
+            start_after,
+            limit,
+        } => to_binary(&query_upcoming_expirations(
+            deps,
+            env,
+            hours,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::LocksExpiringBefore {
+            time,
+            start_after,
+            limit,
+        } => to_binary(&query_locks_expiring_before(
+            deps,
+            time,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::TimeHighWater {} => to_binary(&query_time_high_water(deps)?),
+        QueryMsg::ExecutionGuard {} => to_binary(&query_execution_guard(deps)?),
+        QueryMsg::NotifyChannel {} => to_binary(&query_notify_channel(deps)?),
+        QueryMsg::YieldAdapter { denom } => to_binary(&query_yield_adapter(deps, denom)?),
+        QueryMsg::FeeShare { lp_token } => to_binary(&query_fee_share(deps, lp_token)?),
+        QueryMsg::EmergencyWithdrawal {} => to_binary(&query_emergency_withdrawal(deps)?),
+        QueryMsg::EscheatConfig {} => to_binary(&query_escheat_config(deps)?),
+        QueryMsg::ParamRegistry {} => to_binary(&query_param_registry(deps, env)?),
+        QueryMsg::ExtensionCampaign { campaign } => {
+            to_binary(&query_extension_campaign(deps, campaign)?)
+        }
+        QueryMsg::Listings {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_listings(deps, owner, start_after, limit)?),
+        QueryMsg::Solvency {} => to_binary(&query_solvency(deps, env)?),
+        QueryMsg::Simulate { sender, funds, msg } => {
+            to_binary(&query_simulate(deps, env, sender, funds, msg)?)
+        }
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let state = STATE.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        max_lock_time: state.max_lock_time,
+        owner: state.owner.into(),
+        max_native_coins: state.max_native_coins,
+        max_cw20_tokens: state.max_cw20_tokens,
+        grace_period: state.grace_period,
+        rate_limit: state.rate_limit,
+        max_active_locks: state.max_active_locks,
+        default_lock_time: state.default_lock_time,
+        min_increase: state.min_increase,
+        emergency_delay: state.emergency_delay,
+        immutable: state.immutable,
+        restrict_contract_recipients: state.restrict_contract_recipients,
+        gating_token: state.gating_token.map(String::from),
+        single_asset_mode: state.single_asset_mode,
+        inclusive_expire: state.inclusive_expire,
+        multi_denom_policy: state.multi_denom_policy,
+    })
+}
+
+fn query_lock_count(deps: Deps, address: String) -> StdResult<LockCountResponse> {
+    let owner = deps.api.addr_validate(&address)?;
+    let count = LOCK_COUNTS.may_load(deps.storage, &owner)?;
+
+    Ok(LockCountResponse {
+        count: count.unwrap_or_default(),
+    })
+}
+
+fn query_owners(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OwnersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let owners: StdResult<Vec<_>> = LOCK_COUNTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .map(|item| {
+            let (address, lock_count) = item?;
+            Ok(OwnerInfo {
+                address: String::from_utf8(address)?,
+                lock_count,
             })
         })
         .collect();
+    let mut owners = owners?;
+    let next_start_after = next_cursor(&mut owners, limit, |owner| owner.address.clone());
 
-    let lock_info = LockInfo {
+    Ok(OwnersResponse {
+        owners,
+        next_start_after,
+    })
+}
+
+fn query_lock(
+    deps: Deps,
+    address: String,
+    id: String,
+    viewing_key: Option<String>,
+) -> StdResult<LockInfo> {
+    let owner = deps.api.addr_validate(&address)?;
+    let mut lock = locks().load(deps.storage, (&owner, id.to_owned()))?;
+    if !can_view(deps.storage, &owner, &lock, viewing_key)? {
+        lock.funds = GenericBalance::default();
+    }
+
+    to_lock_info(lock, id)
+}
+
+fn query_v2(deps: Deps, msg: QueryMsgV2) -> StdResult<Binary> {
+    match msg {
+        QueryMsgV2::Lock {
+            address,
+            id,
+            viewing_key,
+            include_config,
+            include_token_info,
+        } => to_binary(&query_lock_v2(
+            deps,
+            address,
+            id,
+            viewing_key,
+            include_config,
+            include_token_info,
+        )?),
+    }
+}
+
+fn query_lock_v2(
+    deps: Deps,
+    address: String,
+    id: String,
+    viewing_key: Option<String>,
+    include_config: bool,
+    include_token_info: bool,
+) -> StdResult<LockInfoV2> {
+    let owner = deps.api.addr_validate(&address)?;
+    let mut lock = locks().load(deps.storage, (&owner, id.to_owned()))?;
+    let mut goal = to_goal_info(&lock);
+    if !can_view(deps.storage, &owner, &lock, viewing_key)? {
+        lock.funds = GenericBalance::default();
+        if let Some(goal) = goal.as_mut() {
+            goal.current = Uint128::zero();
+        }
+    }
+    let config = if include_config {
+        Some(to_lock_config(&lock))
+    } else {
+        None
+    };
+    let token_info = if include_token_info {
+        Some(tokens_metadata(deps, &lock.funds.cw20)?)
+    } else {
+        None
+    };
+
+    Ok(LockInfoV2 {
+        owner: owner.into(),
         id,
         create: lock.create,
         expire: lock.expire,
-        native_balance,
-        cw20_balance: cw20_balance?,
-    };
+        beneficiary: lock.beneficiary.as_ref().map(Addr::to_string),
+        goal,
+        joint: lock.joint.map(|joint| JointInfo {
+            owners: joint.owners.into_iter().map(String::from).collect(),
+            threshold: joint.threshold,
+            approvals: joint.approvals.into_iter().map(String::from).collect(),
+        }),
+        note: lock.note,
+        private: lock.private,
+        single_asset: lock.single_asset,
+        hardship: lock.hardship,
+        denom_metadata: coins_metadata(deps.storage, &lock.funds.native)?,
+        config,
+        token_info,
+        native_balance: lock.funds.native,
+        cw20_balance: to_cw20_coins(lock.funds.cw20),
+    })
+}
+
+fn to_lock_config(lock: &Lock) -> LockConfig {
+    LockConfig {
+        swap: lock.swap.as_ref().map(|swap| SwapMsg {
+            router: swap.router.to_string(),
+            offer: swap.offer.clone(),
+            ask: swap.ask.clone(),
+            min_receive: swap.min_receive,
+        }),
+        deposit_hook: lock.deposit_hook.as_ref().map(|hook| DepositHookMsg {
+            contract: hook.contract.to_string(),
+            denom: hook.denom.clone(),
+            msg: hook.msg.clone(),
+        }),
+        unlock_callback: lock
+            .unlock_callback
+            .as_ref()
+            .map(|callback| UnlockCallbackMsg {
+                contract: callback.contract.to_string(),
+                msg: callback.msg.clone(),
+            }),
+        guardian: lock.guardian.as_ref().map(Addr::to_string),
+        rollover: lock.rollover,
+        encumbrance: lock.encumbrance.as_ref().map(Addr::to_string),
+        plan: lock.plan.as_ref().map(|plan| PlanInfo {
+            token: plan.token.to_string(),
+            amount: plan.amount,
+            interval: plan.interval,
+            next: plan.next,
+        }),
+        beneficiary_proposal: lock.beneficiary_proposal.as_ref().map(|proposal| {
+            BeneficiaryProposalInfo {
+                beneficiary: proposal.beneficiary.to_string(),
+                proposer: proposal.proposer.to_string(),
+            }
+        }),
+        allowances: lock
+            .allowances
+            .iter()
+            .map(|allowance| AllowanceInfo {
+                spender: allowance.spender.to_string(),
+                asset: allowance.asset.clone(),
+                amount: allowance.amount,
+            })
+            .collect(),
+        asset_order: lock.asset_order.clone(),
+    }
+}
+
+fn query_locks(
+    deps: Deps,
+    address: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllLocksResponse> {
+    let owner_addr = &deps.api.addr_validate(&address)?;
+    let limit = limit.map_or(usize::MAX, |limit| limit.min(MAX_LIMIT) as usize);
+    let start = start_after.map(Bound::exclusive);
+
+    let locks_id: Result<Vec<_>, _> = locks()
+        .prefix(owner_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit.saturating_add(1))
+        .map(String::from_utf8)
+        .collect();
+    let mut locks_id = locks_id?;
+    let next_start_after = next_cursor(&mut locks_id, limit, String::clone);
+
+    Ok(AllLocksResponse {
+        locks: locks_id,
+        next_start_after,
+    })
+}
+
+fn query_lockers(deps: Deps) -> StdResult<LockersResponse> {
+    let lockers: Result<Vec<_>, _> = LOCKERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(String::from_utf8)
+        .collect();
+
+    Ok(LockersResponse { lockers: lockers? })
+}
+
+fn query_vested(
+    deps: Deps,
+    env: Env,
+    address: String,
+    id: String,
+    t: Option<Timestamp>,
+    viewing_key: Option<String>,
+) -> StdResult<VestingResponse> {
+    let owner = deps.api.addr_validate(&address)?;
+    let lock = locks().load(deps.storage, (&owner, id))?;
+    let state = STATE.load(deps.storage)?;
+
+    if !can_view(deps.storage, &owner, &lock, viewing_key)? {
+        Ok(VestingResponse::default())
+    } else if lock.is_releasable(t.unwrap_or(env.block.time), state.inclusive_expire) {
+        Ok(to_vesting_response(lock.funds))
+    } else {
+        Ok(VestingResponse::default())
+    }
+}
+
+fn query_unvested(
+    deps: Deps,
+    env: Env,
+    address: String,
+    id: String,
+    t: Option<Timestamp>,
+    viewing_key: Option<String>,
+) -> StdResult<VestingResponse> {
+    let owner = deps.api.addr_validate(&address)?;
+    let lock = locks().load(deps.storage, (&owner, id))?;
+    let state = STATE.load(deps.storage)?;
+
+    if !can_view(deps.storage, &owner, &lock, viewing_key)?
+        || lock.is_releasable(t.unwrap_or(env.block.time), state.inclusive_expire)
+    {
+        Ok(VestingResponse::default())
+    } else {
+        Ok(to_vesting_response(lock.funds))
+    }
+}
+
+fn query_release_preview(
+    deps: Deps,
+    owner: String,
+    id: String,
+    at_time: Timestamp,
+    viewing_key: Option<String>,
+) -> StdResult<ReleasePreviewResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let lock = locks().load(deps.storage, (&owner, id))?;
+    let state = STATE.load(deps.storage)?;
+
+    let (claimable, locked) = if !can_view(deps.storage, &owner, &lock, viewing_key)? {
+        (GenericBalance::default(), GenericBalance::default())
+    } else if lock.is_releasable(at_time, state.inclusive_expire) {
+        (lock.funds, GenericBalance::default())
+    } else {
+        (GenericBalance::default(), lock.funds)
+    };
+
+    Ok(ReleasePreviewResponse {
+        at_time,
+        expire: lock.expire,
+        claimable_native: claimable.native,
+        claimable_cw20: to_cw20_coins(claimable.cw20),
+        locked_native: locked.native,
+        locked_cw20: to_cw20_coins(locked.cw20),
+    })
+}
+
+fn query_can_unlock(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    id: String,
+) -> StdResult<CanUnlockResponse> {
+    let key = (&deps.api.addr_validate(&owner)?, id);
+    let lock = locks().load(deps.storage, key)?;
+    let state = STATE.load(deps.storage)?;
+
+    let approved = lock
+        .joint
+        .as_ref()
+        .map_or(true, |joint| joint.is_approved());
+    Ok(CanUnlockResponse {
+        can_unlock: approved
+            && lock.encumbrance.is_none()
+            && lock.is_releasable(env.block.time, state.inclusive_expire),
+        expire: lock.expire,
+        inclusive_expire: state.inclusive_expire,
+    })
+}
+
+fn query_locked_at_height(
+    deps: Deps,
+    address: String,
+    height: u64,
+    viewing_key: Option<String>,
+) -> StdResult<LockedAtHeightResponse> {
+    let owner_addr = &deps.api.addr_validate(&address)?;
+    let totals = if has_private_locks(deps.storage, owner_addr)?
+        && !is_viewing_key(deps.storage, owner_addr, viewing_key)?
+    {
+        GenericBalance::default()
+    } else {
+        LOCKED_TOTALS
+            .may_load_at_height(deps.storage, owner_addr, height)?
+            .unwrap_or_default()
+    };
+
+    Ok(LockedAtHeightResponse {
+        height,
+        native_balance: totals.native,
+        cw20_balance: to_cw20_coins(totals.cw20),
+    })
+}
+
+fn to_lock_info(lock: Lock, id: String) -> StdResult<LockInfo> {
+    let lock_info = LockInfo {
+        id,
+        create: lock.create,
+        expire: lock.expire,
+        native_balance: lock.funds.native,
+        cw20_balance: to_cw20_coins(lock.funds.cw20),
+    };
+
+    Ok(lock_info)
+}
+
+fn to_vesting_response(funds: GenericBalance) -> VestingResponse {
+    VestingResponse {
+        native_balance: funds.native,
+        cw20_balance: to_cw20_coins(funds.cw20),
+    }
+}
+
+/// Truncates a page read with limit + 1 items, returning the cursor of its last item if more remain
+pub(crate) fn next_cursor<T, C>(
+    items: &mut Vec<T>,
+    limit: usize,
+    cursor: impl Fn(&T) -> C,
+) -> Option<C> {
+    if items.len() <= limit {
+        return None;
+    }
+    items.truncate(limit);
+    items.last().map(cursor)
+}
+
+pub(crate) fn to_cw20_coins(tokens: Vec<Cw20CoinVerified>) -> Vec<Cw20Coin> {
+    tokens
+        .into_iter()
+        .map(|token| Cw20Coin {
+            address: token.address.into(),
+            amount: token.amount,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, Coin, CosmosMsg, StdError, StdResult};
+    use cw_storage_plus::{Map, U64Key};
+    use serde::{Deserialize, Serialize};
+
+    #[test]
+    fn proper_initialization() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &coins(1000, "earth"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+    }
+
+    #[test]
+    fn update_profile() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateProfile {
+            name: Some("Lockbox".into()),
+            description: None,
+            website: Some("https://lockbox.example".into()),
+            logo_url: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Profile {}).unwrap();
+        let value: Profile = from_binary(&res).unwrap();
+        assert_eq!(Some("Lockbox".into()), value.name);
+        assert_eq!(None, value.description);
+    }
+
+    #[test]
+    fn immutable() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            emergency_delay: Some(1000),
+            immutable: true,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateConfig {
+            max_lock_time: Some(7200),
+            max_native_coins: None,
+            max_cw20_tokens: None,
+            grace_period: None,
+            rate_limit: None,
+            max_active_locks: None,
+            default_lock_time: None,
+            min_increase: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert!(value.immutable);
+        assert_eq!(None, value.emergency_delay);
+        assert_eq!(3600, value.max_lock_time);
+    }
+
+    #[test]
+    fn lock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // empty funds
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(10)),
+            referrer: None,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Err(ContractError::EmptyBalance {}) => {}
+            _ => panic!("Must return EmptyBalance error"),
+        }
+
+        // lower expire
+        let info = mock_info("anyone", &coins(2, "token"));
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(10)),
+            referrer: None,
+            amount: None,
+        };
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::LowExpired {}) => {}
+            _ => panic!("Must return LowExpired error"),
+        }
+
+        // high expire
+        env.block.time = Timestamp::from_seconds(0);
+        let info = mock_info("anyone", &coins(2, "token"));
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(4000)),
+            referrer: None,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::HighExpired {}) => {}
+            _ => panic!("Must return HighExpired error"),
+        }
+
+        // lock funds 1
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(200)),
+            referrer: None,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let data: LockData = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(
+            LockData {
+                owner: "anyone".into(),
+                id: "1".into(),
+                expire: Timestamp::from_seconds(200),
+                payouts: vec![],
+            },
+            data
+        );
+
+        // should exists lock
+        let msg = QueryMsg::Lock {
+            address: "anyone".into(),
+            id: "1".into(),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LockInfo = from_binary(&res).unwrap();
+        assert_eq!(0, value.create.seconds());
+        assert_eq!(200, value.expire.seconds());
+
+        // try lock same id
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(200)),
+            referrer: None,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::AlreadyInUse {}) => {}
+            _ => panic!("Must return AlreadyInUse error"),
+        }
+
+        // lock funds 2
+        let msg = ExecuteMsg::Lock {
+            id: "2".into(),
+            expire: Some(Timestamp::from_seconds(300)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // should exists lock
+        let msg = QueryMsg::Lock {
+            address: "anyone".into(),
+            id: "2".into(),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LockInfo = from_binary(&res).unwrap();
+        assert_eq!(300, value.expire.seconds());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllLocks {
+                address: "anyone".into(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: AllLocksResponse = from_binary(&res).unwrap();
+        assert_eq!(2, value.locks.len());
+        assert_eq!(None, value.next_start_after);
+
+        // paginated
+        let page = |start_after: Option<String>| {
+            let msg = QueryMsg::AllLocks {
+                address: "anyone".into(),
+                start_after,
+                limit: Some(1),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<AllLocksResponse>(&res).unwrap()
+        };
+        let value = page(None);
+        assert_eq!(vec!["1".to_string()], value.locks);
+        assert_eq!(Some("1".into()), value.next_start_after);
+        let value = page(value.next_start_after);
+        assert_eq!(vec!["2".to_string()], value.locks);
+        assert_eq!(None, value.next_start_after);
+    }
+
+    #[test]
+    fn default_lock_time() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let info = mock_info("anyone", &coins(2, "token"));
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: None,
+            referrer: None,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::MissingExpire {}) => {}
+            _ => panic!("Must return MissingExpire error"),
+        }
+
+        let config = ExecuteMsg::UpdateConfig {
+            max_lock_time: None,
+            max_native_coins: None,
+            max_cw20_tokens: None,
+            grace_period: None,
+            rate_limit: None,
+            max_active_locks: None,
+            default_lock_time: Some(1800),
+            min_increase: None,
+        };
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            config,
+        )
+        .unwrap();
+
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let data: LockData = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(Timestamp::from_seconds(1900), data.expire);
+    }
+
+    #[test]
+    fn increase_lock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            min_increase: Some(Uint128(3)),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock funds
+        let mut env = mock_env();
+        let info = mock_info("anyone", &coins(2, "token"));
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(200)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // try increase lock invalid id
+        let info = mock_info("anyone", &coins(5, "token"));
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "2".into(),
+            min_remaining: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::LockNotFound { owner, id }) => {
+                assert_eq!(("anyone", "2"), (owner.as_str(), id.as_str()))
+            }
+            _ => panic!("Must return LockNotFound error"),
+        }
+
+        // locks are looked up by the sender, not by id alone
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("other", &[]), msg);
+        match res {
+            Err(ContractError::LockNotFound { owner, .. }) => assert_eq!("other", owner),
+            _ => panic!("Must return LockNotFound error"),
+        }
+
+        // try increase lock after expire
+        let info = mock_info("anyone", &coins(5, "token"));
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        env.block.time = Timestamp::from_seconds(201);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::LockExpired {}) => {}
+            _ => panic!("Must return LockExpired error"),
+        }
+
+        // lock expires too soon for the depositor
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: Some(81),
+        };
+        env.block.time = Timestamp::from_seconds(120);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::RemainingTooLow { remaining }) => assert_eq!(80, remaining),
+            _ => panic!("Must return RemainingTooLow error"),
+        }
+
+        // dust increase
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        let dust = mock_info("anyone", &coins(1, "token"));
+        let res = execute(deps.as_mut(), env.clone(), dust, msg);
+        match res {
+            Err(ContractError::IncreaseTooSmall { min }) => assert_eq!(Uint128(3), min),
+            _ => panic!("Must return IncreaseTooSmall error"),
+        }
+
+        // increase valid lock
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: Some(80),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(
+            res.attributes[4..],
+            [
+                attr("asset", "token"),
+                attr("amount", 5u128),
+                attr("total", 7u128)
+            ]
+        );
+
+        // query funds lock
+        let msg = QueryMsg::Lock {
+            address: "anyone".into(),
+            id: "1".into(),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LockInfo = from_binary(&res).unwrap();
+        assert_eq!(coins(7, "token"), value.native_balance);
+    }
+
+    #[test]
+    fn unlock() {
+        let mut deps = mock_dependencies(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock funds
+        let info = mock_info("anyone", &coins(2, "token"));
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // cannot unlock until expire
+        let auth_info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let res = execute(deps.as_mut(), env.clone(), auth_info, msg);
+        match res {
+            Err(ContractError::LockNotExpired {}) => {}
+            _ => panic!("Must return LockNotExpired error"),
+        }
+
+        // unlock funds
+        let auth_info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        env.block.time = Timestamp::from_seconds(401);
+        let res = execute(deps.as_mut(), env, auth_info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+        let data: LockData = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!("1", data.id);
+        assert_eq!(400, data.expire.seconds());
+        assert_eq!(
+            vec![PayoutInfo {
+                recipient: "anyone".into(),
+                native: coins(2, "token"),
+                cw20: vec![],
+            }],
+            data.payouts
+        );
+        assert_eq!(
+            res.messages[0],
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(2, "token")
+            })
+        );
+
+        // should lock completed
+        let msg = QueryMsg::Lock {
+            address: "anyone".into(),
+            id: "1".into(),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg);
+
+        match res {
+            StdResult::Err(StdError::NotFound { .. }) => {}
+            _ => panic!("Must return StdError::NotFound error"),
+        }
+    }
+
+    #[test]
+    fn inclusive_expire() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // by default the lock is only releasable after its expire second
+        env.block.time = Timestamp::from_seconds(400);
+        let can_unlock = QueryMsg::CanUnlock {
+            owner: "anyone".into(),
+            id: "1".into(),
+        };
+        let res = query(deps.as_ref(), env.clone(), can_unlock.clone()).unwrap();
+        let value: CanUnlockResponse = from_binary(&res).unwrap();
+        assert!(!value.can_unlock && !value.inclusive_expire);
+        let unlock = ExecuteMsg::Unlock { id: "1".into() };
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), unlock.clone());
+        match res {
+            Err(ContractError::LockNotExpired {}) => {}
+            _ => panic!("Must return LockNotExpired error"),
+        }
+
+        let msg = MigrateMsg {
+            inclusive_expire: Some(true),
+            ..MigrateMsg::default()
+        };
+        let _res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        let res = query(deps.as_ref(), env.clone(), can_unlock).unwrap();
+        let value: CanUnlockResponse = from_binary(&res).unwrap();
+        assert!(value.can_unlock && value.inclusive_expire);
+        let res = execute(deps.as_mut(), env, info, unlock).unwrap();
+        assert_eq!(1, res.messages.len());
+    }
+
+    #[test]
+    fn lock_for() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::LockFor {
+            owner: "user".into(),
+            id: "1".into(),
+            expire: Timestamp::from_seconds(200),
+        };
+
+        // only whitelisted lockers
+        let info = mock_info("launchpad", &coins(2, "token"));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        // only owner can whitelist
+        let update = ExecuteMsg::UpdateLockers {
+            add: vec!["launchpad".into()],
+            remove: vec![],
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), update.clone());
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            update,
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Lockers {}).unwrap();
+        let value: LockersResponse = from_binary(&res).unwrap();
+        assert_eq!(vec!["launchpad".to_string()], value.lockers);
+
+        // lock is owned by user
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let msg = QueryMsg::Lock {
+            address: "user".into(),
+            id: "1".into(),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LockInfo = from_binary(&res).unwrap();
+        assert_eq!(coins(2, "token"), value.native_balance);
+    }
+
+    #[test]
+    fn vested() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock funds
+        let info = mock_info("anyone", &coins(2, "token"));
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // nothing vested before expire
+        let msg = QueryMsg::Vested {
+            address: "anyone".into(),
+            id: "1".into(),
+            t: None,
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: VestingResponse = from_binary(&res).unwrap();
+        assert_eq!(VestingResponse::default(), value);
+
+        let msg = QueryMsg::Unvested {
+            address: "anyone".into(),
+            id: "1".into(),
+            t: None,
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: VestingResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(2, "token"), value.native_balance);
+
+        // all vested after expire
+        let msg = QueryMsg::Vested {
+            address: "anyone".into(),
+            id: "1".into(),
+            t: Some(Timestamp::from_seconds(401)),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: VestingResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(2, "token"), value.native_balance);
+
+        let msg = QueryMsg::Unvested {
+            address: "anyone".into(),
+            id: "1".into(),
+            t: Some(Timestamp::from_seconds(401)),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        let value: VestingResponse = from_binary(&res).unwrap();
+        assert_eq!(VestingResponse::default(), value);
+    }
+
+    #[test]
+    fn release_preview() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock funds
+        let info = mock_info("anyone", &coins(2, "token"));
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // at expire everything is still locked
+        let msg = QueryMsg::ReleasePreview {
+            owner: "anyone".into(),
+            id: "1".into(),
+            at_time: Timestamp::from_seconds(400),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ReleasePreviewResponse = from_binary(&res).unwrap();
+        assert_eq!(400, value.expire.seconds());
+        assert!(value.claimable_native.is_empty());
+        assert_eq!(coins(2, "token"), value.locked_native);
+
+        // after expire everything is claimable
+        let msg = QueryMsg::ReleasePreview {
+            owner: "anyone".into(),
+            id: "1".into(),
+            at_time: Timestamp::from_seconds(401),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ReleasePreviewResponse = from_binary(&res).unwrap();
+        assert_eq!(coins(2, "token"), value.claimable_native);
+        assert!(value.locked_native.is_empty());
+    }
+
+    #[test]
+    fn locked_at_height() {
+        let mut deps = mock_dependencies(&coins(5, "token"));
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock funds at height 100
+        let info = mock_info("anyone", &coins(2, "token"));
+        let mut env = mock_env();
+        env.block.height = 100;
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // increase at height 110
+        let info = mock_info("anyone", &coins(3, "token"));
+        env.block.height = 110;
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // unlock at height 120
+        let info = mock_info("anyone", &[]);
+        env.block.height = 120;
+        env.block.time = Timestamp::from_seconds(401);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let locked_at = |height: u64| {
+            let msg = QueryMsg::LockedAtHeight {
+                address: "anyone".into(),
+                height,
+                viewing_key: None,
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let value: LockedAtHeightResponse = from_binary(&res).unwrap();
+            value.native_balance
+        };
+        assert!(locked_at(100).is_empty());
+        assert_eq!(coins(2, "token"), locked_at(101));
+        assert_eq!(coins(5, "token"), locked_at(111));
+        assert!(locked_at(121).is_empty());
+    }
+
+    #[test]
+    fn unlock_many() {
+        let mut deps = mock_dependencies(&coins(5, "token"));
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock funds
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        for (id, expire, amount) in &[("1", 400, 2), ("2", 500, 3)] {
+            let info = mock_info("anyone", &coins(*amount, "token"));
+            let msg = ExecuteMsg::Lock {
+                id: id.to_string(),
+                expire: Some(Timestamp::from_seconds(*expire)),
+                referrer: None,
+                amount: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+
+        // all locks must be expired
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::UnlockMany {
+            ids: vec!["2".into(), "1".into()],
+        };
+        env.block.time = Timestamp::from_seconds(450);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::LockNotExpired {}) => {}
+            _ => panic!("Must return LockNotExpired error"),
+        }
+
+        // same denom is sent once
+        env.block.time = Timestamp::from_seconds(501);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(5, "token")
+            })]
+        );
+        let ids: Vec<_> = res
+            .attributes
+            .iter()
+            .filter(|attr| attr.key == events::LOCK_ID)
+            .map(|attr| attr.value.as_str())
+            .collect();
+        assert_eq!(vec!["2", "1"], ids);
+        let data: Vec<LockData> = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(2, data.len());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AllLocks {
+                address: "anyone".into(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: AllLocksResponse = from_binary(&res).unwrap();
+        assert!(value.locks.is_empty());
+    }
+
+    #[test]
+    fn funds_size() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            max_native_coins: Some(2),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let value: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(2, value.max_native_coins);
+        assert_eq!(DEFAULT_MAX_ASSETS, value.max_cw20_tokens);
+
+        // too many coins in deposit
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let funds = vec![
+            Coin::new(1, "atom"),
+            Coin::new(1, "earth"),
+            Coin::new(1, "token"),
+        ];
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &funds),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::TooManyNativeCoins { max: 2 }) => {}
+            _ => panic!("Must return TooManyNativeCoins error"),
+        }
+
+        // too many coins in lock after increase
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &funds[..2]),
+            msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &funds[2..]),
+            msg,
+        );
+        match res {
+            Err(ContractError::TooManyNativeCoins { max: 2 }) => {}
+            _ => panic!("Must return TooManyNativeCoins error"),
+        }
 
-    Ok(lock_info)
-}
+        // owner raises the limit
+        let msg = ExecuteMsg::UpdateConfig {
+            max_lock_time: None,
+            max_native_coins: Some(3),
+            max_cw20_tokens: None,
+            grace_period: None,
+            rate_limit: None,
+            max_active_locks: None,
+            default_lock_time: None,
+            min_increase: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary, CosmosMsg, StdError, StdResult};
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        let _res = execute(deps.as_mut(), env, mock_info("anyone", &funds[2..]), msg).unwrap();
+    }
 
     #[test]
-    fn proper_initialization() {
+    fn single_asset_mode() {
         let mut deps = mock_dependencies(&[]);
 
         let msg = InstantiateMsg {
             max_lock_time: 3600,
+            single_asset_mode: true,
+            ..InstantiateMsg::default()
         };
-        let info = mock_info("creator", &coins(1000, "earth"));
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let receive = |msg: ReceiveMsg| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "anyone".into(),
+                amount: Uint128(5),
+                msg: to_binary(&msg).unwrap(),
+            })
+        };
+        let lock = receive(ReceiveMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+        });
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("cw20", &[]), lock).unwrap();
+
+        let increase = receive(ReceiveMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        });
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other", &[]),
+            increase.clone(),
+        );
+        match res {
+            Err(ContractError::AssetMismatch {}) => {}
+            _ => panic!("Must return AssetMismatch error"),
+        }
+        let _res = execute(deps.as_mut(), env, mock_info("cw20", &[]), increase).unwrap();
+
+        let value = query_deposits(deps.as_ref(), "anyone".into(), "1".into(), None, None).unwrap();
+        assert_eq!("anyone", value.deposits[0].from);
+        assert_eq!(Some("cw20".into()), value.deposits[0].token_contract);
     }
 
     #[test]
-    fn lock() {
-        let mut deps = mock_dependencies(&[]);
+    fn unlock_for() {
+        let mut deps = mock_dependencies(&coins(2, "token"));
 
         let msg = InstantiateMsg {
             max_lock_time: 3600,
+            grace_period: Some(100),
+            ..InstantiateMsg::default()
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // empty funds
-        let info = mock_info("anyone", &[]);
+        // lock funds
+        let info = mock_info("anyone", &coins(2, "token"));
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
         let msg = ExecuteMsg::Lock {
             id: "1".into(),
-            expire: Timestamp::from_seconds(10),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
         };
-        let res = execute(deps.as_mut(), mock_env(), info, msg);
-        match res {
-            Err(ContractError::EmptyBalance {}) => {}
-            _ => panic!("Must return EmptyBalance error"),
-        }
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // lower expire
-        let info = mock_info("anyone", &coins(2, "token"));
-        let msg = ExecuteMsg::Lock {
+        // owner has a private window after expire
+        let info = mock_info("keeper", &[]);
+        let msg = ExecuteMsg::UnlockFor {
+            owner: "anyone".into(),
             id: "1".into(),
-            expire: Timestamp::from_seconds(10),
         };
-        let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(100);
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        env.block.time = Timestamp::from_seconds(500);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
         match res {
-            Err(ContractError::LowExpired {}) => {}
-            _ => panic!("Must return LowExpired error"),
+            Err(ContractError::GracePeriodActive {}) => {}
+            _ => panic!("Must return GracePeriodActive error"),
         }
 
-        // high expire
-        env.block.time = Timestamp::from_seconds(0);
-        let info = mock_info("anyone", &coins(2, "token"));
-        let msg = ExecuteMsg::Lock {
-            id: "1".into(),
-            expire: Timestamp::from_seconds(4000),
+        // funds are sent to the owner
+        env.block.time = Timestamp::from_seconds(501);
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(2, "token")
+            })]
+        );
+    }
+
+    #[test]
+    fn unlock_for_endless_grace_period() {
+        let mut deps = mock_dependencies(&coins(2, "token"));
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            grace_period: Some(u64::MAX),
+            ..InstantiateMsg::default()
         };
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-        match res {
-            Err(ContractError::HighExpired {}) => {}
-            _ => panic!("Must return HighExpired error"),
-        }
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // lock funds 1
+        let info = mock_info("anyone", &coins(2, "token"));
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
         let msg = ExecuteMsg::Lock {
             id: "1".into(),
-            expire: Timestamp::from_seconds(200),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // should exists lock
-        let msg = QueryMsg::Lock {
-            address: "anyone".into(),
+        let msg = ExecuteMsg::UnlockFor {
+            owner: "anyone".into(),
             id: "1".into(),
         };
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let value: LockInfo = from_binary(&res).unwrap();
-        assert_eq!(0, value.create.seconds());
-        assert_eq!(200, value.expire.seconds());
+        env.block.time = Timestamp::from_seconds(501);
+        let res = execute(deps.as_mut(), env, mock_info("keeper", &[]), msg);
+        match res {
+            Err(ContractError::GracePeriodActive {}) => {}
+            _ => panic!("Must return GracePeriodActive error"),
+        }
+    }
 
-        // try lock same id
-        let msg = ExecuteMsg::Lock {
-            id: "1".into(),
-            expire: Timestamp::from_seconds(200),
+    #[test]
+    fn rate_limit() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            rate_limit: Some(RateLimit {
+                max_locks: 2,
+                window: 100,
+            }),
+            ..InstantiateMsg::default()
         };
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-        match res {
-            Err(ContractError::AlreadyInUse {}) => {}
-            _ => panic!("Must return AlreadyInUse error"),
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let info = mock_info("anyone", &coins(2, "token"));
+        for id in &["1", "2"] {
+            let msg = ExecuteMsg::Lock {
+                id: id.to_string(),
+                expire: Some(Timestamp::from_seconds(400)),
+                referrer: None,
+                amount: None,
+            };
+            let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
         }
 
-        // lock funds 2
         let msg = ExecuteMsg::Lock {
-            id: "2".into(),
-            expire: Timestamp::from_seconds(300),
+            id: "3".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::RateLimited { retry_after }) => {
+                assert_eq!(Timestamp::from_seconds(100), retry_after)
+            }
+            _ => panic!("Must return RateLimited error"),
+        }
 
-        // should exists lock
-        let msg = QueryMsg::Lock {
-            address: "anyone".into(),
-            id: "2".into(),
-        };
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let value: LockInfo = from_binary(&res).unwrap();
-        assert_eq!(300, value.expire.seconds());
+        // other owners have their own window
+        let other = mock_info("other", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), other, msg.clone()).unwrap();
 
-        let res = query(
-            deps.as_ref(),
-            mock_env(),
-            QueryMsg::AllLocks {
-                address: "anyone".into(),
-            },
-        )
-        .unwrap();
-        let value: AllLocksResponse = from_binary(&res).unwrap();
-        assert_eq!(2, value.locks.len())
+        env.block.time = Timestamp::from_seconds(100);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
     }
 
     #[test]
-    fn increase_lock() {
+    fn max_active_locks() {
         let mut deps = mock_dependencies(&[]);
 
         let msg = InstantiateMsg {
             max_lock_time: 3600,
+            max_active_locks: Some(1),
+            ..InstantiateMsg::default()
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // lock funds
         let mut env = mock_env();
-        let info = mock_info("anyone", &coins(2, "token"));
-        env.block.time = Timestamp::from_seconds(100);
+        env.block.time = Timestamp::from_seconds(0);
         let msg = ExecuteMsg::Lock {
             id: "1".into(),
-            expire: Timestamp::from_seconds(200),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
         };
-        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
 
-        // try increase lock invalid id
-        let info = mock_info("anyone", &coins(5, "token"));
-        let msg = ExecuteMsg::IncreaseLock { id: "2".into() };
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        let info = mock_info("partner", &coins(2, "token"));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
         match res {
-            Err(ContractError::Std(StdError::NotFound { .. })) => {}
-            _ => panic!("Must return StdError::NotFound error"),
+            Err(ContractError::TooManyLocks { max: 1 }) => {}
+            _ => panic!("Must return TooManyLocks error"),
         }
 
-        // try increase lock after expire
-        let info = mock_info("anyone", &coins(5, "token"));
-        let msg = ExecuteMsg::IncreaseLock { id: "1".into() };
-        env.block.time = Timestamp::from_seconds(201);
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-        match res {
-            Err(ContractError::LockExpired {}) => {}
-            _ => panic!("Must return LockExpired error"),
+        // bypass list is not capped
+        let bypass = ExecuteMsg::UpdateCapBypass {
+            add: vec!["partner".into()],
+            remove: vec![],
+        };
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            bypass,
+        )
+        .unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // unlock frees capacity
+        env.block.time = Timestamp::from_seconds(401);
+        let unlock = ExecuteMsg::Unlock { id: "1".into() };
+        let _res = execute(deps.as_mut(), env, mock_info("anyone", &[]), unlock).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ActiveLocks {}).unwrap();
+        let value: ActiveLocksResponse = from_binary(&res).unwrap();
+        assert_eq!(1, value.count);
+
+        let lock_count = |address: &str| {
+            let msg = QueryMsg::LockCount {
+                address: address.into(),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<LockCountResponse>(&res).unwrap().count
+        };
+        assert_eq!(0, lock_count("anyone"));
+        assert_eq!(1, lock_count("partner"));
+    }
+
+    #[test]
+    fn owners_pagination() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        for (owner, id) in &[("bob", "1"), ("alice", "1"), ("carol", "1"), ("alice", "2")] {
+            let msg = ExecuteMsg::Lock {
+                id: id.to_string(),
+                expire: Some(Timestamp::from_seconds(100)),
+                referrer: None,
+                amount: None,
+            };
+            let info = mock_info(owner, &coins(2, "token"));
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
         }
 
-        // increase valid lock
-        let msg = ExecuteMsg::IncreaseLock { id: "1".into() };
-        env.block.time = Timestamp::from_seconds(120);
-        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        // owners without locks are dropped
+        env.block.time = Timestamp::from_seconds(101);
+        let unlock = ExecuteMsg::Unlock { id: "1".into() };
+        let _res = execute(deps.as_mut(), env, mock_info("bob", &[]), unlock).unwrap();
 
-        // query funds lock
-        let msg = QueryMsg::Lock {
-            address: "anyone".into(),
-            id: "1".into(),
+        let owners = |start_after: Option<String>| {
+            let msg = QueryMsg::Owners {
+                start_after,
+                limit: Some(1),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<OwnersResponse>(&res).unwrap()
         };
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let value: LockInfo = from_binary(&res).unwrap();
-        assert_eq!(coins(7, "token"), value.native_balance);
+        let page = owners(None);
+        assert_eq!(
+            vec![OwnerInfo {
+                address: "alice".into(),
+                lock_count: 2,
+            }],
+            page.owners
+        );
+        let page = owners(page.next_start_after);
+        assert_eq!("carol", page.owners[0].address);
+        assert_eq!(None, page.next_start_after);
     }
 
     #[test]
-    fn unlock() {
-        let mut deps = mock_dependencies(&coins(2, "token"));
+    fn lock_config() {
+        let mut deps = mock_dependencies(&[]);
 
         let msg = InstantiateMsg {
             max_lock_time: 3600,
+            ..InstantiateMsg::default()
         };
         let info = mock_info("creator", &[]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // lock funds
-        let info = mock_info("anyone", &coins(2, "token"));
         let mut env = mock_env();
         env.block.time = Timestamp::from_seconds(0);
         let msg = ExecuteMsg::Lock {
             id: "1".into(),
-            expire: Timestamp::from_seconds(400),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
         };
-        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
-        // cannot unlock until expire
-        let auth_info = mock_info("anyone", &[]);
-        let msg = ExecuteMsg::Unlock { id: "1".into() };
-        let mut env = mock_env();
-        env.block.time = Timestamp::from_seconds(100);
-        let res = execute(deps.as_mut(), env.clone(), auth_info, msg);
-        match res {
-            Err(ContractError::LockNotExpired {}) => {}
-            _ => panic!("Must return LockNotExpired error"),
-        }
+        let swap = SwapMsg {
+            router: "router".into(),
+            offer: Denom::Native("token".into()),
+            ask: Denom::Native("uusd".into()),
+            min_receive: Uint128::new(10),
+        };
+        let msg = ExecuteMsg::SetSwap {
+            id: "1".into(),
+            swap: Some(swap.clone()),
+        };
+        let _res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
 
-        // unlock funds
-        let auth_info = mock_info("anyone", &[]);
-        let msg = ExecuteMsg::Unlock { id: "1".into() };
-        env.block.time = Timestamp::from_seconds(401);
-        let res = execute(deps.as_mut(), env, auth_info, msg).unwrap();
-        assert_eq!(1, res.messages.len());
+        let query_lock = |include_config| {
+            let msg = QueryMsg::V2(QueryMsgV2::Lock {
+                address: "anyone".into(),
+                id: "1".into(),
+                viewing_key: None,
+                include_config,
+                include_token_info: false,
+            });
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<LockInfoV2>(&res).unwrap()
+        };
+        assert_eq!(None, query_lock(false).config);
         assert_eq!(
-            res.messages[0],
-            CosmosMsg::Bank(BankMsg::Send {
-                to_address: "anyone".into(),
-                amount: coins(2, "token")
-            })
+            Some(LockConfig {
+                swap: Some(swap),
+                deposit_hook: None,
+                unlock_callback: None,
+                guardian: None,
+                rollover: false,
+                encumbrance: None,
+                plan: None,
+                beneficiary_proposal: None,
+                allowances: vec![],
+                asset_order: vec![],
+            }),
+            query_lock(true).config
         );
+    }
 
-        // should lock completed
-        let msg = QueryMsg::Lock {
-            address: "anyone".into(),
-            id: "1".into(),
+    #[test]
+    fn migrate_lock_indexes() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
         };
-        let res = query(deps.as_ref(), mock_env(), msg);
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock stored by 0.2.0, before the indexes and the locked totals
+        #[derive(Serialize, Deserialize)]
+        struct LegacyLock {
+            create: Timestamp,
+            expire: Timestamp,
+            funds: GenericBalance,
+        }
+        let owner = Addr::unchecked("anyone");
+        let lock = LegacyLock {
+            create: Timestamp::from_seconds(0),
+            expire: Timestamp::from_seconds(400),
+            funds: GenericBalance {
+                native: coins(2, "token"),
+                cw20: vec![],
+            },
+        };
+        let legacy: Map<(&Addr, String), LegacyLock> = Map::new("locks");
+        for id in &["1", "2"] {
+            legacy
+                .save(deps.as_mut().storage, (&owner, id.to_string()), &lock)
+                .unwrap();
+        }
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "0.2.0").unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(attr("locks", 2), res.attributes[1]);
+        assert_eq!(2, ACTIVE_LOCKS.load(&deps.storage).unwrap());
+        let totals = LOCKED_TOTALS.load(&deps.storage, &owner).unwrap();
+        assert_eq!(coins(4, "token"), totals.native);
+        let sum = LOCKED_SUM.load(&deps.storage).unwrap();
+        assert_eq!(coins(4, "token"), sum.native);
+        // indexes are only rebuilt when migrating from an older version
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(attr("locks", 0), res.attributes[1]);
+
+        let locks = locks();
+        let token = Denom::Native("token".into());
+        let count_by_asset = |storage: &dyn Storage| {
+            locks
+                .idx
+                .asset
+                .prefix(&token)
+                .keys(storage, None, None, Order::Ascending)
+                .count()
+        };
+        assert_eq!(2, count_by_asset(&deps.storage));
+        let by_expire = locks
+            .idx
+            .expire
+            .prefix(U64Key::new(400))
+            .keys(&deps.storage, None, None, Order::Ascending)
+            .count();
+        assert_eq!(2, by_expire);
+        assert_eq!(
+            Some(2),
+            LOCK_COUNTS.may_load(&deps.storage, &owner).unwrap()
+        );
 
+        // a rebuild larger than a page is finished by RebuildLockIndexes
+        start_index_rebuild(deps.as_mut().storage).unwrap();
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(401);
+        let info = mock_info("anyone", &[]);
+        let unlock = |id: &str| ExecuteMsg::Unlock { id: id.into() };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), unlock("1"));
         match res {
-            StdResult::Err(StdError::NotFound { .. }) => {}
-            _ => panic!("Must return StdError::NotFound error"),
+            Err(ContractError::IndexRebuildPending {}) => {}
+            _ => panic!("Must return IndexRebuildPending error"),
         }
+        let rebuild = ExecuteMsg::RebuildLockIndexes { limit: Some(1) };
+        let keeper = mock_info("keeper", &[]);
+        let res = execute(deps.as_mut(), env.clone(), keeper.clone(), rebuild.clone()).unwrap();
+        assert_eq!(attr("done", "false"), res.attributes[2]);
+        let res = execute(deps.as_mut(), env.clone(), keeper, rebuild).unwrap();
+        assert_eq!(attr("locks", "1"), res.attributes[1]);
+        assert_eq!(attr("done", "true"), res.attributes[2]);
+        assert_eq!(2, count_by_asset(&deps.storage));
+        assert_eq!(2, ACTIVE_LOCKS.load(&deps.storage).unwrap());
+        assert_eq!(
+            Some(2),
+            LOCK_COUNTS.may_load(&deps.storage, &owner).unwrap()
+        );
+
+        // unlock removes the index entries
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), unlock("1")).unwrap();
+        let _res = execute(deps.as_mut(), env, info, unlock("2")).unwrap();
+        let by_owner = locks
+            .idx
+            .owner
+            .prefix(b"anyone".to_vec())
+            .keys(&deps.storage, None, None, Order::Ascending)
+            .count();
+        assert_eq!(0, by_owner);
+        assert_eq!(0, count_by_asset(&deps.storage));
+        assert_eq!(0, ACTIVE_LOCKS.load(&deps.storage).unwrap());
+        let totals = LOCKED_TOTALS.load(&deps.storage, &owner).unwrap();
+        assert!(totals.native.is_empty());
+        assert!(LOCKED_SUM.load(&deps.storage).unwrap().native.is_empty());
     }
 }
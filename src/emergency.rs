@@ -0,0 +1,223 @@
+use cosmwasm_std::{
+    attr, to_binary, BankMsg, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    WasmMsg,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+
+use crate::error::ContractError;
+use crate::msg::EmergencyWithdrawalResponse;
+use crate::solvency::total_liabilities;
+use crate::state::{EmergencyWithdrawal, EMERGENCY_WITHDRAWAL, STATE};
+
+/// Starts the emergency delay, announcing again restarts it
+pub fn try_announce_emergency_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let delay = state
+        .emergency_delay
+        .ok_or(ContractError::EmergencyDisabled {})?;
+
+    let withdrawal = EmergencyWithdrawal {
+        recipient: deps.api.addr_validate(&recipient)?,
+        executable_at: env.block.time.plus_seconds(delay),
+    };
+    EMERGENCY_WITHDRAWAL.save(deps.storage, &withdrawal)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "announce_emergency_withdraw"),
+            attr("recipient", withdrawal.recipient),
+            attr("executable_at", withdrawal.executable_at),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn try_cancel_emergency_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if EMERGENCY_WITHDRAWAL.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoEmergencyAnnounced {});
+    }
+    EMERGENCY_WITHDRAWAL.remove(deps.storage);
+
+    Ok(Response {
+        attributes: vec![attr("action", "cancel_emergency_withdraw")],
+        ..Response::default()
+    })
+}
+
+/// Sends every native balance and every locked cw20 token held by the contract,
+/// funds deposited into yield adapters stay there
+pub fn try_emergency_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let withdrawal = EMERGENCY_WITHDRAWAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoEmergencyAnnounced {})?;
+    if env.block.time < withdrawal.executable_at {
+        return Err(ContractError::EmergencyTimelocked {
+            executable_at: withdrawal.executable_at,
+        });
+    }
+    EMERGENCY_WITHDRAWAL.remove(deps.storage);
+
+    let contract = &env.contract.address;
+    let recipient = withdrawal.recipient;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![
+        attr("action", "emergency_withdraw"),
+        attr("recipient", &recipient),
+    ];
+
+    let natives = deps.querier.query_all_balances(contract)?;
+    for coin in natives.iter() {
+        attributes.push(attr("denom", &coin.denom));
+        attributes.push(attr("amount", coin.amount));
+    }
+    if !natives.is_empty() {
+        messages.push(
+            BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: natives,
+            }
+            .into(),
+        );
+    }
+
+    for token in total_liabilities(deps.as_ref())?.cw20 {
+        let res: BalanceResponse = deps.querier.query_wasm_smart(
+            &token.address,
+            &Cw20QueryMsg::Balance {
+                address: contract.to_string(),
+            },
+        )?;
+        if res.balance.is_zero() {
+            continue;
+        }
+        attributes.push(attr("denom", &token.address));
+        attributes.push(attr("amount", res.balance));
+        messages.push(
+            WasmMsg::Execute {
+                contract_addr: token.address.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: res.balance,
+                })?,
+                send: vec![],
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response {
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+pub fn query_emergency_withdrawal(deps: Deps) -> StdResult<EmergencyWithdrawalResponse> {
+    let withdrawal = EMERGENCY_WITHDRAWAL.may_load(deps.storage)?;
+
+    Ok(EmergencyWithdrawalResponse {
+        recipient: withdrawal.as_ref().map(|w| w.recipient.to_string()),
+        executable_at: withdrawal.map(|w| w.executable_at),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, Timestamp};
+
+    #[test]
+    fn emergency_withdraw() {
+        let mut deps = mock_dependencies(&coins(10, "token"));
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let announce = ExecuteMsg::AnnounceEmergencyWithdraw {
+            recipient: "recovery".into(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), announce.clone());
+        match res {
+            Err(ContractError::EmergencyDisabled {}) => {}
+            _ => panic!("Must return EmergencyDisabled error"),
+        }
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            emergency_delay: Some(1000),
+            ..InstantiateMsg::default()
+        };
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            announce.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), announce).unwrap();
+
+        env.block.time = Timestamp::from_seconds(999);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::EmergencyWithdraw {},
+        );
+        match res {
+            Err(ContractError::EmergencyTimelocked { executable_at }) => {
+                assert_eq!(Timestamp::from_seconds(1000), executable_at)
+            }
+            _ => panic!("Must return EmergencyTimelocked error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(1000);
+        let res = execute(deps.as_mut(), env, info, ExecuteMsg::EmergencyWithdraw {}).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "recovery".into(),
+                amount: coins(10, "token"),
+            }
+            .into()]
+        );
+        let value = query_emergency_withdrawal(deps.as_ref()).unwrap();
+        assert_eq!(None, value.recipient);
+    }
+}
@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp, Uint128};
+use hex::FromHexError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,6 +7,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Hex(#[from] FromHexError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -18,12 +22,448 @@ pub enum ContractError {
     #[error("Expire time is higher")]
     HighExpired {},
 
+    #[error("Sent less {denom} than the declared amount")]
+    InsufficientFunds { denom: String },
+
+    #[error("Expire is required, no default lock time is set")]
+    MissingExpire {},
+
+    #[error("Lock expires in {remaining} seconds")]
+    RemainingTooLow { remaining: u64 },
+
+    #[error("Lock {id} of {owner} does not exist")]
+    LockNotFound { owner: String, id: String },
+
+    #[error("Not authorized on lock {id}")]
+    LockUnauthorized { id: String },
+
     #[error("Lock has not expired")]
     LockNotExpired {},
 
+    #[error("Lock holds none of the asset")]
+    AssetNotLocked {},
+
     #[error("Lock has expired")]
     LockExpired {},
 
+    #[error("Threshold must be between 1 and the number of owners")]
+    InvalidThreshold {},
+
+    #[error("Joint lock approvals below threshold")]
+    ThresholdNotMet {},
+
+    #[error("Lock is not a joint lock")]
+    NotJointLock {},
+
+    #[error("Beneficiary already set, use ProposeBeneficiaryChange")]
+    BeneficiaryAlreadySet {},
+
+    #[error("Note is larger than {max} bytes")]
+    NoteTooLarge { max: usize },
+
+    #[error("Callback msg is larger than {max} bytes")]
+    CallbackTooLarge { max: usize },
+
+    #[error("Guardian did not approve")]
+    GuardianRejected {},
+
+    #[error("Reminder payload is larger than {max} bytes")]
+    ReminderTooLarge { max: usize },
+
+    #[error("Reason is larger than {max} bytes")]
+    ReasonTooLarge { max: usize },
+
+    #[error("Lock has no hardship unlock request")]
+    NoHardshipRequest {},
+
+    #[error("Hardship unlock already approved")]
+    HardshipApproved {},
+
+    #[error("Lock has no beneficiary")]
+    NoBeneficiary {},
+
+    #[error("No beneficiary change proposed")]
+    NoBeneficiaryProposal {},
+
+    #[error("Invariant violated: {reason}")]
+    InvariantViolated { reason: String },
+
+    #[error("Adapter holds locked funds")]
+    AdapterInUse {},
+
+    #[error("Yield is paid to locks")]
+    YieldToLock {},
+
+    #[error("Goal amount must not be zero")]
+    InvalidGoal {},
+
+    #[error("Plan amount and interval must not be zero")]
+    InvalidPlan {},
+
+    #[error("Lock has no deposit plan")]
+    NoPlan {},
+
+    #[error("Next plan deposit is due at {next}")]
+    PlanNotDue { next: Timestamp },
+
+    #[error("Contract reached max active locks {max}")]
+    TooManyLocks { max: u64 },
+
+    #[error("Too many locks created, retry after {retry_after}")]
+    RateLimited { retry_after: Timestamp },
+
+    #[error("Lock is in the owner grace period")]
+    GracePeriodActive {},
+
+    #[error("Block time is behind the recorded high-water mark {high_water}")]
+    TimeRegressed { high_water: Timestamp },
+
+    #[error("Emergency withdraw is disabled")]
+    EmergencyDisabled {},
+
+    #[error("No emergency withdraw announced")]
+    NoEmergencyAnnounced {},
+
+    #[error("Emergency withdraw is executable at {executable_at}")]
+    EmergencyTimelocked { executable_at: Timestamp },
+
+    #[error("Sender can't be its own referrer")]
+    SelfReferral {},
+
+    #[error("Contract {recipient} is not in the recipient allowlist")]
+    RecipientNotAllowed { recipient: String },
+
+    #[error("Token {token} is frozen")]
+    TokenFrozen { token: String },
+
+    #[error("Unlocks of frozen token {token} are held")]
+    UnlocksHeld { token: String },
+
+    #[error("Contract is paused")]
+    Paused { until: Option<Timestamp> },
+
     #[error("Lock id already in use")]
     AlreadyInUse {},
+
+    #[error("Create time must be before expire and not after the block time")]
+    InvalidCreateTime {},
+
+    #[error("No successor lockbox is set")]
+    NoSuccessor {},
+
+    #[error("Lock is not opted in to rollover")]
+    RolloverNotSet {},
+
+    #[error("Rollover needs a lock of native coins only or a single cw20 token")]
+    RolloverUnsupported {},
+
+    #[error("Id prefix must end with its only '/'")]
+    InvalidIdPrefix {},
+
+    #[error("Id prefix {prefix} is reserved")]
+    ReservedIdPrefix { prefix: String },
+
+    #[error("Too many native coins, max {max}")]
+    TooManyNativeCoins { max: u32 },
+
+    #[error("Too many cw20 tokens, max {max}")]
+    TooManyCw20Tokens { max: u32 },
+
+    #[error("Airdrop must be funded with a single token")]
+    InvalidAirdropFunds {},
+
+    #[error("Stream must be funded with a single token")]
+    InvalidStreamFunds {},
+
+    #[error("Stream rate must not be zero")]
+    ZeroStreamRate {},
+
+    #[error("Only {withdrawable} streamed tokens can be withdrawn")]
+    InsufficientStreamed { withdrawable: Uint128 },
+
+    #[error("Increase below the minimum of {min} per asset")]
+    IncreaseTooSmall { min: Uint128 },
+
+    #[error("Lock holds a different asset")]
+    AssetMismatch {},
+
+    #[error("Stream can only be topped up with the stream token")]
+    StreamDenomMismatch {},
+
+    #[error("No stream rate waiting for approval")]
+    NoPendingStreamRate {},
+
+    #[error("Vesting group must be funded with a single token")]
+    InvalidVestingFunds {},
+
+    #[error("Vesting amount must not be zero and the cliff must be within start and end")]
+    InvalidVestingSchedule {},
+
+    #[error("Only {available} unallocated tokens are left in the vesting group")]
+    InsufficientVestingPool { available: Uint128 },
+
+    #[error("No vested tokens to claim")]
+    NothingVested {},
+
+    #[error("Invalid merkle hash length")]
+    WrongLength {},
+
+    #[error("Merkle proof verification failed")]
+    VerificationFailed {},
+
+    #[error("Airdrop already claimed")]
+    AirdropClaimed {},
+
+    #[error("Not enough airdrop funds left")]
+    AirdropExhausted {},
+
+    #[error("Unknown reply id {id}")]
+    UnknownReply { id: u64 },
+
+    #[error("Lock {id} has a swap and must be unlocked alone")]
+    SwapNotBatchable { id: String },
+
+    #[error("Batch must not be empty nor contain Batch or Receive msgs")]
+    InvalidBatchMsg {},
+
+    #[error("Batch can unlock a single lock with a swap")]
+    TooManyBatchSwaps {},
+
+    #[error("Deposit hook msg must contain the amount placeholder")]
+    InvalidHookTemplate {},
+
+    #[error("Deposit hook rejected by destination contract")]
+    HookRejected {},
+
+    #[error("Amount exceeds the withdraw allowance of {allowance}")]
+    AllowanceExceeded { allowance: Uint128 },
+
+    #[error("Lock holds only {available} of the asset")]
+    InsufficientLockBalance { available: Uint128 },
+
+    #[error("Escheat is disabled")]
+    EscheatDisabled {},
+
+    #[error("Lock is abandoned at {abandoned_at}")]
+    LockNotAbandoned { abandoned_at: Timestamp },
+
+    #[error("Next warning or escheat is allowed at {next}")]
+    WarningTooSoon { next: Timestamp },
+
+    #[error("Escheat requires {required} warnings")]
+    MissingWarnings { required: u32 },
+
+    #[error("Campaign must be funded with a single token")]
+    InvalidCampaignFunds {},
+
+    #[error("Campaign deadline has passed")]
+    CampaignEnded {},
+
+    #[error("Campaign accepts extensions until its deadline")]
+    CampaignActive {},
+
+    #[error("Not enough campaign bonus left")]
+    CampaignExhausted {},
+
+    #[error("Lock is not eligible for the campaign bonus")]
+    ExtensionNotEligible {},
+
+    #[error("Bonus is claimable at {claimable_at}")]
+    BonusNotClaimable { claimable_at: Timestamp },
+
+    #[error("Lock binding other parties or running owner provided msgs can't be sold")]
+    LockNotTransferable {},
+
+    #[error("Listing price must not be zero")]
+    InvalidPrice {},
+
+    #[error("Lock is not listed for sale")]
+    LockNotListed {},
+
+    #[error("Lock funds or expire changed since it was listed")]
+    ListingChanged {},
+
+    #[error("Payment must be exactly {price} of the listing asset")]
+    InvalidPayment { price: Uint128 },
+
+    #[error("Lock is collateral of {lender}")]
+    LockEncumbered { lender: String },
+
+    #[error("Reentrant execution")]
+    ReentrantExecution {},
+
+    #[error("Lock accepts a single asset")]
+    MultipleDenoms {},
+
+    #[error("Goal can only be raised above the lock funds before expire")]
+    GoalLoosened {},
+
+    #[error("Denom is held by live locks")]
+    DenomLocked {},
+
+    #[error("Lock owner did not approve the lender")]
+    LenderNotApproved {},
+
+    #[error("Escheat requires abandon_after of at least {min} seconds and a warning")]
+    InvalidEscheatConfig { min: u64 },
+
+    #[error("Joint and beneficiary locks can't be escheated")]
+    EscheatUnsupported {},
+
+    #[error("Joint and beneficiary locks don't support allowances")]
+    AllowanceUnsupported {},
+
+    #[error("Beneficiary locks can't have a deposit hook or swap")]
+    BeneficiaryPayout {},
+
+    #[error("Converter paid {received} of the {expected} new tokens")]
+    ConversionShortfall {
+        expected: Uint128,
+        received: Uint128,
+    },
+
+    #[error("Lock indexes are being rebuilt, send RebuildLockIndexes")]
+    IndexRebuildPending {},
+
+    #[error("Batch funds can only be attached to a single msg taking funds")]
+    AmbiguousBatchFunds {},
+
+    #[error("Private locks can't be listed for sale")]
+    PrivateLockListing {},
+
+    #[error("Controller is mapped to another owner")]
+    ControllerInUse {},
+
+    #[error("Channel {channel_id} isn't a connected notify channel")]
+    UnknownNotifyChannel { channel_id: String },
+
+    #[error("Notify channels must be unordered and use the {version} version")]
+    InvalidNotifyChannel { version: String },
+}
+
+impl ContractError {
+    /// Variant name, such as LockNotExpired, or the StdError variant name for Std errors
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ContractError::Std(err) => match err {
+                StdError::GenericErr { .. } => "GenericErr",
+                StdError::NotFound { .. } => "NotFound",
+                StdError::ParseErr { .. } => "ParseErr",
+                StdError::SerializeErr { .. } => "SerializeErr",
+                StdError::Overflow { .. } => "Overflow",
+                StdError::DivideByZero { .. } => "DivideByZero",
+                _ => "Std",
+            },
+            ContractError::Hex(_) => "Hex",
+            ContractError::Unauthorized {} => "Unauthorized",
+            ContractError::EmptyBalance {} => "EmptyBalance",
+            ContractError::LowExpired {} => "LowExpired",
+            ContractError::HighExpired {} => "HighExpired",
+            ContractError::InsufficientFunds { .. } => "InsufficientFunds",
+            ContractError::MissingExpire {} => "MissingExpire",
+            ContractError::RemainingTooLow { .. } => "RemainingTooLow",
+            ContractError::LockNotFound { .. } => "LockNotFound",
+            ContractError::LockUnauthorized { .. } => "LockUnauthorized",
+            ContractError::LockNotExpired {} => "LockNotExpired",
+            ContractError::AssetNotLocked {} => "AssetNotLocked",
+            ContractError::LockExpired {} => "LockExpired",
+            ContractError::InvalidThreshold {} => "InvalidThreshold",
+            ContractError::ThresholdNotMet {} => "ThresholdNotMet",
+            ContractError::NotJointLock {} => "NotJointLock",
+            ContractError::BeneficiaryAlreadySet {} => "BeneficiaryAlreadySet",
+            ContractError::NoteTooLarge { .. } => "NoteTooLarge",
+            ContractError::CallbackTooLarge { .. } => "CallbackTooLarge",
+            ContractError::GuardianRejected {} => "GuardianRejected",
+            ContractError::ReminderTooLarge { .. } => "ReminderTooLarge",
+            ContractError::ReasonTooLarge { .. } => "ReasonTooLarge",
+            ContractError::NoHardshipRequest {} => "NoHardshipRequest",
+            ContractError::HardshipApproved {} => "HardshipApproved",
+            ContractError::NoBeneficiary {} => "NoBeneficiary",
+            ContractError::NoBeneficiaryProposal {} => "NoBeneficiaryProposal",
+            ContractError::InvariantViolated { .. } => "InvariantViolated",
+            ContractError::AdapterInUse {} => "AdapterInUse",
+            ContractError::YieldToLock {} => "YieldToLock",
+            ContractError::InvalidGoal {} => "InvalidGoal",
+            ContractError::InvalidPlan {} => "InvalidPlan",
+            ContractError::NoPlan {} => "NoPlan",
+            ContractError::PlanNotDue { .. } => "PlanNotDue",
+            ContractError::TooManyLocks { .. } => "TooManyLocks",
+            ContractError::RateLimited { .. } => "RateLimited",
+            ContractError::GracePeriodActive {} => "GracePeriodActive",
+            ContractError::TimeRegressed { .. } => "TimeRegressed",
+            ContractError::EmergencyDisabled {} => "EmergencyDisabled",
+            ContractError::NoEmergencyAnnounced {} => "NoEmergencyAnnounced",
+            ContractError::EmergencyTimelocked { .. } => "EmergencyTimelocked",
+            ContractError::SelfReferral {} => "SelfReferral",
+            ContractError::RecipientNotAllowed { .. } => "RecipientNotAllowed",
+            ContractError::TokenFrozen { .. } => "TokenFrozen",
+            ContractError::UnlocksHeld { .. } => "UnlocksHeld",
+            ContractError::Paused { .. } => "Paused",
+            ContractError::AlreadyInUse {} => "AlreadyInUse",
+            ContractError::InvalidCreateTime {} => "InvalidCreateTime",
+            ContractError::NoSuccessor {} => "NoSuccessor",
+            ContractError::RolloverNotSet {} => "RolloverNotSet",
+            ContractError::RolloverUnsupported {} => "RolloverUnsupported",
+            ContractError::InvalidIdPrefix {} => "InvalidIdPrefix",
+            ContractError::ReservedIdPrefix { .. } => "ReservedIdPrefix",
+            ContractError::TooManyNativeCoins { .. } => "TooManyNativeCoins",
+            ContractError::TooManyCw20Tokens { .. } => "TooManyCw20Tokens",
+            ContractError::InvalidAirdropFunds {} => "InvalidAirdropFunds",
+            ContractError::InvalidStreamFunds {} => "InvalidStreamFunds",
+            ContractError::ZeroStreamRate {} => "ZeroStreamRate",
+            ContractError::InsufficientStreamed { .. } => "InsufficientStreamed",
+            ContractError::IncreaseTooSmall { .. } => "IncreaseTooSmall",
+            ContractError::AssetMismatch {} => "AssetMismatch",
+            ContractError::StreamDenomMismatch {} => "StreamDenomMismatch",
+            ContractError::NoPendingStreamRate {} => "NoPendingStreamRate",
+            ContractError::InvalidVestingFunds {} => "InvalidVestingFunds",
+            ContractError::InvalidVestingSchedule {} => "InvalidVestingSchedule",
+            ContractError::InsufficientVestingPool { .. } => "InsufficientVestingPool",
+            ContractError::NothingVested {} => "NothingVested",
+            ContractError::WrongLength {} => "WrongLength",
+            ContractError::VerificationFailed {} => "VerificationFailed",
+            ContractError::AirdropClaimed {} => "AirdropClaimed",
+            ContractError::AirdropExhausted {} => "AirdropExhausted",
+            ContractError::UnknownReply { .. } => "UnknownReply",
+            ContractError::SwapNotBatchable { .. } => "SwapNotBatchable",
+            ContractError::InvalidBatchMsg {} => "InvalidBatchMsg",
+            ContractError::TooManyBatchSwaps {} => "TooManyBatchSwaps",
+            ContractError::InvalidHookTemplate {} => "InvalidHookTemplate",
+            ContractError::HookRejected {} => "HookRejected",
+            ContractError::AllowanceExceeded { .. } => "AllowanceExceeded",
+            ContractError::InsufficientLockBalance { .. } => "InsufficientLockBalance",
+            ContractError::EscheatDisabled {} => "EscheatDisabled",
+            ContractError::LockNotAbandoned { .. } => "LockNotAbandoned",
+            ContractError::WarningTooSoon { .. } => "WarningTooSoon",
+            ContractError::MissingWarnings { .. } => "MissingWarnings",
+            ContractError::InvalidCampaignFunds {} => "InvalidCampaignFunds",
+            ContractError::CampaignEnded {} => "CampaignEnded",
+            ContractError::CampaignActive {} => "CampaignActive",
+            ContractError::CampaignExhausted {} => "CampaignExhausted",
+            ContractError::ExtensionNotEligible {} => "ExtensionNotEligible",
+            ContractError::BonusNotClaimable { .. } => "BonusNotClaimable",
+            ContractError::LockNotTransferable {} => "LockNotTransferable",
+            ContractError::InvalidPrice {} => "InvalidPrice",
+            ContractError::LockNotListed {} => "LockNotListed",
+            ContractError::ListingChanged {} => "ListingChanged",
+            ContractError::InvalidPayment { .. } => "InvalidPayment",
+            ContractError::LockEncumbered { .. } => "LockEncumbered",
+            ContractError::ReentrantExecution {} => "ReentrantExecution",
+            ContractError::MultipleDenoms {} => "MultipleDenoms",
+            ContractError::GoalLoosened {} => "GoalLoosened",
+            ContractError::DenomLocked {} => "DenomLocked",
+            ContractError::LenderNotApproved {} => "LenderNotApproved",
+            ContractError::InvalidEscheatConfig { .. } => "InvalidEscheatConfig",
+            ContractError::EscheatUnsupported {} => "EscheatUnsupported",
+            ContractError::AllowanceUnsupported {} => "AllowanceUnsupported",
+            ContractError::BeneficiaryPayout {} => "BeneficiaryPayout",
+            ContractError::ConversionShortfall { .. } => "ConversionShortfall",
+            ContractError::IndexRebuildPending {} => "IndexRebuildPending",
+            ContractError::AmbiguousBatchFunds {} => "AmbiguousBatchFunds",
+            ContractError::PrivateLockListing {} => "PrivateLockListing",
+            ContractError::ControllerInUse {} => "ControllerInUse",
+            ContractError::UnknownNotifyChannel { .. } => "UnknownNotifyChannel",
+            ContractError::InvalidNotifyChannel { .. } => "InvalidNotifyChannel",
+        }
+    }
 }
@@ -0,0 +1,345 @@
+use cosmwasm_std::{attr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage};
+
+use crate::contract::{load_lock, lock_data, remove_lock, send_tokens, sub_locked_total};
+use crate::error::ContractError;
+use crate::events::{self, asset_attrs, lock_attrs};
+use crate::freeze::check_release;
+use crate::history::record_activity;
+use crate::lending::check_unencumbered;
+use crate::msg::{EscheatConfigMsg, EscheatConfigResponse};
+use crate::state::{
+    locks, AbandonWarnings, ActivityKind, EscheatConfig, Lock, ESCHEAT_CONFIG, STATE,
+};
+use crate::yield_adapter::withdraw_msgs;
+
+/// Locks can't be escheated within a year of expire
+pub const MIN_ABANDON_AFTER: u64 = 365 * 24 * 60 * 60;
+
+/// Enables or disables escheat of abandoned locks, only callable by owner
+pub fn try_update_escheat_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<EscheatConfigMsg>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match config {
+        Some(config) => {
+            let config = EscheatConfig {
+                abandon_after: config.abandon_after,
+                destination: deps.api.addr_validate(&config.destination)?,
+                warnings: config.warnings,
+                warning_interval: config.warning_interval,
+            };
+            check_config(&config)?;
+            ESCHEAT_CONFIG.save(deps.storage, &config)?;
+        }
+        None => ESCHEAT_CONFIG.remove(deps.storage),
+    }
+
+    Ok(Response {
+        attributes: vec![attr("action", "update_escheat_config")],
+        ..Response::default()
+    })
+}
+
+/// Records a warning on an abandoned lock, warnings issued before the lock
+/// was last abandoned, such as before an extend, are discarded
+pub fn try_warn_abandoned(
+    deps: DepsMut,
+    env: Env,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let config = load_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&owner)?;
+    let key = (&owner, id.to_owned());
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+    check_escheatable(&lock)?;
+
+    let now = env.block.time;
+    let abandoned_at = lock.expire.plus_seconds(config.abandon_after);
+    if now < abandoned_at {
+        return Err(ContractError::LockNotAbandoned { abandoned_at });
+    }
+    let warnings = match lock.abandon_warnings.take() {
+        Some(warnings) if warnings.first >= abandoned_at => {
+            let next = warnings.last.plus_seconds(config.warning_interval);
+            if now < next {
+                return Err(ContractError::WarningTooSoon { next });
+            }
+            AbandonWarnings {
+                count: warnings.count + 1,
+                last: now,
+                ..warnings
+            }
+        }
+        _ => AbandonWarnings {
+            count: 1,
+            first: now,
+            last: now,
+        },
+    };
+    lock.abandon_warnings = Some(warnings.clone());
+    locks().save(deps.storage, key, &lock)?;
+
+    let mut attributes = lock_attrs(events::ABANDON_WARNING, &owner, &id, lock.expire);
+    attributes.push(attr("warning", warnings.count));
+    attributes.push(attr("required", config.warnings));
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
+
+/// Sends the funds of an abandoned lock to the escheat destination once it was
+/// warned enough times, the last warning at least warning_interval ago
+pub fn try_escheat(
+    deps: DepsMut,
+    env: Env,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let config = load_config(deps.storage)?;
+    let owner = deps.api.addr_validate(&owner)?;
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+    check_unencumbered(&lock)?;
+    check_escheatable(&lock)?;
+
+    let abandoned_at = lock.expire.plus_seconds(config.abandon_after);
+    if env.block.time < abandoned_at {
+        return Err(ContractError::LockNotAbandoned { abandoned_at });
+    }
+    match &lock.abandon_warnings {
+        Some(warnings) if warnings.first >= abandoned_at && warnings.count >= config.warnings => {
+            let next = warnings.last.plus_seconds(config.warning_interval);
+            if env.block.time < next {
+                return Err(ContractError::WarningTooSoon { next });
+            }
+        }
+        _ => {
+            return Err(ContractError::MissingWarnings {
+                required: config.warnings,
+            })
+        }
+    }
+
+    let mut funds = lock.funds.clone();
+    check_release(deps.storage, &funds)?;
+    sub_locked_total(deps.storage, &owner, env.block.height, &funds)?;
+    record_activity(
+        deps.storage,
+        &owner,
+        &id,
+        ActivityKind::Withdraw,
+        funds.clone(),
+        env.block.time,
+    )?;
+    remove_lock(deps.storage, &owner, &id)?;
+
+    let mut attributes = lock_attrs(events::ESCHEAT, &owner, &id, lock.expire);
+    attributes.extend(asset_attrs(&funds));
+    attributes.push(attr("destination", &config.destination));
+
+    let mut messages = withdraw_msgs(
+        deps.storage,
+        &deps.querier,
+        &env.contract.address,
+        &mut lock,
+        &mut funds,
+    )?;
+    messages.append(&mut send_tokens(&config.destination, &funds)?);
+
+    Ok(Response {
+        messages,
+        attributes,
+        data: Some(lock_data(&owner, id, lock.expire)?),
+        ..Response::default()
+    })
+}
+
+/// Loads the config, configs saved before the minimums were enforced are ignored
+fn load_config(storage: &dyn Storage) -> Result<EscheatConfig, ContractError> {
+    let config = ESCHEAT_CONFIG
+        .may_load(storage)?
+        .ok_or(ContractError::EscheatDisabled {})?;
+    check_config(&config)?;
+
+    Ok(config)
+}
+
+fn check_config(config: &EscheatConfig) -> Result<(), ContractError> {
+    if config.abandon_after < MIN_ABANDON_AFTER || config.warnings == 0 {
+        return Err(ContractError::InvalidEscheatConfig {
+            min: MIN_ABANDON_AFTER,
+        });
+    }
+    Ok(())
+}
+
+/// Joint owners and beneficiaries never agreed to escheat the lock to the destination
+fn check_escheatable(lock: &Lock) -> Result<(), ContractError> {
+    if lock.joint.is_some() || lock.beneficiary.is_some() {
+        return Err(ContractError::EscheatUnsupported {});
+    }
+    Ok(())
+}
+
+pub fn query_escheat_config(deps: Deps) -> StdResult<EscheatConfigResponse> {
+    let config = ESCHEAT_CONFIG.may_load(deps.storage)?;
+
+    Ok(EscheatConfigResponse {
+        config: config.map(|config| EscheatConfigMsg {
+            abandon_after: config.abandon_after,
+            destination: config.destination.into(),
+            warnings: config.warnings,
+            warning_interval: config.warning_interval,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, BankMsg, Timestamp};
+
+    #[test]
+    fn escheat_abandoned_lock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        // a beneficiary lock pays its beneficiary only
+        let msg = ExecuteMsg::Lock {
+            id: "2".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetBeneficiary {
+            id: "2".into(),
+            beneficiary: "heir".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let warn = ExecuteMsg::WarnAbandoned {
+            owner: "anyone".into(),
+            id: "1".into(),
+        };
+        let escheat = ExecuteMsg::Escheat {
+            owner: "anyone".into(),
+            id: "1".into(),
+        };
+        let keeper = mock_info("keeper", &[]);
+        let res = execute(deps.as_mut(), env.clone(), keeper.clone(), warn.clone());
+        match res {
+            Err(ContractError::EscheatDisabled {}) => {}
+            _ => panic!("Must return EscheatDisabled error"),
+        }
+
+        // the owner can't escheat locks right after expire
+        let config = EscheatConfigMsg {
+            abandon_after: 1000,
+            destination: "pool".into(),
+            warnings: 0,
+            warning_interval: 100,
+        };
+        let msg = ExecuteMsg::UpdateEscheatConfig {
+            config: Some(config.clone()),
+        };
+        let res = execute(deps.as_mut(), env.clone(), owner.clone(), msg);
+        match res {
+            Err(ContractError::InvalidEscheatConfig { min }) => assert_eq!(MIN_ABANDON_AFTER, min),
+            _ => panic!("Must return InvalidEscheatConfig error"),
+        }
+
+        let config = EscheatConfigMsg {
+            abandon_after: MIN_ABANDON_AFTER,
+            warnings: 2,
+            ..config
+        };
+        let msg = ExecuteMsg::UpdateEscheatConfig {
+            config: Some(config.clone()),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), owner, msg).unwrap();
+        let value = query_escheat_config(deps.as_ref()).unwrap();
+        assert_eq!(Some(config), value.config);
+
+        let abandoned = 100 + MIN_ABANDON_AFTER;
+        env.block.time = Timestamp::from_seconds(500);
+        let res = execute(deps.as_mut(), env.clone(), keeper.clone(), warn.clone());
+        match res {
+            Err(ContractError::LockNotAbandoned { abandoned_at }) => {
+                assert_eq!(Timestamp::from_seconds(abandoned), abandoned_at)
+            }
+            _ => panic!("Must return LockNotAbandoned error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(abandoned);
+        let _res = execute(deps.as_mut(), env.clone(), keeper.clone(), warn.clone()).unwrap();
+        let msg = ExecuteMsg::WarnAbandoned {
+            owner: "anyone".into(),
+            id: "2".into(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), keeper.clone(), msg);
+        match res {
+            Err(ContractError::EscheatUnsupported {}) => {}
+            _ => panic!("Must return EscheatUnsupported error"),
+        }
+        let res = execute(deps.as_mut(), env.clone(), keeper.clone(), escheat.clone());
+        match res {
+            Err(ContractError::MissingWarnings { required }) => assert_eq!(2, required),
+            _ => panic!("Must return MissingWarnings error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(abandoned + 50);
+        let res = execute(deps.as_mut(), env.clone(), keeper.clone(), warn.clone());
+        match res {
+            Err(ContractError::WarningTooSoon { next }) => {
+                assert_eq!(Timestamp::from_seconds(abandoned + 100), next)
+            }
+            _ => panic!("Must return WarningTooSoon error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(abandoned + 100);
+        let _res = execute(deps.as_mut(), env.clone(), keeper.clone(), warn).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), keeper.clone(), escheat.clone());
+        match res {
+            Err(ContractError::WarningTooSoon { .. }) => {}
+            _ => panic!("Must return WarningTooSoon error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(abandoned + 200);
+        let res = execute(deps.as_mut(), env, keeper, escheat).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "pool".into(),
+                amount: coins(2, "token"),
+            }
+            .into()]
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use cosmwasm_std::{attr, Addr, Attribute, Timestamp};
+
+use crate::balance::GenericBalance;
+
+pub use lockbox_interface::events::*;
+
+/// Leading attributes of every msg acting on a single lock
+pub fn lock_attrs(action: &str, owner: &Addr, id: &str, expire: Timestamp) -> Vec<Attribute> {
+    vec![
+        attr(ACTION, action),
+        attr(OWNER, owner),
+        attr(LOCK_ID, id),
+        attr(EXPIRE, expire),
+    ]
+}
+
+/// An asset and amount pair per native coin and cw20 token in funds
+pub fn asset_attrs(funds: &GenericBalance) -> Vec<Attribute> {
+    let native = funds
+        .native
+        .iter()
+        .map(|coin| (coin.denom.to_string(), coin.amount));
+    let cw20 = funds
+        .cw20
+        .iter()
+        .map(|coin| (coin.address.to_string(), coin.amount));
+
+    native
+        .chain(cw20)
+        .flat_map(|(asset, amount)| vec![attr(ASSET, asset), attr(AMOUNT, amount)])
+        .collect()
+}
@@ -0,0 +1,159 @@
+use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, StdError};
+
+use crate::error::ContractError;
+
+/// Splits the sent coins into the declared amount and the excess to refund,
+/// coins of undeclared denoms are refunded in full. The declared amount is merged per denom
+/// and sorted, as the bank module expects of the coins sent on release
+pub fn split_excess(
+    funds: Vec<Coin>,
+    declared: Option<Vec<Coin>>,
+) -> Result<(Vec<Coin>, Vec<Coin>), ContractError> {
+    let declared = match declared {
+        Some(declared) => merge_coins(declared)?,
+        None => return Ok((funds, vec![])),
+    };
+
+    let mut excess = funds;
+    for coin in declared.iter() {
+        let sent = excess
+            .iter_mut()
+            .find(|sent| sent.denom == coin.denom && sent.amount >= coin.amount)
+            .ok_or_else(|| ContractError::InsufficientFunds {
+                denom: coin.denom.clone(),
+            })?;
+        sent.amount = sent
+            .amount
+            .checked_sub(coin.amount)
+            .map_err(StdError::from)?;
+    }
+    excess.retain(|coin| !coin.amount.is_zero());
+
+    Ok((declared, excess))
+}
+
+/// Coins without zero amounts, summed per denom and sorted by denom
+fn merge_coins(coins: Vec<Coin>) -> Result<Vec<Coin>, ContractError> {
+    let mut merged: Vec<Coin> = vec![];
+    for coin in coins.into_iter().filter(|coin| !coin.amount.is_zero()) {
+        match merged.iter_mut().find(|exist| exist.denom == coin.denom) {
+            Some(exist) => {
+                exist.amount = exist
+                    .amount
+                    .checked_add(coin.amount)
+                    .map_err(StdError::from)?
+            }
+            None => merged.push(coin),
+        }
+    }
+    merged.sort_by(|a, b| a.denom.cmp(&b.denom));
+    Ok(merged)
+}
+
+pub fn refund_msg(to: &Addr, excess: Vec<Coin>) -> Option<CosmosMsg> {
+    if excess.is_empty() {
+        return None;
+    }
+    Some(
+        BankMsg::Send {
+            to_address: to.into(),
+            amount: excess,
+        }
+        .into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use crate::state::locks;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coin, coins, Timestamp};
+
+    #[test]
+    fn refund_excess_funds() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let lock = |amount: Vec<Coin>| ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: Some(amount),
+        };
+        let info = mock_info("anyone", &[coin(10, "token"), coin(3, "other")]);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            lock(coins(11, "token")),
+        );
+        match res {
+            Err(ContractError::InsufficientFunds { denom }) => assert_eq!("token", denom),
+            _ => panic!("Must return InsufficientFunds error"),
+        }
+
+        let res = execute(deps.as_mut(), env, info, lock(coins(4, "token"))).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: vec![coin(6, "token"), coin(3, "other")],
+            }
+            .into()]
+        );
+    }
+
+    #[test]
+    fn merge_declared_amounts() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let lock = |id: &str, amount: Vec<Coin>| ExecuteMsg::Lock {
+            id: id.into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: Some(amount),
+        };
+        let info = mock_info("anyone", &[coin(3, "other"), coin(8, "token")]);
+        let amount = vec![coin(4, "token"), coin(5, "token")];
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), lock("1", amount));
+        match res {
+            Err(ContractError::InsufficientFunds { denom }) => assert_eq!("token", denom),
+            _ => panic!("Must return InsufficientFunds error"),
+        }
+
+        let amount = vec![coin(4, "token"), coin(1, "other"), coin(4, "token")];
+        let res = execute(deps.as_mut(), env, info, lock("1", amount)).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(2, "other"),
+            }
+            .into()]
+        );
+        let lock = locks()
+            .load(&deps.storage, (&Addr::unchecked("anyone"), "1".into()))
+            .unwrap();
+        assert_eq!(vec![coin(1, "other"), coin(8, "token")], lock.funds.native);
+    }
+}
@@ -0,0 +1,123 @@
+use cosmwasm_std::{Deps, Order, StdResult, Timestamp};
+use cw_storage_plus::{Bound, PrimaryKey};
+
+use crate::contract::next_cursor;
+use crate::msg::{ExpirationCursor, ExpiringLocksResponse, LockData};
+use crate::state::{locks, reminder_bound, split_lock_pk};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Locks of any owner expiring before time, ordered by expire, for keepers running UnlockFor
+pub fn query_locks_expiring_before(
+    deps: Deps,
+    time: Timestamp,
+    start_after: Option<ExpirationCursor>,
+    limit: Option<u32>,
+) -> StdResult<ExpiringLocksResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let min = match start_after {
+        Some(cursor) => {
+            let owner = deps.api.addr_validate(&cursor.owner)?;
+            let pk = (&owner, cursor.id).joined_key();
+            Some(Bound::exclusive(reminder_bound(
+                cursor.expire.seconds(),
+                pk,
+            )))
+        }
+        None => None,
+    };
+    let max = Bound::exclusive(reminder_bound(time.seconds(), vec![]));
+
+    let locks: StdResult<Vec<_>> = locks()
+        .idx
+        .expire
+        .range(deps.storage, min, Some(max), Order::Ascending)
+        .take(limit + 1)
+        .map(|item| {
+            let (pk, lock) = item?;
+            let (owner, id) = split_lock_pk(&pk)?;
+            Ok(LockData {
+                owner: owner.into(),
+                id,
+                expire: lock.expire,
+                payouts: vec![],
+            })
+        })
+        .collect();
+
+    let mut locks = locks?;
+    let next_start_after = next_cursor(&mut locks, limit, |lock| ExpirationCursor {
+        expire: lock.expire,
+        owner: lock.owner.clone(),
+        id: lock.id.clone(),
+    });
+
+    Ok(ExpiringLocksResponse {
+        locks,
+        next_start_after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+
+    #[test]
+    fn locks_expiring_before() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 100_000,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        for (owner, id, expire) in &[("bob", "1", 300), ("alice", "2", 100), ("bob", "3", 900)] {
+            let msg = ExecuteMsg::Lock {
+                id: id.to_string(),
+                expire: Some(Timestamp::from_seconds(*expire)),
+                referrer: None,
+                amount: None,
+            };
+            let info = mock_info(owner, &coins(2, "token"));
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+
+        let msg = QueryMsg::LocksExpiringBefore {
+            time: Timestamp::from_seconds(900),
+            start_after: None,
+            limit: Some(1),
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: ExpiringLocksResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![LockData {
+                owner: "alice".into(),
+                id: "2".into(),
+                expire: Timestamp::from_seconds(100),
+                payouts: vec![],
+            }],
+            value.locks
+        );
+
+        let value = query_locks_expiring_before(
+            deps.as_ref(),
+            Timestamp::from_seconds(900),
+            value.next_start_after,
+            None,
+        )
+        .unwrap();
+        assert_eq!(None, value.next_start_after);
+        let ids: Vec<_> = value.locks.into_iter().map(|lock| lock.id).collect();
+        assert_eq!(vec!["1".to_string()], ids);
+    }
+}
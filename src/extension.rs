@@ -0,0 +1,425 @@
+use cosmwasm_std::{attr, Addr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Timestamp};
+use cw20::{Balance, Denom};
+use cw_storage_plus::U64Key;
+
+use crate::balance::{denom_balance, single_token, GenericBalance};
+use crate::contract::{load_lock, send_tokens};
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::ExtensionCampaignResponse;
+use crate::params::load_max_lock_time;
+use crate::state::{
+    locks, ExtensionBonus, ExtensionCampaign, EXTENSION_BONUSES, EXTENSION_CAMPAIGNS,
+    EXTENSION_CAMPAIGN_COUNT, STATE,
+};
+
+/// Offers the attached token as a bonus to lockers extending to min_expire before deadline,
+/// only callable by owner
+#[allow(clippy::too_many_arguments)]
+pub fn try_create_extension_campaign(
+    deps: DepsMut,
+    env: &Env,
+    balance: Balance,
+    sender: &Addr,
+    asset: Denom,
+    bonus_bps: u64,
+    min_expire: Timestamp,
+    deadline: Timestamp,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if deadline <= env.block.time {
+        return Err(ContractError::CampaignEnded {});
+    }
+    if min_expire <= env.block.time {
+        return Err(ContractError::LowExpired {});
+    }
+    let (bonus_denom, amount) = match single_token(&balance) {
+        Some(token) if !token.1.is_zero() => token,
+        _ => return Err(ContractError::InvalidCampaignFunds {}),
+    };
+    let asset = match asset {
+        Denom::Cw20(address) => Denom::Cw20(deps.api.addr_validate(address.as_str())?),
+        denom => denom,
+    };
+
+    let campaign_id = EXTENSION_CAMPAIGN_COUNT
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1;
+    let campaign = ExtensionCampaign {
+        asset,
+        bonus_bps,
+        min_expire,
+        deadline,
+        bonus_denom,
+        remaining: amount,
+    };
+    EXTENSION_CAMPAIGNS.save(deps.storage, U64Key::from(campaign_id), &campaign)?;
+    EXTENSION_CAMPAIGN_COUNT.save(deps.storage, &campaign_id)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::CREATE_EXTENSION_CAMPAIGN),
+            attr("campaign", campaign_id),
+            attr(events::AMOUNT, amount),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Extends the lock to expire, reserving bonus_bps of its locked asset from the campaign
+pub fn try_accept_extension(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    campaign_id: u64,
+    id: String,
+    expire: Timestamp,
+) -> Result<Response, ContractError> {
+    let mut campaign = EXTENSION_CAMPAIGNS.load(deps.storage, U64Key::from(campaign_id))?;
+    let now = env.block.time;
+    if now >= campaign.deadline {
+        return Err(ContractError::CampaignEnded {});
+    }
+
+    let key = (&info.sender, id.to_owned());
+    let bonus_key = (U64Key::from(campaign_id), key.clone());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+    let accepted = EXTENSION_BONUSES.may_load(deps.storage, bonus_key.clone())?;
+    if accepted.is_some() || lock.expire >= campaign.min_expire || expire < campaign.min_expire {
+        return Err(ContractError::ExtensionNotEligible {});
+    }
+    let state = STATE.load(deps.storage)?;
+    let max_lock_time = load_max_lock_time(deps.storage, &deps.querier, &env, &state)?;
+    let duration = expire
+        .seconds()
+        .checked_sub(now.seconds())
+        .ok_or(ContractError::LowExpired {})?;
+    if duration >= max_lock_time {
+        return Err(ContractError::HighExpired {});
+    }
+
+    let bonus = lock
+        .funds
+        .amount_of(&campaign.asset)
+        .multiply_ratio(campaign.bonus_bps, 10_000u64);
+    if bonus.is_zero() {
+        return Err(ContractError::ExtensionNotEligible {});
+    }
+    campaign.remaining = campaign
+        .remaining
+        .checked_sub(bonus)
+        .map_err(|_| ContractError::CampaignExhausted {})?;
+    EXTENSION_CAMPAIGNS.save(deps.storage, U64Key::from(campaign_id), &campaign)?;
+
+    lock.expire = expire;
+    locks().save(deps.storage, key, &lock)?;
+    let bonus_info = ExtensionBonus {
+        amount: bonus,
+        claimable_at: expire,
+        lock_create: lock.create,
+    };
+    EXTENSION_BONUSES.save(deps.storage, bonus_key, &bonus_info)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::ACCEPT_EXTENSION),
+            attr("campaign", campaign_id),
+            attr(events::OWNER, info.sender),
+            attr(events::LOCK_ID, id),
+            attr(events::EXPIRE, expire),
+            attr("bonus", bonus),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Pays the bonus of an accepted extension once the extended expire is reached. The lock must
+/// still be held, a lock released early (by its goal or a hardship) forfeits the bonus
+pub fn try_claim_extension_bonus(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    campaign_id: u64,
+    id: String,
+) -> Result<Response, ContractError> {
+    let campaign = EXTENSION_CAMPAIGNS.load(deps.storage, U64Key::from(campaign_id))?;
+    let bonus_key = (U64Key::from(campaign_id), (&info.sender, id.to_owned()));
+    let bonus = EXTENSION_BONUSES.load(deps.storage, bonus_key.clone())?;
+    if env.block.time < bonus.claimable_at {
+        return Err(ContractError::BonusNotClaimable {
+            claimable_at: bonus.claimable_at,
+        });
+    }
+    let lock = load_lock(deps.storage, &info.sender, &id)?;
+    if lock.create != bonus.lock_create || lock.expire < bonus.claimable_at {
+        return Err(ContractError::ExtensionNotEligible {});
+    }
+    EXTENSION_BONUSES.remove(deps.storage, bonus_key);
+
+    let funds = GenericBalance::from(denom_balance(&campaign.bonus_denom, bonus.amount));
+    Ok(Response {
+        messages: send_tokens(&info.sender, &funds)?,
+        attributes: vec![
+            attr(events::ACTION, events::CLAIM_EXTENSION_BONUS),
+            attr("campaign", campaign_id),
+            attr(events::OWNER, info.sender),
+            attr(events::LOCK_ID, id),
+            attr(events::AMOUNT, bonus.amount),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Refunds the unreserved bonus to the owner after the deadline, accepted bonuses stay claimable
+pub fn try_close_extension_campaign(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    campaign_id: u64,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut campaign = EXTENSION_CAMPAIGNS.load(deps.storage, U64Key::from(campaign_id))?;
+    if env.block.time < campaign.deadline {
+        return Err(ContractError::CampaignActive {});
+    }
+
+    let refund = std::mem::take(&mut campaign.remaining);
+    EXTENSION_CAMPAIGNS.save(deps.storage, U64Key::from(campaign_id), &campaign)?;
+    let messages = if refund.is_zero() {
+        vec![]
+    } else {
+        let funds = GenericBalance::from(denom_balance(&campaign.bonus_denom, refund));
+        send_tokens(&info.sender, &funds)?
+    };
+
+    Ok(Response {
+        messages,
+        attributes: vec![
+            attr(events::ACTION, events::CLOSE_EXTENSION_CAMPAIGN),
+            attr("campaign", campaign_id),
+            attr("refund", refund),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn query_extension_campaign(
+    deps: Deps,
+    campaign_id: u64,
+) -> StdResult<ExtensionCampaignResponse> {
+    let campaign = EXTENSION_CAMPAIGNS.load(deps.storage, U64Key::from(campaign_id))?;
+
+    Ok(ExtensionCampaignResponse {
+        campaign: campaign_id,
+        asset: campaign.asset,
+        bonus_bps: campaign.bonus_bps,
+        min_expire: campaign.min_expire,
+        deadline: campaign.deadline,
+        bonus_denom: campaign.bonus_denom,
+        remaining: campaign.remaining,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use crate::solvency::total_liabilities;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, BankMsg, Uint128};
+
+    #[test]
+    fn extension_campaign() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2000, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // 5% of the locked token, paid in reward
+        let msg = ExecuteMsg::CreateExtensionCampaign {
+            asset: Denom::Native("token".into()),
+            bonus_bps: 500,
+            min_expire: Timestamp::from_seconds(1000),
+            deadline: Timestamp::from_seconds(50),
+        };
+        let info = mock_info("creator", &coins(150, "reward"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let accept = |expire: u64| ExecuteMsg::AcceptExtension {
+            campaign: 1,
+            id: "1".into(),
+            expire: Timestamp::from_seconds(expire),
+        };
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), accept(900));
+        match res {
+            Err(ContractError::ExtensionNotEligible {}) => {}
+            _ => panic!("Must return ExtensionNotEligible error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), accept(1200)).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), accept(1300));
+        match res {
+            Err(ContractError::ExtensionNotEligible {}) => {}
+            _ => panic!("Must return ExtensionNotEligible error"),
+        }
+        let lock = locks()
+            .load(&deps.storage, (&Addr::unchecked("anyone"), "1".into()))
+            .unwrap();
+        assert_eq!(Timestamp::from_seconds(1200), lock.expire);
+        let value = query_extension_campaign(deps.as_ref(), 1).unwrap();
+        assert_eq!(Uint128::new(50), value.remaining);
+        // the reserved bonus is still owed
+        let liabilities = total_liabilities(deps.as_ref()).unwrap();
+        let reward = Denom::Native("reward".into());
+        assert_eq!(Uint128::new(150), liabilities.amount_of(&reward));
+
+        let claim = ExecuteMsg::ClaimExtensionBonus {
+            campaign: 1,
+            id: "1".into(),
+        };
+        env.block.time = Timestamp::from_seconds(1100);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), claim.clone());
+        match res {
+            Err(ContractError::BonusNotClaimable { claimable_at }) => {
+                assert_eq!(Timestamp::from_seconds(1200), claimable_at)
+            }
+            _ => panic!("Must return BonusNotClaimable error"),
+        }
+        env.block.time = Timestamp::from_seconds(1200);
+        let res = execute(deps.as_mut(), env.clone(), info, claim).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(100, "reward"),
+            }
+            .into()]
+        );
+
+        // unreserved bonus goes back to the owner
+        let msg = ExecuteMsg::CloseExtensionCampaign { campaign: 1 };
+        let res = execute(deps.as_mut(), env, mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "creator".into(),
+                amount: coins(50, "reward"),
+            }
+            .into()]
+        );
+    }
+
+    #[test]
+    fn extension_needs_held_lock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(500);
+        let lock = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(600)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2000, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), lock.clone()).unwrap();
+
+        let campaign = |min_expire: u64| ExecuteMsg::CreateExtensionCampaign {
+            asset: Denom::Native("token".into()),
+            bonus_bps: 500,
+            min_expire: Timestamp::from_seconds(min_expire),
+            deadline: Timestamp::from_seconds(2000),
+        };
+        let funds = mock_info("creator", &coins(150, "reward"));
+        let res = execute(deps.as_mut(), env.clone(), funds.clone(), campaign(400));
+        match res {
+            Err(ContractError::LowExpired {}) => {}
+            _ => panic!("Must return LowExpired error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), funds, campaign(1000)).unwrap();
+
+        // the block time passed min_expire before the deadline
+        let accept = |expire: u64| ExecuteMsg::AcceptExtension {
+            campaign: 1,
+            id: "1".into(),
+            expire: Timestamp::from_seconds(expire),
+        };
+        let sender = mock_info("anyone", &[]);
+        env.block.time = Timestamp::from_seconds(1500);
+        let res = execute(deps.as_mut(), env.clone(), sender.clone(), accept(1200));
+        match res {
+            Err(ContractError::LowExpired {}) => {}
+            _ => panic!("Must return LowExpired error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), sender.clone(), accept(3000)).unwrap();
+
+        // a hardship releases the lock early, forfeiting the bonus
+        let msg = ExecuteMsg::RequestHardshipUnlock {
+            id: "1".into(),
+            reason: "medical".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), sender.clone(), msg).unwrap();
+        let msg = ExecuteMsg::ApproveHardshipUnlock {
+            owner: "anyone".into(),
+            id: "1".into(),
+            reason: "medical".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), owner, msg).unwrap();
+        let unlock = ExecuteMsg::Unlock { id: "1".into() };
+        let _res = execute(deps.as_mut(), env.clone(), sender.clone(), unlock).unwrap();
+
+        let claim = ExecuteMsg::ClaimExtensionBonus {
+            campaign: 1,
+            id: "1".into(),
+        };
+        env.block.time = Timestamp::from_seconds(3000);
+        let res = execute(deps.as_mut(), env.clone(), sender.clone(), claim.clone());
+        match res {
+            Err(ContractError::LockNotFound { .. }) => {}
+            _ => panic!("Must return LockNotFound error"),
+        }
+        // nor can a new lock under the same id claim it
+        let lock = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(3100)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, lock).unwrap();
+        let res = execute(deps.as_mut(), env, sender, claim);
+        match res {
+            Err(ContractError::ExtensionNotEligible {}) => {}
+            _ => panic!("Must return ExtensionNotEligible error"),
+        }
+    }
+}
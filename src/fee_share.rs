@@ -0,0 +1,418 @@
+use cosmwasm_std::{
+    attr, to_binary, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Storage, WasmMsg,
+};
+use cw20::Balance;
+use cw_storage_plus::PrimaryKey;
+
+use crate::balance::GenericBalance;
+use crate::contract::{add_locked_total, check_funds_size};
+use crate::error::ContractError;
+use crate::events::{self, asset_attrs, lock_attrs};
+use crate::msg::{FeeShareExecuteMsg, FeeSharePendingResponse, FeeShareQueryMsg, FeeShareResponse};
+use crate::multi_denom::check_denom_policy;
+use crate::state::{locks, Lock, FEE_SHARES, REWARD_INDEXES, REWARD_SCALE, STATE};
+use crate::yield_adapter::deposit_msgs;
+
+/// Sets or clears the fee-share contract of an LP token, only callable by owner
+pub fn try_register_fee_share(
+    deps: DepsMut,
+    info: MessageInfo,
+    lp_token: String,
+    fee_share: Option<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    match &fee_share {
+        Some(fee_share) => {
+            let fee_share = deps.api.addr_validate(fee_share)?;
+            FEE_SHARES.save(deps.storage, &lp_token, &fee_share)?;
+        }
+        None => FEE_SHARES.remove(deps.storage, &lp_token),
+    }
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "register_fee_share"),
+            attr("lp_token", lp_token),
+            attr("fee_share", fee_share.unwrap_or_default()),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Claims the fee rewards pending for the LP token into its reward index. Locks accrue their
+/// share whenever their balance changes, or when credited with CreditLpRewards
+pub fn try_claim_lp_rewards(
+    deps: DepsMut,
+    env: Env,
+    lp_token: String,
+) -> Result<Response, ContractError> {
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let fee_share = FEE_SHARES.load(deps.storage, &lp_token)?;
+    let messages = sync_rewards(deps, &env, &lp_token)?;
+    if messages.is_empty() {
+        return Err(ContractError::EmptyBalance {});
+    }
+
+    Ok(Response {
+        messages,
+        attributes: vec![
+            attr(events::ACTION, events::CLAIM_LP_REWARDS),
+            attr("lp_token", &lp_token),
+            attr("fee_share", fee_share),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Credits the fee rewards accrued by the lock to its funds, callable by anyone.
+/// Single-asset locks, or locks that would hold too many assets, are paid out to their
+/// recipient instead, like the rewards of a lock released since they accrued
+pub fn try_credit_lp_rewards(
+    mut deps: DepsMut,
+    env: Env,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let key = (&owner, id.to_owned());
+    let lock = locks().may_load(deps.storage, key.clone())?;
+    let mut messages = vec![];
+    if let Some(lock) = &lock {
+        messages = sync_lp_rewards(deps.branch(), &env, &lock.funds)?;
+        // saving the lock accrues its rewards up to the synced indexes
+        locks().save(deps.storage, key.clone(), lock)?;
+    }
+    let owed = locks()
+        .idx
+        .lp_rewards
+        .take_owed(deps.storage, &key.joined_key())?
+        .ok_or(ContractError::EmptyBalance {})?;
+
+    let mut res = match lock {
+        Some(mut lock) => {
+            let res = credit_rewards(deps.branch(), &env, &owner, &id, &mut lock, owed.rewards)?;
+            locks().save(deps.storage, key, &lock)?;
+            res
+        }
+        None => pay_rewards(&owner, &id, &owed.recipient, owed.rewards),
+    };
+    messages.append(&mut res.messages);
+    res.messages = messages;
+
+    Ok(res)
+}
+
+/// Claims the rewards pending for the LP tokens in funds before a lock holding them changes,
+/// so the rewards paid so far are split among the token locked until now
+pub fn sync_lp_rewards(
+    mut deps: DepsMut,
+    env: &Env,
+    funds: &GenericBalance,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut messages = vec![];
+    for token in &funds.cw20 {
+        messages.append(&mut sync_rewards(deps.branch(), env, &token.address)?);
+    }
+    Ok(messages)
+}
+
+/// Claims the pending rewards of the LP token into its index, split among the token locked
+/// so far. Returns the fee-share Claim message, if anything was pending
+fn sync_rewards(deps: DepsMut, env: &Env, lp_token: &Addr) -> StdResult<Vec<CosmosMsg>> {
+    let fee_share = match FEE_SHARES.may_load(deps.storage, lp_token)? {
+        Some(fee_share) => fee_share,
+        None => return Ok(vec![]),
+    };
+    let pending: FeeSharePendingResponse = deps.querier.query_wasm_smart(
+        &fee_share,
+        &FeeShareQueryMsg::Pending {
+            address: env.contract.address.to_string(),
+        },
+    )?;
+    let rewards: Vec<Coin> = pending
+        .rewards
+        .into_iter()
+        .filter(|coin| !coin.amount.is_zero())
+        .collect();
+    let total = locks().idx.lp_rewards.total(deps.storage, lp_token)?;
+    if rewards.is_empty() || total.is_zero() {
+        return Ok(vec![]);
+    }
+
+    let mut index = load_index(deps.storage, lp_token)?;
+    for coin in rewards {
+        let per_token = coin.amount.multiply_ratio(REWARD_SCALE, total);
+        match index.iter_mut().find(|exist| exist.denom == coin.denom) {
+            Some(exist) => exist.amount += per_token,
+            None => index.push(Coin {
+                denom: coin.denom,
+                amount: per_token,
+            }),
+        }
+    }
+    REWARD_INDEXES.save(deps.storage, lp_token, &index)?;
+
+    Ok(vec![WasmMsg::Execute {
+        contract_addr: fee_share.to_string(),
+        msg: to_binary(&FeeShareExecuteMsg::Claim {})?,
+        send: vec![],
+    }
+    .into()])
+}
+
+/// Adds the owed rewards to the lock funds, single-asset locks and locks that would hold
+/// too many assets, or more than one under the multi denom policy, are paid out to their
+/// recipient instead
+fn credit_rewards(
+    deps: DepsMut,
+    env: &Env,
+    owner: &Addr,
+    id: &str,
+    lock: &mut Lock,
+    owed: Vec<Coin>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    let mut funds = lock.funds.clone();
+    funds.add_tokens(Balance::from(owed.clone()));
+    if lock.single_asset
+        || check_funds_size(&state, &funds).is_err()
+        || check_denom_policy(&state, &funds).is_err()
+    {
+        return Ok(pay_rewards(owner, id, lock.recipient(owner), owed));
+    }
+
+    let mut attributes = lock_attrs(events::CREDIT_LP_REWARDS, owner, id, lock.expire);
+    attributes.extend(asset_attrs(&Balance::from(owed.clone()).into()));
+    lock.funds = funds;
+    // credited coins with a yield adapter are deposited like any other locked coin
+    let credited = Balance::from(owed);
+    let messages = deposit_msgs(deps.storage, lock, &credited)?;
+    add_locked_total(deps.storage, owner, env.block.height, credited)?;
+
+    Ok(Response {
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+/// Sends the rewards of the lock to recipient instead of crediting them
+fn pay_rewards(owner: &Addr, id: &str, recipient: &Addr, rewards: Vec<Coin>) -> Response {
+    let mut attributes = vec![
+        attr(events::ACTION, events::PAY_LP_REWARDS),
+        attr(events::OWNER, owner),
+        attr(events::LOCK_ID, id),
+        attr("recipient", recipient),
+    ];
+    attributes.extend(asset_attrs(&Balance::from(rewards.clone()).into()));
+
+    Response {
+        messages: vec![BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: rewards,
+        }
+        .into()],
+        attributes,
+        ..Response::default()
+    }
+}
+
+fn load_index(storage: &dyn Storage, lp_token: &Addr) -> StdResult<Vec<Coin>> {
+    Ok(REWARD_INDEXES
+        .may_load(storage, lp_token)?
+        .unwrap_or_default())
+}
+
+pub fn query_fee_share(deps: Deps, lp_token: String) -> StdResult<FeeShareResponse> {
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    let fee_share = FEE_SHARES.may_load(deps.storage, &lp_token)?;
+
+    Ok(FeeShareResponse {
+        fee_share: fee_share.map(String::from),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::mock_querier::mock_dependencies_with_wasm;
+    use crate::msg::{ExecuteMsg, InstantiateMsg, ReceiveMsg};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, Binary, ContractResult, Timestamp, Uint128};
+    use cw20::Cw20ReceiveMsg;
+
+    /// Fee-share query handler with amount of fee pending
+    fn pending(amount: u128) -> impl Fn(&str, &Binary) -> ContractResult<Binary> {
+        move |contract, msg| {
+            assert_eq!("fee_share", contract);
+            let res = match from_binary(msg).unwrap() {
+                FeeShareQueryMsg::Pending { .. } => to_binary(&FeeSharePendingResponse {
+                    rewards: coins(amount, "fee"),
+                }),
+            };
+            ContractResult::Ok(res.unwrap())
+        }
+    }
+
+    #[test]
+    fn claim_lp_rewards() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        deps.querier.update_wasm(pending(100));
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::RegisterFeeShare {
+            lp_token: "lp".into(),
+            fee_share: Some("fee_share".into()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), owner, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let lock = |sender: &str, amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_binary(&ReceiveMsg::Lock {
+                    id: "1".into(),
+                    expire: Some(Timestamp::from_seconds(100)),
+                    referrer: None,
+                })
+                .unwrap(),
+            })
+        };
+        let claim_msg: CosmosMsg = WasmMsg::Execute {
+            contract_addr: "fee_share".into(),
+            msg: to_binary(&FeeShareExecuteMsg::Claim {}).unwrap(),
+            send: vec![],
+        }
+        .into();
+        let info = mock_info("lp", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), lock("alice", 3)).unwrap();
+        assert!(res.messages.is_empty());
+        // the rewards pending before bob locks are claimed for alice alone
+        let res = execute(deps.as_mut(), env.clone(), info, lock("bob", 1)).unwrap();
+        assert_eq!(vec![claim_msg.clone()], res.messages);
+
+        let msg = ExecuteMsg::ClaimLpRewards {
+            lp_token: "lp".into(),
+        };
+        let anyone = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), anyone.clone(), msg.clone()).unwrap();
+        assert_eq!(vec![claim_msg.clone()], res.messages);
+
+        deps.querier.update_wasm(pending(0));
+        let credit = |owner: &str| ExecuteMsg::CreditLpRewards {
+            owner: owner.into(),
+            id: "1".into(),
+        };
+        for owner in &["alice", "bob"] {
+            let _res = execute(deps.as_mut(), env.clone(), anyone.clone(), credit(*owner)).unwrap();
+        }
+        let alice = Addr::unchecked("alice");
+        let lock = locks().load(&deps.storage, (&alice, "1".into())).unwrap();
+        assert_eq!(coins(174, "fee"), lock.funds.native);
+        let bob = Addr::unchecked("bob");
+        let lock = locks().load(&deps.storage, (&bob, "1".into())).unwrap();
+        assert_eq!(coins(25, "fee"), lock.funds.native);
+        // nothing accrued since
+        let res = execute(deps.as_mut(), env.clone(), anyone.clone(), credit("bob"));
+        match res {
+            Err(ContractError::EmptyBalance {}) => {}
+            _ => panic!("Must return EmptyBalance error"),
+        }
+
+        // bob unlocks with the rewards pending up to then, and claims them after
+        deps.querier.update_wasm(pending(100));
+        env.block.time = Timestamp::from_seconds(101);
+        let unlock = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), unlock).unwrap();
+        assert_eq!(claim_msg, res.messages[0]);
+        deps.querier.update_wasm(pending(0));
+        let res = execute(deps.as_mut(), env.clone(), anyone.clone(), credit("bob")).unwrap();
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".into(),
+                amount: coins(25, "fee"),
+            })],
+            res.messages
+        );
+        let res = execute(deps.as_mut(), env.clone(), anyone.clone(), msg);
+        match res {
+            Err(ContractError::EmptyBalance {}) => {}
+            _ => panic!("Must return EmptyBalance error"),
+        }
+
+        // alice only gets her own share of them
+        let _res = execute(deps.as_mut(), env, anyone, credit("alice")).unwrap();
+        let lock = locks().load(&deps.storage, (&alice, "1".into())).unwrap();
+        assert_eq!(coins(249, "fee"), lock.funds.native);
+    }
+
+    #[test]
+    fn lp_rewards_funds_size() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        deps.querier.update_wasm(pending(100));
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            max_native_coins: Some(0),
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+        let msg = ExecuteMsg::RegisterFeeShare {
+            lp_token: "lp".into(),
+            fee_share: Some("fee_share".into()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), owner, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "alice".into(),
+            amount: Uint128::new(3),
+            msg: to_binary(&ReceiveMsg::Lock {
+                id: "1".into(),
+                expire: Some(Timestamp::from_seconds(100)),
+                referrer: None,
+            })
+            .unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("lp", &[]), msg).unwrap();
+        let msg = ExecuteMsg::ClaimLpRewards {
+            lp_token: "lp".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+
+        // the lock can't hold the fee coin, it is paid out instead
+        deps.querier.update_wasm(pending(0));
+        let msg = ExecuteMsg::CreditLpRewards {
+            owner: "alice".into(),
+            id: "1".into(),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".into(),
+                amount: coins(99, "fee"),
+            })],
+            res.messages
+        );
+        let alice = Addr::unchecked("alice");
+        let lock = locks().load(&deps.storage, (&alice, "1".into())).unwrap();
+        assert!(lock.funds.native.is_empty());
+    }
+}
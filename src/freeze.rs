@@ -0,0 +1,171 @@
+use cosmwasm_std::{attr, Deps, DepsMut, MessageInfo, Order, Response, StdResult, Storage};
+use cw20::Balance;
+
+use crate::balance::GenericBalance;
+use crate::error::ContractError;
+use crate::msg::{FrozenToken, FrozenTokensResponse};
+use crate::roles::is_operator;
+use crate::state::{FROZEN_TOKENS, STATE};
+
+pub fn try_freeze_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    token: String,
+    hold_unlocks: bool,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !is_operator(deps.storage, &state, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let token = deps.api.addr_validate(&token)?;
+    FROZEN_TOKENS.save(deps.storage, &token, &hold_unlocks)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "freeze_token"),
+            attr("token", token),
+            attr("hold_unlocks", hold_unlocks),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn try_unfreeze_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    token: String,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !is_operator(deps.storage, &state, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let token = deps.api.addr_validate(&token)?;
+    FROZEN_TOKENS.remove(deps.storage, &token);
+
+    Ok(Response {
+        attributes: vec![attr("action", "unfreeze_token"), attr("token", token)],
+        ..Response::default()
+    })
+}
+
+/// Rejects deposits of frozen tokens into new or existing locks
+pub fn check_deposit(storage: &dyn Storage, balance: &Balance) -> Result<(), ContractError> {
+    if let Balance::Cw20(coin) = balance {
+        if FROZEN_TOKENS.may_load(storage, &coin.address)?.is_some() {
+            return Err(ContractError::TokenFrozen {
+                token: coin.address.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Rejects releasing funds holding a frozen token with held unlocks
+pub fn check_release(storage: &dyn Storage, funds: &GenericBalance) -> Result<(), ContractError> {
+    for coin in funds.cw20.iter() {
+        if FROZEN_TOKENS
+            .may_load(storage, &coin.address)?
+            .unwrap_or_default()
+        {
+            return Err(ContractError::UnlocksHeld {
+                token: coin.address.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+pub fn query_frozen_tokens(deps: Deps) -> StdResult<FrozenTokensResponse> {
+    let tokens: StdResult<Vec<_>> = FROZEN_TOKENS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (token, hold_unlocks) = item?;
+            Ok(FrozenToken {
+                token: String::from_utf8(token)?,
+                hold_unlocks,
+            })
+        })
+        .collect();
+
+    Ok(FrozenTokensResponse { tokens: tokens? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, ReceiveMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{to_binary, Timestamp, Uint128};
+    use cw20::Cw20ReceiveMsg;
+
+    #[test]
+    fn freeze_token() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let receive = |msg: ReceiveMsg| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "anyone".into(),
+                amount: Uint128(5),
+                msg: to_binary(&msg).unwrap(),
+            })
+        };
+        let lock = receive(ReceiveMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+        });
+        let cw20 = mock_info("cw20", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), cw20.clone(), lock).unwrap();
+
+        let msg = ExecuteMsg::FreezeToken {
+            token: "cw20".into(),
+            hold_unlocks: true,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), msg).unwrap();
+        let value = query_frozen_tokens(deps.as_ref()).unwrap();
+        assert_eq!(
+            vec![FrozenToken {
+                token: "cw20".into(),
+                hold_unlocks: true,
+            }],
+            value.tokens
+        );
+
+        let increase = receive(ReceiveMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        });
+        let res = execute(deps.as_mut(), env.clone(), cw20, increase);
+        match res {
+            Err(ContractError::TokenFrozen { token }) => assert_eq!("cw20", token),
+            _ => panic!("Must return TokenFrozen error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(101);
+        let info = mock_info("anyone", &[]);
+        let unlock = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), unlock.clone());
+        match res {
+            Err(ContractError::UnlocksHeld { token }) => assert_eq!("cw20", token),
+            _ => panic!("Must return UnlocksHeld error"),
+        }
+
+        let msg = ExecuteMsg::UnfreezeToken {
+            token: "cw20".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), owner, msg).unwrap();
+        let _res = execute(deps.as_mut(), env, info, unlock).unwrap();
+    }
+}
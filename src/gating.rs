@@ -0,0 +1,70 @@
+use cosmwasm_std::{Deps, StdError, StdResult};
+use cw20::{BalanceResponse, Denom};
+
+use crate::state::{LOCKED_TOTALS, STATE};
+
+/// Total gating token locked by address, answering the cw20 Balance query so token-gated apps
+/// can point at this contract to count locked tokens as balance
+pub fn query_balance(deps: Deps, address: String) -> StdResult<BalanceResponse> {
+    let token = STATE
+        .load(deps.storage)?
+        .gating_token
+        .ok_or_else(|| StdError::generic_err("Gating token not configured"))?;
+    let owner = deps.api.addr_validate(&address)?;
+    let totals = LOCKED_TOTALS
+        .may_load(deps.storage, &owner)?
+        .unwrap_or_default();
+
+    Ok(BalanceResponse {
+        balance: totals.amount_of(&Denom::Cw20(token)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{from_binary, to_binary, Timestamp, Uint128};
+    use cw20::Cw20ReceiveMsg;
+
+    #[test]
+    fn gating_balance() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            gating_token: Some("gov".into()),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        for (token, id) in &[("gov", "1"), ("gov", "2"), ("other", "3")] {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "anyone".into(),
+                amount: Uint128(5),
+                msg: to_binary(&ReceiveMsg::Lock {
+                    id: id.to_string(),
+                    expire: Some(Timestamp::from_seconds(100)),
+                    referrer: None,
+                })
+                .unwrap(),
+            });
+            let _res = execute(deps.as_mut(), env.clone(), mock_info(token, &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::Balance {
+            address: "anyone".into(),
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: BalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128(10), value.balance);
+
+        let value = query_balance(deps.as_ref(), "nobody".into()).unwrap();
+        assert_eq!(Uint128::zero(), value.balance);
+    }
+}
@@ -0,0 +1,222 @@
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+use cw20::Denom;
+
+use crate::contract::load_lock;
+use crate::error::ContractError;
+use crate::msg::{GoalInfo, GoalMsg};
+use crate::state::{locks, Goal, Lock, STATE};
+
+/// Sets or clears the savings goal. Before expire a goal can only be tightened, it must be
+/// above the current funds and can't be lowered or removed, so it never releases funds early
+pub fn try_set_goal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    goal: Option<GoalMsg>,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+    let state = STATE.load(deps.storage)?;
+    let expired = lock.is_expired(env.block.time, state.inclusive_expire);
+
+    let goal = match goal {
+        Some(goal) => {
+            if goal.amount.is_zero() {
+                return Err(ContractError::InvalidGoal {});
+            }
+            let denom = match goal.denom {
+                Denom::Cw20(address) => Denom::Cw20(deps.api.addr_validate(address.as_str())?),
+                denom => denom,
+            };
+            Some(Goal {
+                denom,
+                amount: goal.amount,
+                require_expire: goal.require_expire,
+            })
+        }
+        None => None,
+    };
+    if !expired && !is_tightened(&lock, goal.as_ref()) {
+        return Err(ContractError::GoalLoosened {});
+    }
+    lock.goal = goal;
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_goal"),
+            attr("from", info.sender),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+/// The new goal is not reached by the current funds and doesn't lower the current goal
+fn is_tightened(lock: &Lock, goal: Option<&Goal>) -> bool {
+    let goal = match goal {
+        Some(goal) => goal,
+        None => return false,
+    };
+    if goal.is_reached(&lock.funds) {
+        return false;
+    }
+    match &lock.goal {
+        Some(current) => {
+            current.denom == goal.denom
+                && current.amount <= goal.amount
+                && (goal.require_expire || !current.require_expire)
+        }
+        None => true,
+    }
+}
+
+pub fn to_goal_info(lock: &Lock) -> Option<GoalInfo> {
+    lock.goal.as_ref().map(|goal| GoalInfo {
+        denom: goal.denom.clone(),
+        amount: goal.amount,
+        require_expire: goal.require_expire,
+        current: lock.funds.amount_of(&goal.denom),
+        reached: goal.is_reached(&lock.funds),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, LockInfoV2, QueryMsg, QueryMsgV2};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, Timestamp, Uint128};
+
+    #[test]
+    fn unlock_on_goal() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(1000)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(6, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::SetGoal {
+            id: "1".into(),
+            goal: Some(GoalMsg {
+                denom: Denom::Native("token".into()),
+                amount: Uint128::new(10),
+                require_expire: false,
+            }),
+        };
+        let info = mock_info("anyone", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::LockNotExpired {}) => {}
+            _ => panic!("Must return LockNotExpired error"),
+        }
+
+        let increase = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &coins(4, "token")),
+            increase,
+        )
+        .unwrap();
+
+        let query_msg = QueryMsg::V2(QueryMsgV2::Lock {
+            address: "anyone".into(),
+            id: "1".into(),
+            viewing_key: None,
+            include_config: false,
+            include_token_info: false,
+        });
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let value: LockInfoV2 = from_binary(&res).unwrap();
+        let goal = value.goal.unwrap();
+        assert_eq!(Uint128::new(10), goal.current);
+        assert!(goal.reached);
+
+        // goal reached before expire
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+    }
+
+    #[test]
+    fn goal_cannot_release_early() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(1000)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(6, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let set_goal = |amount: Option<u128>| ExecuteMsg::SetGoal {
+            id: "1".into(),
+            goal: amount.map(|amount| GoalMsg {
+                denom: Denom::Native("token".into()),
+                amount: Uint128::new(amount),
+                require_expire: false,
+            }),
+        };
+        let info = mock_info("anyone", &[]);
+        // a goal already met by the funds, lowered or removed, would release them now
+        for goal in [Some(6), None].iter() {
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), set_goal(*goal));
+            match res {
+                Err(ContractError::GoalLoosened {}) => {}
+                _ => panic!("Must return GoalLoosened error"),
+            }
+        }
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), set_goal(Some(10))).unwrap();
+        for goal in [Some(8), None].iter() {
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), set_goal(*goal));
+            match res {
+                Err(ContractError::GoalLoosened {}) => {}
+                _ => panic!("Must return GoalLoosened error"),
+            }
+        }
+
+        let unlock = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), unlock);
+        match res {
+            Err(ContractError::LockNotExpired {}) => {}
+            _ => panic!("Must return LockNotExpired error"),
+        }
+
+        // any goal once expired
+        env.block.time = Timestamp::from_seconds(1001);
+        let _res = execute(deps.as_mut(), env, info, set_goal(None)).unwrap();
+    }
+}
@@ -0,0 +1,144 @@
+use cosmwasm_std::{attr, Addr, DepsMut, MessageInfo, QuerierWrapper, Response};
+
+use crate::contract::load_lock;
+use crate::error::ContractError;
+use crate::msg::{GuardianApprovalResponse, GuardianQueryMsg};
+use crate::state::{locks, Lock};
+
+/// Sets the guardian of a lock, replacing or clearing a guardian needs its approval
+pub fn try_set_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    guardian: Option<String>,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    if let Some(current) = &lock.guardian {
+        let msg = GuardianQueryMsg::ApproveGuardianChange {
+            owner: info.sender.to_string(),
+            id: id.to_owned(),
+            guardian: guardian.clone(),
+        };
+        let approval: GuardianApprovalResponse =
+            deps.querier.query_wasm_smart(current.as_str(), &msg)?;
+        if !approval.approved {
+            return Err(ContractError::GuardianRejected {});
+        }
+    }
+    lock.guardian = guardian
+        .map(|guardian| deps.api.addr_validate(&guardian))
+        .transpose()?;
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_guardian"),
+            attr("from", info.sender),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Asks the lock guardian, if any, to approve releasing the lock funds
+pub fn check_guardian(
+    querier: &QuerierWrapper,
+    owner: &Addr,
+    id: &str,
+    lock: &Lock,
+) -> Result<(), ContractError> {
+    let guardian = match &lock.guardian {
+        Some(guardian) => guardian,
+        None => return Ok(()),
+    };
+
+    let msg = GuardianQueryMsg::ApproveUnlock {
+        owner: owner.to_string(),
+        id: id.to_owned(),
+    };
+    let approval: GuardianApprovalResponse = querier.query_wasm_smart(guardian.as_str(), &msg)?;
+    if !approval.approved {
+        return Err(ContractError::GuardianRejected {});
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::mock_querier::mock_dependencies_with_wasm;
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, to_binary, BankMsg, ContractResult, Timestamp};
+
+    #[test]
+    fn guarded_unlock() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        deps.querier.update_wasm(|_, _| {
+            ContractResult::Ok(to_binary(&GuardianApprovalResponse { approved: false }).unwrap())
+        });
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let set_guardian = |guardian: Option<&str>| ExecuteMsg::SetGuardian {
+            id: "1".into(),
+            guardian: guardian.map(String::from),
+        };
+        let info = mock_info("anyone", &[]);
+        let msg = set_guardian(Some("2fa"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // the guardian must approve both clearing it and the unlock
+        env.block.time = Timestamp::from_seconds(101);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), set_guardian(None));
+        match res {
+            Err(ContractError::GuardianRejected {}) => {}
+            _ => panic!("Must return GuardianRejected error"),
+        }
+        let unlock = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), unlock.clone());
+        match res {
+            Err(ContractError::GuardianRejected {}) => {}
+            _ => panic!("Must return GuardianRejected error"),
+        }
+
+        deps.querier.update_wasm(|contract_addr, msg| {
+            let approved = contract_addr == "2fa"
+                && from_binary::<GuardianQueryMsg>(msg).unwrap()
+                    == GuardianQueryMsg::ApproveUnlock {
+                        owner: "anyone".into(),
+                        id: "1".into(),
+                    };
+            ContractResult::Ok(to_binary(&GuardianApprovalResponse { approved }).unwrap())
+        });
+        let res = execute(deps.as_mut(), env, info, unlock).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(2, "token"),
+            }
+            .into()]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+use crate::contract::load_lock;
+use crate::error::ContractError;
+use crate::msg::HardshipRequest;
+use crate::state::{locks, STATE};
+
+/// Max reason size in bytes, it is loaded with the lock on every access
+pub const MAX_REASON_SIZE: usize = 256;
+
+/// Asks the contract owner for an early unlock, a new request replaces a pending one
+pub fn try_request_hardship_unlock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    check_reason(&reason)?;
+    if matches!(&lock.hardship, Some(hardship) if hardship.approval.is_some()) {
+        return Err(ContractError::HardshipApproved {});
+    }
+    lock.hardship = Some(HardshipRequest {
+        reason,
+        requested_at: env.block.time,
+        approval: None,
+    });
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "request_hardship_unlock"),
+            attr("from", info.sender),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Approves the early unlock, the lock can be unlocked as if expired
+pub fn try_approve_hardship_unlock(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    check_reason(&reason)?;
+
+    let owner = deps.api.addr_validate(&owner)?;
+    let key = (&owner, id.to_owned());
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+    let hardship = lock
+        .hardship
+        .as_mut()
+        .ok_or(ContractError::NoHardshipRequest {})?;
+    if hardship.approval.is_some() {
+        return Err(ContractError::HardshipApproved {});
+    }
+    hardship.approval = Some(reason);
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "approve_hardship_unlock"),
+            attr("owner", owner),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+fn check_reason(reason: &str) -> Result<(), ContractError> {
+    if reason.len() > MAX_REASON_SIZE {
+        return Err(ContractError::ReasonTooLarge {
+            max: MAX_REASON_SIZE,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, LockInfoV2, QueryMsg, QueryMsgV2};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, BankMsg, Timestamp};
+
+    #[test]
+    fn hardship_unlock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let approve = ExecuteMsg::ApproveHardshipUnlock {
+            owner: "anyone".into(),
+            id: "1".into(),
+            reason: "medical bills verified".into(),
+        };
+        let res = execute(deps.as_mut(), env.clone(), owner.clone(), approve.clone());
+        match res {
+            Err(ContractError::NoHardshipRequest {}) => {}
+            _ => panic!("Must return NoHardshipRequest error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(10);
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::RequestHardshipUnlock {
+            id: "1".into(),
+            reason: "medical bills".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), approve.clone());
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), owner, approve).unwrap();
+
+        let msg = QueryMsg::V2(QueryMsgV2::Lock {
+            address: "anyone".into(),
+            id: "1".into(),
+            viewing_key: None,
+            include_config: false,
+            include_token_info: false,
+        });
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LockInfoV2 = from_binary(&res).unwrap();
+        assert_eq!(
+            Some(HardshipRequest {
+                reason: "medical bills".into(),
+                requested_at: Timestamp::from_seconds(10),
+                approval: Some("medical bills verified".into()),
+            }),
+            value.hardship
+        );
+
+        // unlocked before expire
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(2, "token"),
+            }
+            .into()]
+        );
+    }
+}
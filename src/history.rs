@@ -0,0 +1,312 @@
+use cosmwasm_std::{Addr, Deps, Order, StdResult, Storage, Timestamp};
+use cw20::Balance;
+use cw_storage_plus::{Bound, PrimaryKey, U64Key};
+
+use crate::balance::GenericBalance;
+use crate::contract::{next_cursor, to_cw20_coins};
+use crate::msg::{ActivityReportResponse, ActivitySummary, DepositInfo, DepositsResponse};
+use crate::privacy::{can_view, is_viewing_key};
+use crate::state::{
+    locks, Activity, ActivityKind, Deposit, ACTIVITY, ACTIVITY_COUNT, DEPOSITS, DEPOSIT_COUNT,
+};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Appends a deposit to the lock history, kept after unlock
+pub fn record_deposit(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    id: &str,
+    from: &Addr,
+    balance: Balance,
+    time: Timestamp,
+) -> StdResult<()> {
+    let key = (owner, id.to_owned());
+    let index = DEPOSIT_COUNT
+        .may_load(storage, key.clone())?
+        .unwrap_or_default();
+    let token_contract = match &balance {
+        Balance::Cw20(token) => Some(token.address.clone()),
+        Balance::Native(_) => None,
+    };
+    let deposit = Deposit {
+        from: from.clone(),
+        token_contract,
+        funds: balance.into(),
+        time,
+    };
+    DEPOSITS.save(storage, (key.clone(), U64Key::from(index)), &deposit)?;
+    DEPOSIT_COUNT.save(storage, key, &(index + 1))?;
+
+    Ok(())
+}
+
+/// Appends a lock creation, deposit or withdrawal to the owner activity
+pub fn record_activity(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    id: &str,
+    kind: ActivityKind,
+    funds: GenericBalance,
+    time: Timestamp,
+) -> StdResult<()> {
+    let index = ACTIVITY_COUNT.may_load(storage)?.unwrap_or_default();
+    let activity = Activity {
+        kind,
+        id: id.to_owned(),
+        funds,
+    };
+    let key = (U64Key::new(time.seconds()), U64Key::new(index));
+    ACTIVITY.save(storage, (owner, key), &activity)?;
+    ACTIVITY_COUNT.save(storage, &(index + 1))?;
+
+    Ok(())
+}
+
+/// Sums the activity of address from from_time (inclusive) to to_time (exclusive)
+pub fn query_activity_report(
+    deps: Deps,
+    address: String,
+    from_time: Timestamp,
+    to_time: Timestamp,
+    viewing_key: Option<String>,
+) -> StdResult<ActivityReportResponse> {
+    let owner = deps.api.addr_validate(&address)?;
+    let show_private = is_viewing_key(deps.storage, &owner, viewing_key)?;
+    let bound = |time: Timestamp| (U64Key::new(time.seconds()), U64Key::new(0)).joined_key();
+
+    let mut created = Summary::default();
+    let mut deposited = Summary::default();
+    let mut withdrawn = Summary::default();
+    for item in ACTIVITY.prefix(&owner).range(
+        deps.storage,
+        Some(Bound::inclusive(bound(from_time))),
+        Some(Bound::exclusive(bound(to_time))),
+        Order::Ascending,
+    ) {
+        let (_, activity) = item?;
+        if !show_private && is_private(deps.storage, &owner, &activity.id)? {
+            continue;
+        }
+        let summary = match activity.kind {
+            ActivityKind::Create => &mut created,
+            ActivityKind::Deposit => &mut deposited,
+            ActivityKind::Withdraw => &mut withdrawn,
+        };
+        summary.count += 1;
+        summary.funds.add_balance(activity.funds);
+    }
+
+    Ok(ActivityReportResponse {
+        address,
+        from_time,
+        to_time,
+        created: created.into(),
+        deposited: deposited.into(),
+        withdrawn: withdrawn.into(),
+    })
+}
+
+fn is_private(storage: &dyn Storage, owner: &Addr, id: &str) -> StdResult<bool> {
+    let lock = locks().may_load(storage, (owner, id.to_owned()))?;
+    Ok(lock.map_or(false, |lock| lock.private))
+}
+
+#[derive(Default)]
+struct Summary {
+    count: u64,
+    funds: GenericBalance,
+}
+
+impl From<Summary> for ActivitySummary {
+    fn from(summary: Summary) -> Self {
+        let GenericBalance { native, cw20 } = summary.funds;
+        ActivitySummary {
+            count: summary.count,
+            native_balance: native,
+            cw20_balance: to_cw20_coins(cw20),
+        }
+    }
+}
+
+pub fn query_deposits(
+    deps: Deps,
+    address: String,
+    id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    viewing_key: Option<String>,
+) -> StdResult<DepositsResponse> {
+    let owner = deps.api.addr_validate(&address)?;
+    if let Some(lock) = locks().may_load(deps.storage, (&owner, id.clone()))? {
+        if !can_view(deps.storage, &owner, &lock, viewing_key)? {
+            return Ok(DepositsResponse {
+                deposits: vec![],
+                next_start_after: None,
+            });
+        }
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|index| Bound::exclusive(U64Key::from(index).wrapped));
+
+    let deposits: StdResult<Vec<_>> = DEPOSITS
+        .prefix((&owner, id))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .map(|item| {
+            let (key, deposit) = item?;
+            let mut index = [0u8; 8];
+            index.copy_from_slice(&key);
+            Ok(to_deposit_info(u64::from_be_bytes(index), deposit))
+        })
+        .collect();
+
+    let mut deposits = deposits?;
+    let next_start_after = next_cursor(&mut deposits, limit, |deposit| deposit.index);
+
+    Ok(DepositsResponse {
+        deposits,
+        next_start_after,
+    })
+}
+
+fn to_deposit_info(index: u64, deposit: Deposit) -> DepositInfo {
+    let GenericBalance { native, cw20 } = deposit.funds;
+    DepositInfo {
+        index,
+        from: deposit.from.into(),
+        token_contract: deposit.token_contract.map(String::from),
+        time: deposit.time,
+        native_balance: native,
+        cw20_balance: to_cw20_coins(cw20),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+
+    #[test]
+    fn deposit_history() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        for amount in 1..=3 {
+            env.block.time = Timestamp::from_seconds(amount * 10);
+            let msg = ExecuteMsg::IncreaseLock {
+                id: "1".into(),
+                min_remaining: None,
+            };
+            let info = mock_info("anyone", &coins(amount.into(), "token"));
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+
+        let msg = QueryMsg::Deposits {
+            address: "anyone".into(),
+            id: "1".into(),
+            start_after: Some(0),
+            limit: Some(1),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: DepositsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.deposits,
+            vec![DepositInfo {
+                index: 1,
+                from: "anyone".into(),
+                token_contract: None,
+                time: Timestamp::from_seconds(20),
+                native_balance: coins(2, "token"),
+                cw20_balance: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn activity_report() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(10);
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        let info = mock_info("anyone", &coins(3, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(101);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let _res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+
+        let report = |from_time: u64, to_time: u64| {
+            let msg = QueryMsg::ActivityReport {
+                address: "anyone".into(),
+                from_time: Timestamp::from_seconds(from_time),
+                to_time: Timestamp::from_seconds(to_time),
+                viewing_key: None,
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<ActivityReportResponse>(&res).unwrap()
+        };
+        let summary = |count: u64, amount: u128| ActivitySummary {
+            count,
+            native_balance: if count == 0 {
+                vec![]
+            } else {
+                coins(amount, "token")
+            },
+            cw20_balance: vec![],
+        };
+
+        let value = report(0, 101);
+        assert_eq!(summary(1, 2), value.created);
+        assert_eq!(summary(1, 3), value.deposited);
+        assert_eq!(summary(0, 0), value.withdrawn);
+
+        let value = report(10, 200);
+        assert_eq!(summary(0, 0), value.created);
+        assert_eq!(summary(1, 3), value.deposited);
+        assert_eq!(summary(1, 5), value.withdrawn);
+    }
+}
@@ -0,0 +1,241 @@
+use cosmwasm_std::{
+    attr, coins, to_binary, Binary, CosmosMsg, Deps, DepsMut, MessageInfo, Response, StdResult,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Denom};
+
+use crate::allowlist::check_recipient;
+use crate::balance::{denom_balance, GenericBalance};
+use crate::contract::{load_lock, push_payout};
+use crate::error::ContractError;
+use crate::msg::{DepositHookMsg, DepositHookQueryMsg, PayoutInfo, SimulateDepositResponse};
+use crate::state::{locks, DepositHook};
+
+/// Replaced by the deposited amount in the hook msg template
+pub const AMOUNT_PLACEHOLDER: &str = "{{amount}}";
+
+pub fn try_set_deposit_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    hook: Option<DepositHookMsg>,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    // the hook contract would receive the beneficiary payout
+    if hook.is_some() && lock.beneficiary.is_some() {
+        return Err(ContractError::BeneficiaryPayout {});
+    }
+    lock.deposit_hook = match hook {
+        Some(hook) => Some(validate_hook(deps.as_ref(), &lock.funds, hook)?),
+        None => None,
+    };
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_deposit_hook"),
+            attr("from", info.sender),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Checks the template and asks the destination to simulate a deposit of the locked amount
+fn validate_hook(
+    deps: Deps,
+    funds: &GenericBalance,
+    hook: DepositHookMsg,
+) -> Result<DepositHook, ContractError> {
+    if !hook.msg.contains(AMOUNT_PLACEHOLDER) {
+        return Err(ContractError::InvalidHookTemplate {});
+    }
+
+    let contract = deps.api.addr_validate(&hook.contract)?;
+    check_recipient(deps, &contract)?;
+    let denom = match hook.denom {
+        Denom::Cw20(address) => Denom::Cw20(deps.api.addr_validate(address.as_str())?),
+        denom => denom,
+    };
+
+    let amount = funds.amount_of(&denom);
+    let simulation: SimulateDepositResponse = deps.querier.query_wasm_smart(
+        contract.as_str(),
+        &DepositHookQueryMsg::SimulateDeposit {
+            denom: denom.clone(),
+            amount,
+            msg: render_msg(&hook.msg, amount),
+        },
+    )?;
+    if !simulation.accepted {
+        return Err(ContractError::HookRejected {});
+    }
+
+    Ok(DepositHook {
+        contract,
+        denom,
+        msg: hook.msg,
+    })
+}
+
+fn render_msg(template: &str, amount: Uint128) -> Binary {
+    Binary::from(
+        template
+            .replace(AMOUNT_PLACEHOLDER, &amount.to_string())
+            .into_bytes(),
+    )
+}
+
+/// Takes the hook denom out of funds and returns the deposit message
+pub fn deposit_msg(
+    hook: &DepositHook,
+    funds: &mut GenericBalance,
+    payouts: &mut Vec<PayoutInfo>,
+) -> StdResult<Option<CosmosMsg>> {
+    let amount = match funds.remove_denom(&hook.denom) {
+        Some(amount) => amount,
+        None => return Ok(None),
+    };
+    push_payout(
+        payouts,
+        &hook.contract,
+        denom_balance(&hook.denom, amount).into(),
+    );
+
+    let msg = render_msg(&hook.msg, amount);
+    let exec = match &hook.denom {
+        Denom::Native(denom) => WasmMsg::Execute {
+            contract_addr: hook.contract.to_string(),
+            msg,
+            send: coins(amount.u128(), denom),
+        },
+        Denom::Cw20(address) => WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: hook.contract.to_string(),
+                amount,
+                msg: Some(msg),
+            })?,
+            send: vec![],
+        },
+    };
+
+    Ok(Some(exec.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::mock_querier::mock_dependencies_with_wasm;
+    use crate::msg::{ExecuteMsg, InstantiateMsg, LockData};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{from_binary, BankMsg, Coin, ContractResult, Timestamp};
+
+    #[test]
+    fn unlock_with_deposit_hook() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        deps.querier.update_wasm(|contract_addr, msg| {
+            let DepositHookQueryMsg::SimulateDeposit { amount, .. } = from_binary(msg).unwrap();
+            let accepted = contract_addr == "vault" && amount == Uint128::new(2);
+            ContractResult::Ok(to_binary(&SimulateDepositResponse { accepted }).unwrap())
+        });
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock funds
+        let funds = vec![Coin::new(2, "token"), Coin::new(5, "earth")];
+        let info = mock_info("anyone", &funds);
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // template must contain amount
+        let mut hook = DepositHookMsg {
+            contract: "vault".into(),
+            denom: Denom::Native("token".into()),
+            msg: r#"{"deposit":{}}"#.into(),
+        };
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::SetDepositHook {
+            id: "1".into(),
+            hook: Some(hook.clone()),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::InvalidHookTemplate {}) => {}
+            _ => panic!("Must return InvalidHookTemplate error"),
+        }
+
+        // destination must accept the deposit
+        hook.msg = r#"{"deposit":{"amount":"{{amount}}"}}"#.into();
+        hook.contract = "other".into();
+        let msg = ExecuteMsg::SetDepositHook {
+            id: "1".into(),
+            hook: Some(hook.clone()),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::HookRejected {}) => {}
+            _ => panic!("Must return HookRejected error"),
+        }
+
+        hook.contract = "vault".into();
+        let msg = ExecuteMsg::SetDepositHook {
+            id: "1".into(),
+            hook: Some(hook),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(401);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                WasmMsg::Execute {
+                    contract_addr: "vault".into(),
+                    msg: Binary::from(br#"{"deposit":{"amount":"2"}}"#),
+                    send: coins(2, "token"),
+                }
+                .into(),
+                BankMsg::Send {
+                    to_address: "anyone".into(),
+                    amount: coins(5, "earth"),
+                }
+                .into(),
+            ]
+        );
+
+        // data lists the deposit apart from the owner payout
+        let data: LockData = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(
+            vec![
+                PayoutInfo {
+                    recipient: "vault".into(),
+                    native: coins(2, "token"),
+                    cw20: vec![],
+                },
+                PayoutInfo {
+                    recipient: "anyone".into(),
+                    native: coins(5, "earth"),
+                    cw20: vec![],
+                },
+            ],
+            data.payouts
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Notify protocol, compiled with the ibc feature. Counterparty contracts open an unordered
+//! channel with the NOTIFY_VERSION version and the owner picks the one notified with
+//! SetNotifyChannel. Packets only flow out, received packets are acknowledged as errors
+use cosmwasm_std::{
+    attr, entry_point, from_binary, to_binary, CosmosMsg, DepsMut, Empty, Env, IbcAcknowledgement,
+    IbcBasicResponse, IbcChannel, IbcMsg, IbcOrder, IbcPacket, IbcReceiveResponse, StdResult,
+};
+
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::{NotifyAck, NotifyPacket};
+use crate::state::{NOTIFY_CHANNEL, NOTIFY_CHANNELS};
+
+pub const NOTIFY_VERSION: &str = "lockbox-notify-1";
+/// Seconds a notification waits to be relayed before it times out
+pub const NOTIFY_TIMEOUT: u64 = 24 * 60 * 60;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    channel: IbcChannel,
+) -> Result<(), ContractError> {
+    check_channel(&channel)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    deps: DepsMut,
+    _env: Env,
+    channel: IbcChannel,
+) -> Result<IbcBasicResponse, ContractError> {
+    check_channel(&channel)?;
+    let channel_id = channel.endpoint.channel_id;
+    NOTIFY_CHANNELS.save(deps.storage, &channel_id, &Empty {})?;
+
+    Ok(IbcBasicResponse {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr(events::ACTION, events::CONNECT_NOTIFY_CHANNEL),
+            attr(events::CHANNEL_ID, channel_id),
+        ],
+    })
+}
+
+/// Stops the notifications if the owner picked the closed channel
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    deps: DepsMut,
+    _env: Env,
+    channel: IbcChannel,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel_id = channel.endpoint.channel_id;
+    NOTIFY_CHANNELS.remove(deps.storage, &channel_id);
+    if NOTIFY_CHANNEL.may_load(deps.storage)? == Some(channel_id.clone()) {
+        NOTIFY_CHANNEL.remove(deps.storage);
+    }
+
+    Ok(IbcBasicResponse {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr(events::ACTION, events::CLOSE_NOTIFY_CHANNEL),
+            attr(events::CHANNEL_ID, channel_id),
+        ],
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    _deps: DepsMut,
+    _env: Env,
+    _packet: IbcPacket,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let ack = NotifyAck::Error("Notify channels only send packets".into());
+
+    Ok(IbcReceiveResponse {
+        acknowledgement: to_binary(&ack)?,
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![],
+    })
+}
+
+/// Notifications are best effort, a failed one is recorded but not sent again
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    ack: IbcAcknowledgement,
+) -> Result<IbcBasicResponse, ContractError> {
+    let success = matches!(from_binary(&ack.acknowledgement), Ok(NotifyAck::Result(_)));
+
+    Ok(IbcBasicResponse {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr(events::ACTION, events::NOTIFY_ACK),
+            attr(events::CHANNEL_ID, ack.original_packet.src.channel_id),
+            attr("success", success),
+        ],
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    packet: IbcPacket,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse {
+        submessages: vec![],
+        messages: vec![],
+        attributes: vec![
+            attr(events::ACTION, events::NOTIFY_TIMEOUT),
+            attr(events::CHANNEL_ID, packet.src.channel_id),
+        ],
+    })
+}
+
+pub(crate) fn send_packet(
+    env: &Env,
+    channel_id: String,
+    packet: &NotifyPacket,
+) -> StdResult<CosmosMsg> {
+    Ok(IbcMsg::SendPacket {
+        channel_id,
+        data: to_binary(packet)?,
+        timeout_block: None,
+        timeout_timestamp: Some(env.block.time.plus_seconds(NOTIFY_TIMEOUT)),
+    }
+    .into())
+}
+
+fn check_channel(channel: &IbcChannel) -> Result<(), ContractError> {
+    let counterparty_version = channel.counterparty_version.as_deref();
+    if channel.order != IbcOrder::Unordered
+        || channel.version != NOTIFY_VERSION
+        || counterparty_version.map_or(false, |version| version != NOTIFY_VERSION)
+    {
+        return Err(ContractError::InvalidNotifyChannel {
+            version: NOTIFY_VERSION.into(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::GenericBalance;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use crate::notify::{created_packet, query_notify_channel, released_packet};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, Addr, IbcEndpoint, Timestamp};
+
+    #[test]
+    fn notify_lock_lifecycle() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        // as if a counterparty connected the channel
+        NOTIFY_CHANNELS
+            .save(&mut deps.storage, "channel-1", &Empty {})
+            .unwrap();
+        let msg = ExecuteMsg::SetNotifyChannel {
+            channel_id: Some("channel-1".into()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), owner, msg).unwrap();
+
+        let sent = |msgs: &[CosmosMsg]| -> Vec<NotifyPacket> {
+            msgs.iter()
+                .filter_map(|msg| match msg {
+                    CosmosMsg::Ibc(IbcMsg::SendPacket {
+                        channel_id, data, ..
+                    }) => {
+                        assert_eq!("channel-1", channel_id);
+                        Some(from_binary(data).unwrap())
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let owner = Addr::unchecked("anyone");
+        let funds = GenericBalance {
+            native: coins(2, "token"),
+            cw20: vec![],
+        };
+        assert_eq!(
+            vec![created_packet(
+                &owner,
+                "1",
+                Timestamp::from_seconds(100),
+                &funds
+            )],
+            sent(&res.messages)
+        );
+
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(
+            vec![released_packet(&owner, "1", &funds)],
+            sent(&res.messages)
+        );
+
+        // closing the channel stops the notifications
+        let channel = IbcChannel {
+            endpoint: IbcEndpoint {
+                port_id: "wasm.lockbox".into(),
+                channel_id: "channel-1".into(),
+            },
+            counterparty_endpoint: IbcEndpoint {
+                port_id: "wasm.ledger".into(),
+                channel_id: "channel-7".into(),
+            },
+            order: IbcOrder::Unordered,
+            version: NOTIFY_VERSION.into(),
+            counterparty_version: Some(NOTIFY_VERSION.into()),
+            connection_id: "connection-2".into(),
+        };
+        let _res = ibc_channel_close(deps.as_mut(), mock_env(), channel).unwrap();
+        let value = query_notify_channel(deps.as_ref()).unwrap();
+        assert_eq!(None, value.channel_id);
+    }
+}
@@ -0,0 +1,185 @@
+use cosmwasm_std::{attr, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw20::Balance;
+
+use crate::contract::{expire_or_default, try_increase_lock, unlock};
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::{ControllerResponse, RemoteLockMsg};
+use crate::multi_denom::try_lock_denoms;
+use crate::state::CONTROLLERS;
+
+/// Maps controller to the sender, the owner whose locks its remote msgs run on
+pub fn try_map_controller(
+    deps: DepsMut,
+    info: MessageInfo,
+    controller: String,
+) -> Result<Response, ContractError> {
+    let controller = deps.api.addr_validate(&controller)?;
+    CONTROLLERS.update(deps.storage, &controller, |owner| match owner {
+        Some(owner) if owner != info.sender => Err(ContractError::ControllerInUse {}),
+        _ => Ok(info.sender.clone()),
+    })?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::MAP_CONTROLLER),
+            attr(events::OWNER, info.sender),
+            attr(events::CONTROLLER, controller),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn try_unmap_controller(
+    deps: DepsMut,
+    info: MessageInfo,
+    controller: String,
+) -> Result<Response, ContractError> {
+    let controller = deps.api.addr_validate(&controller)?;
+    if CONTROLLERS.may_load(deps.storage, &controller)? != Some(info.sender.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+    CONTROLLERS.remove(deps.storage, &controller);
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::UNMAP_CONTROLLER),
+            attr(events::OWNER, info.sender),
+            attr(events::CONTROLLER, controller),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Runs msg as the owner mapped to the sender, the attached funds are deposited for the owner
+pub fn try_remote_lock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: RemoteLockMsg,
+) -> Result<Response, ContractError> {
+    let owner = CONTROLLERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::Unauthorized {})?;
+
+    let mut res = match msg {
+        RemoteLockMsg::Lock { id, expire } => {
+            let expire = expire_or_default(deps.storage, &env, expire)?;
+            try_lock_denoms(deps, env, info.funds, &owner, id, expire, None)?
+        }
+        RemoteLockMsg::IncreaseLock { id } => {
+            try_increase_lock(deps, env, Balance::from(info.funds), &owner, id, None)?
+        }
+        RemoteLockMsg::Unlock { id } => unlock(deps, env, &owner, id)?,
+    };
+    res.attributes.push(attr(events::CONTROLLER, info.sender));
+
+    Ok(res)
+}
+
+pub fn query_controller(deps: Deps, controller: String) -> StdResult<ControllerResponse> {
+    let controller = deps.api.addr_validate(&controller)?;
+    let owner = CONTROLLERS.may_load(deps.storage, &controller)?;
+
+    Ok(ControllerResponse {
+        owner: owner.map(String::from),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, LockData};
+    use crate::state::locks;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, Addr, BankMsg, Timestamp};
+
+    #[test]
+    fn remote_lock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let map = ExecuteMsg::MapController {
+            controller: "voice".into(),
+        };
+        let owner = mock_info("owner", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), map.clone()).unwrap();
+        let value = query_controller(deps.as_ref(), "voice".into()).unwrap();
+        assert_eq!(Some("owner".into()), value.owner);
+
+        // a controller acts for a single owner
+        let res = execute(deps.as_mut(), mock_env(), mock_info("other", &[]), map);
+        match res {
+            Err(ContractError::ControllerInUse {}) => {}
+            _ => panic!("Must return ControllerInUse error"),
+        }
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let lock = ExecuteMsg::RemoteLock {
+            msg: RemoteLockMsg::Lock {
+                id: "1".into(),
+                expire: Some(Timestamp::from_seconds(100)),
+            },
+        };
+        let info = mock_info("stranger", &coins(2, "token"));
+        let res = execute(deps.as_mut(), env.clone(), info, lock.clone());
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        // the lock belongs to the owner, its data goes back to the home chain
+        let info = mock_info("voice", &coins(2, "token"));
+        let res = execute(deps.as_mut(), env.clone(), info, lock).unwrap();
+        let data: LockData = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(("owner", "1"), (data.owner.as_str(), data.id.as_str()));
+        let msg = ExecuteMsg::RemoteLock {
+            msg: RemoteLockMsg::IncreaseLock { id: "1".into() },
+        };
+        let info = mock_info("voice", &coins(1, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let lock = locks()
+            .load(&deps.storage, (&Addr::unchecked("owner"), "1".into()))
+            .unwrap();
+        assert_eq!(coins(3, "token"), lock.funds.native);
+
+        env.block.time = Timestamp::from_seconds(101);
+        let unlock = ExecuteMsg::RemoteLock {
+            msg: RemoteLockMsg::Unlock { id: "1".into() },
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("voice", &[]), unlock).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "owner".into(),
+                amount: coins(3, "token"),
+            }
+            .into()]
+        );
+
+        let unmap = ExecuteMsg::UnmapController {
+            controller: "voice".into(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("other", &[]),
+            unmap.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env, owner, unmap).unwrap();
+        let value = query_controller(deps.as_ref(), "voice".into()).unwrap();
+        assert_eq!(None, value.owner);
+    }
+}
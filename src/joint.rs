@@ -0,0 +1,176 @@
+use cosmwasm_std::{attr, Addr, DepsMut, Env, Response, Timestamp};
+use cw20::Balance;
+
+use crate::contract::{load_lock, try_lock, unlock};
+use crate::error::ContractError;
+use crate::state::{locks, Joint, STATE};
+
+/// Locks funds under sender requiring threshold approvals from owners to unlock
+#[allow(clippy::too_many_arguments)]
+pub fn try_lock_joint(
+    mut deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    sender: &Addr,
+    id: String,
+    expire: Timestamp,
+    owners: Vec<String>,
+    threshold: u32,
+) -> Result<Response, ContractError> {
+    let mut joint_owners: Vec<Addr> = vec![];
+    for owner in owners {
+        let owner = deps.api.addr_validate(&owner)?;
+        if !joint_owners.contains(&owner) {
+            joint_owners.push(owner);
+        }
+    }
+    if threshold == 0 || threshold as usize > joint_owners.len() {
+        return Err(ContractError::InvalidThreshold {});
+    }
+
+    let key = (sender, id.to_owned());
+    let mut res = try_lock(deps.branch(), env, balance, sender, id, expire)?;
+    let mut lock = locks().load(deps.storage, key.clone())?;
+    lock.joint = Some(Joint {
+        owners: joint_owners,
+        threshold,
+        approvals: vec![],
+    });
+    locks().save(deps.storage, key, &lock)?;
+    res.attributes.push(attr("threshold", threshold));
+
+    Ok(res)
+}
+
+/// Records the approval of a joint owner, funds are released to the lock
+/// owner once the threshold is met and the lock has expired
+pub fn try_approve_unlock(
+    deps: DepsMut,
+    env: Env,
+    sender: &Addr,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let key = (&owner, id.to_owned());
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+
+    let joint = lock.joint.as_mut().ok_or(ContractError::NotJointLock {})?;
+    if !joint.owners.contains(sender) {
+        return Err(ContractError::LockUnauthorized { id });
+    }
+    if !joint.approvals.contains(sender) {
+        joint.approvals.push(sender.clone());
+    }
+    let approved = joint.is_approved();
+    locks().save(deps.storage, key, &lock)?;
+
+    let state = STATE.load(deps.storage)?;
+    let mut res = if approved && lock.is_releasable(env.block.time, state.inclusive_expire) {
+        unlock(deps, env, &owner, id)?
+    } else {
+        Response {
+            attributes: vec![attr("action", "approve_unlock"), attr("from", &owner)],
+            ..Response::default()
+        }
+    };
+    res.attributes.push(attr("approver", sender));
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contract::{execute, instantiate};
+    use crate::error::ContractError;
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, BankMsg, CosmosMsg, Timestamp};
+
+    #[test]
+    fn joint_unlock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let info = mock_info("treasury", &coins(2, "token"));
+        let msg = ExecuteMsg::LockJoint {
+            id: "1".into(),
+            expire: Timestamp::from_seconds(400),
+            owners: vec!["alice".into(), "bob".into()],
+            threshold: 3,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::InvalidThreshold {}) => {}
+            _ => panic!("Must return InvalidThreshold error"),
+        }
+
+        let msg = ExecuteMsg::LockJoint {
+            id: "1".into(),
+            expire: Timestamp::from_seconds(400),
+            owners: vec!["alice".into(), "bob".into(), "carl".into()],
+            threshold: 2,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // plain unlock requires approvals
+        env.block.time = Timestamp::from_seconds(401);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("treasury", &[]), msg);
+        match res {
+            Err(ContractError::ThresholdNotMet {}) => {}
+            _ => panic!("Must return ThresholdNotMet error"),
+        }
+
+        let msg = ExecuteMsg::ApproveUnlock {
+            owner: "treasury".into(),
+            id: "1".into(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("eve", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::LockUnauthorized { id }) => assert_eq!("1", id),
+            _ => panic!("Must return LockUnauthorized error"),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // duplicated approvals are not counted
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let res = execute(deps.as_mut(), env, mock_info("bob", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".into(),
+                amount: coins(2, "token"),
+            })]
+        );
+    }
+}
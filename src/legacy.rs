@@ -0,0 +1,143 @@
+use cosmwasm_std::{
+    attr, Addr, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdResult, Timestamp,
+};
+use cw20::Balance;
+
+use crate::contract::create_lock;
+use crate::error::ContractError;
+use crate::msg::LegacyLockersResponse;
+use crate::namespace::check_id_prefix;
+use crate::state::{locks, LEGACY_LOCKERS, STATE};
+
+/// Add or remove the locker contracts allowed to migrate their locks in, only callable by owner
+pub fn try_update_legacy_lockers(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for contract in add {
+        let addr = deps.api.addr_validate(&contract)?;
+        LEGACY_LOCKERS.save(deps.storage, &addr, &Empty {})?;
+    }
+    for contract in remove {
+        let addr = deps.api.addr_validate(&contract)?;
+        LEGACY_LOCKERS.remove(deps.storage, &addr);
+    }
+
+    Ok(Response {
+        attributes: vec![attr("action", "update_legacy_lockers")],
+        ..Response::default()
+    })
+}
+
+/// Recreates a lock sent by a legacy locker contract, keeping its original create and expire.
+/// The lock is checked as a new lock of owner, so expire must be within max lock time
+#[allow(clippy::too_many_arguments)]
+pub fn try_migrate_in(
+    mut deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    sender: &Addr,
+    owner: String,
+    id: String,
+    create: Timestamp,
+    expire: Timestamp,
+) -> Result<Response, ContractError> {
+    if LEGACY_LOCKERS.may_load(deps.storage, sender)?.is_none() {
+        return Err(ContractError::Unauthorized {});
+    }
+    if create > env.block.time || create >= expire {
+        return Err(ContractError::InvalidCreateTime {});
+    }
+
+    let owner = deps.api.addr_validate(&owner)?;
+    check_id_prefix(deps.storage, &id, &owner)?;
+    let key = (&owner, id.to_owned());
+    let mut res = create_lock(deps.branch(), env, balance, &owner, id, expire)?;
+    let mut lock = locks().load(deps.storage, key.clone())?;
+    lock.create = create;
+    locks().save(deps.storage, key, &lock)?;
+    res.attributes.push(attr("create", create));
+    res.attributes.push(attr("legacy_locker", sender));
+
+    Ok(res)
+}
+
+pub fn query_legacy_lockers(deps: Deps) -> StdResult<LegacyLockersResponse> {
+    let lockers: Result<Vec<_>, _> = LEGACY_LOCKERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(String::from_utf8)
+        .collect();
+
+    Ok(LegacyLockersResponse { lockers: lockers? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, ReceiveMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{to_binary, Uint128};
+    use cw20::Cw20ReceiveMsg;
+
+    #[test]
+    fn migrate_in_from_legacy_locker() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1000);
+        let migrate_in = |create: u64| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "old_locker".into(),
+                amount: Uint128::new(50),
+                msg: to_binary(&ReceiveMsg::MigrateIn {
+                    owner: "anyone".into(),
+                    id: "1".into(),
+                    create: Timestamp::from_seconds(create),
+                    expire: Timestamp::from_seconds(2000),
+                })
+                .unwrap(),
+            })
+        };
+        let token = mock_info("token", &[]);
+        let res = execute(deps.as_mut(), env.clone(), token.clone(), migrate_in(400));
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        let msg = ExecuteMsg::UpdateLegacyLockers {
+            add: vec!["old_locker".into()],
+            remove: vec![],
+        };
+        let _res = execute(deps.as_mut(), env.clone(), owner, msg).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), token.clone(), migrate_in(1200));
+        match res {
+            Err(ContractError::InvalidCreateTime {}) => {}
+            _ => panic!("Must return InvalidCreateTime error"),
+        }
+        let _res = execute(deps.as_mut(), env, token, migrate_in(400)).unwrap();
+
+        let lock = locks()
+            .load(&deps.storage, (&Addr::unchecked("anyone"), "1".into()))
+            .unwrap();
+        assert_eq!(Timestamp::from_seconds(400), lock.create);
+        assert_eq!(Timestamp::from_seconds(2000), lock.expire);
+        let value = query_legacy_lockers(deps.as_ref()).unwrap();
+        assert_eq!(vec!["old_locker".to_string()], value.lockers);
+    }
+}
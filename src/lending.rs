@@ -0,0 +1,278 @@
+use cosmwasm_std::{attr, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response, StdResult};
+
+use crate::contract::load_lock;
+use crate::error::ContractError;
+use crate::events::{self, asset_attrs, lock_attrs};
+use crate::market::{check_transferable, move_lock};
+use crate::msg::LendersResponse;
+use crate::state::{locks, Lock, LENDERS, STATE};
+
+/// Add or remove the lending contracts allowed to encumber locks, only callable by owner.
+/// Removed lenders can still release or seize the locks they encumbered
+pub fn try_update_lenders(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for contract in add {
+        let addr = deps.api.addr_validate(&contract)?;
+        LENDERS.save(deps.storage, &addr, &Empty {})?;
+    }
+    for contract in remove {
+        let addr = deps.api.addr_validate(&contract)?;
+        LENDERS.remove(deps.storage, &addr);
+    }
+
+    Ok(Response {
+        attributes: vec![attr(events::ACTION, events::UPDATE_LENDERS)],
+        ..Response::default()
+    })
+}
+
+/// Sets or clears the lender allowed to encumber the lock, consumed by Encumber
+pub fn try_approve_lender(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    lender: Option<String>,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+    check_unencumbered(&lock)?;
+
+    lock.approved_lender = lender
+        .map(|lender| deps.api.addr_validate(&lender))
+        .transpose()?;
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::APPROVE_LENDER),
+            attr("from", info.sender),
+            attr("id", id),
+            attr(
+                events::LENDER,
+                lock.approved_lender.map(String::from).unwrap_or_default(),
+            ),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Marks the lock as collateral of the sender lender, blocking its release and sale until
+/// the lender releases or seizes it. The lender must be approved by the lock owner, and
+/// only locks that could be sold can be encumbered
+pub fn try_encumber(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    if LENDERS.may_load(deps.storage, &info.sender)?.is_none() {
+        return Err(ContractError::Unauthorized {});
+    }
+    let owner = deps.api.addr_validate(&owner)?;
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+    check_unencumbered(&lock)?;
+    check_transferable(&lock)?;
+    if lock.approved_lender.as_ref() != Some(&info.sender) {
+        return Err(ContractError::LenderNotApproved {});
+    }
+
+    lock.approved_lender = None;
+    lock.encumbrance = Some(info.sender.clone());
+    locks().save(deps.storage, (&owner, id.to_owned()), &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::ENCUMBER),
+            attr(events::OWNER, owner),
+            attr(events::LOCK_ID, id),
+            attr(events::LENDER, info.sender),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Releases the encumbrance, only callable by its lender
+pub fn try_release_encumbrance(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+    if lock.encumbrance.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    lock.encumbrance = None;
+    locks().save(deps.storage, (&owner, id.to_owned()), &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::RELEASE_ENCUMBRANCE),
+            attr(events::OWNER, owner),
+            attr(events::LOCK_ID, id),
+            attr(events::LENDER, info.sender),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Moves an encumbered lock to its lender under the same id, the lock keeps its expire
+pub fn try_seize_lock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+    if lock.encumbrance.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // settings the owner made while the lock was encumbered don't bind the lender
+    lock.encumbrance = None;
+    lock.unlock_callback = None;
+    lock.guardian = None;
+    lock.rollover = false;
+    lock.rollover_successor = None;
+    lock.plan = None;
+    lock.beneficiary = None;
+    lock.beneficiary_proposal = None;
+    lock.note = None;
+    lock.reminder = None;
+    lock.hardship = None;
+    lock.allowances = vec![];
+    lock.deposit_hook = None;
+    lock.abandon_warnings = None;
+    move_lock(
+        deps.storage,
+        env.block.height,
+        &owner,
+        &info.sender,
+        &id,
+        &lock,
+    )?;
+
+    let mut attributes = lock_attrs(events::SEIZE_LOCK, &owner, &id, lock.expire);
+    attributes.extend(asset_attrs(&lock.funds));
+    attributes.push(attr(events::LENDER, info.sender));
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
+
+pub(crate) fn check_unencumbered(lock: &Lock) -> Result<(), ContractError> {
+    match &lock.encumbrance {
+        Some(lender) => Err(ContractError::LockEncumbered {
+            lender: lender.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+pub fn query_lenders(deps: Deps) -> StdResult<LendersResponse> {
+    let lenders: StdResult<Vec<String>> = LENDERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|key| Ok(String::from_utf8(key)?))
+        .collect();
+
+    Ok(LendersResponse { lenders: lenders? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, BankMsg, Timestamp};
+
+    #[test]
+    fn encumbered_lock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+        let msg = ExecuteMsg::UpdateLenders {
+            add: vec!["lender".into()],
+            remove: vec![],
+        };
+        let _res = execute(deps.as_mut(), mock_env(), owner, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let encumber = ExecuteMsg::Encumber {
+            owner: "anyone".into(),
+            id: "1".into(),
+        };
+        let info = mock_info("other", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, encumber.clone());
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let lender = mock_info("lender", &[]);
+        let res = execute(deps.as_mut(), env.clone(), lender.clone(), encumber.clone());
+        match res {
+            Err(ContractError::LenderNotApproved {}) => {}
+            _ => panic!("Must return LenderNotApproved error"),
+        }
+        let msg = ExecuteMsg::ApproveLender {
+            id: "1".into(),
+            lender: Some("lender".into()),
+        };
+        let info = mock_info("anyone", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), lender.clone(), encumber).unwrap();
+
+        env.block.time = Timestamp::from_seconds(101);
+        let unlock = ExecuteMsg::Unlock { id: "1".into() };
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info, unlock.clone());
+        match res {
+            Err(ContractError::LockEncumbered { lender }) => assert_eq!("lender", lender),
+            _ => panic!("Must return LockEncumbered error"),
+        }
+
+        let msg = ExecuteMsg::SeizeLock {
+            owner: "anyone".into(),
+            id: "1".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), lender.clone(), msg).unwrap();
+        let res = execute(deps.as_mut(), env, lender, unlock).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "lender".into(),
+                amount: coins(2, "token"),
+            }
+            .into()]
+        );
+    }
+}
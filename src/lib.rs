@@ -1,5 +1,64 @@
+pub mod airdrop;
+pub mod allowance;
+pub mod allowlist;
 pub mod balance;
+pub mod batch;
+pub mod beneficiary;
+pub mod callback;
+pub mod claim;
 pub mod contract;
+pub mod emergency;
 pub mod error;
+pub mod escheat;
+pub mod events;
+pub mod excess;
+pub mod expiring;
+pub mod extension;
+pub mod fee_share;
+pub mod freeze;
+pub mod gating;
+pub mod goal;
+pub mod guardian;
+pub mod hardship;
+pub mod history;
+pub mod hook;
+#[cfg(feature = "ibc")]
+pub mod ibc;
+pub mod interchain;
+pub mod joint;
+pub mod legacy;
+pub mod lending;
+pub mod lock_id;
+pub mod lock_params;
+pub mod market;
+pub mod metadata;
+pub mod migration;
+#[cfg(test)]
+mod mock_querier;
 pub mod msg;
+pub mod multi_denom;
+pub mod namespace;
+pub mod note;
+pub mod notify;
+pub mod ownership;
+pub mod params;
+pub mod pause;
+pub mod plan;
+pub mod privacy;
+pub mod referral;
+pub mod relayer;
+pub mod reminder;
+pub mod roles;
+pub mod rollover;
+pub mod safety;
+pub mod schedule;
+pub mod simulate;
+pub mod single_asset;
+pub mod solvency;
 pub mod state;
+pub mod stream;
+pub mod swap;
+#[cfg(feature = "testing")]
+pub mod time_travel;
+pub mod vesting_group;
+pub mod yield_adapter;
@@ -0,0 +1,96 @@
+use cosmwasm_std::{attr, to_vec, Addr, DepsMut, Env, Response, StdResult, Timestamp};
+use cw20::Balance;
+use sha2::Digest;
+
+use crate::balance::GenericBalance;
+use crate::contract::try_lock;
+use crate::error::ContractError;
+use crate::state::LOCK_NONCE;
+
+/// Locks funds under an id derived by the contract, so callers don't need to
+/// coordinate unique ids. The id is returned in LockData
+pub fn try_lock_derived(
+    mut deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    sender: &Addr,
+    expire: Timestamp,
+) -> Result<Response, ContractError> {
+    let nonce = LOCK_NONCE
+        .may_load(deps.storage, sender)?
+        .unwrap_or_default();
+    let id = derive_id(sender, expire, &balance.clone().into(), nonce)?;
+
+    let mut res = try_lock(deps.branch(), env, balance, sender, id, expire)?;
+    LOCK_NONCE.save(deps.storage, sender, &(nonce + 1))?;
+    res.attributes.push(attr("nonce", nonce));
+
+    Ok(res)
+}
+
+/// Hex encoded sha256 of (sender, expire, funds, nonce)
+pub fn derive_id(
+    sender: &Addr,
+    expire: Timestamp,
+    funds: &GenericBalance,
+    nonce: u64,
+) -> StdResult<String> {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(sender.as_str().as_bytes());
+    hasher.update(expire.nanos().to_be_bytes());
+    hasher.update(to_vec(funds)?);
+    hasher.update(nonce.to_be_bytes());
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, LockData, LockInfo, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+
+    #[test]
+    fn lock_derived() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::LockDerived {
+            expire: Timestamp::from_seconds(100),
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        let first: LockData = from_binary(&res.data.unwrap()).unwrap();
+        let funds = GenericBalance {
+            native: coins(2, "token"),
+            cw20: vec![],
+        };
+        let id = derive_id(&Addr::unchecked("anyone"), first.expire, &funds, 0).unwrap();
+        assert_eq!(id, first.id);
+
+        // same msg retried gets a new id
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let second: LockData = from_binary(&res.data.unwrap()).unwrap();
+        assert_ne!(first.id, second.id);
+
+        let msg = QueryMsg::Lock {
+            address: "anyone".into(),
+            id: second.id.clone(),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LockInfo = from_binary(&res).unwrap();
+        assert_eq!(second.id, value.id);
+        assert_eq!(coins(2, "token"), value.native_balance);
+    }
+}
@@ -0,0 +1,119 @@
+use cosmwasm_std::{Coin, Deps, Env, StdResult, Timestamp};
+
+use crate::msg::{LockParamsViolation, ValidateLockParamsResponse};
+use crate::params::read_max_lock_time;
+use crate::state::STATE;
+
+/// Runs the Lock checks on expire and the native funds, at the current block time
+pub fn query_validate_lock_params(
+    deps: Deps,
+    env: Env,
+    expire: Option<Timestamp>,
+    amount_hints: Option<Vec<Coin>>,
+) -> StdResult<ValidateLockParamsResponse> {
+    let state = STATE.load(deps.storage)?;
+    let now = env.block.time;
+    let max_lock_time = read_max_lock_time(deps.storage, &deps.querier, &env, &state)?;
+    let max_expire = now.plus_seconds(max_lock_time);
+
+    let mut violations = vec![];
+    let expire = match expire {
+        Some(expire) => Some(expire),
+        None if state.default_lock_time > 0 => Some(now.plus_seconds(state.default_lock_time)),
+        None => {
+            violations.push(LockParamsViolation::MissingExpire {});
+            None
+        }
+    };
+    if let Some(expire) = expire {
+        if expire <= now {
+            violations.push(LockParamsViolation::LowExpired {});
+        } else if expire >= max_expire {
+            violations.push(LockParamsViolation::HighExpired {});
+        }
+    }
+    if let Some(funds) = amount_hints {
+        if funds.iter().all(|coin| coin.amount.is_zero()) {
+            violations.push(LockParamsViolation::EmptyBalance {});
+        }
+        if funds.len() > state.max_native_coins as usize {
+            violations.push(LockParamsViolation::TooManyNativeCoins {
+                max: state.max_native_coins,
+            });
+        }
+    }
+
+    Ok(ValidateLockParamsResponse {
+        ok: violations.is_empty(),
+        violations,
+        min_expire: now,
+        max_expire,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{instantiate, query};
+    use crate::msg::{InstantiateMsg, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+
+    #[test]
+    fn validate_lock_params() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            max_native_coins: Some(1),
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(100);
+        let msg = QueryMsg::ValidateLockParams {
+            expire: None,
+            amount_hints: Some(vec![]),
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: ValidateLockParamsResponse = from_binary(&res).unwrap();
+        assert!(!value.ok);
+        assert_eq!(
+            vec![
+                LockParamsViolation::MissingExpire {},
+                LockParamsViolation::EmptyBalance {},
+            ],
+            value.violations
+        );
+        assert_eq!(Timestamp::from_seconds(100), value.min_expire);
+        assert_eq!(Timestamp::from_seconds(3700), value.max_expire);
+
+        let mut funds = coins(2, "token");
+        funds.extend(coins(3, "other"));
+        let value = query_validate_lock_params(
+            deps.as_ref(),
+            env.clone(),
+            Some(Timestamp::from_seconds(3700)),
+            Some(funds),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                LockParamsViolation::HighExpired {},
+                LockParamsViolation::TooManyNativeCoins { max: 1 },
+            ],
+            value.violations
+        );
+
+        let value = query_validate_lock_params(
+            deps.as_ref(),
+            env,
+            Some(Timestamp::from_seconds(200)),
+            Some(coins(2, "token")),
+        )
+        .unwrap();
+        assert!(value.ok);
+    }
+}
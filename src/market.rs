@@ -0,0 +1,436 @@
+use cosmwasm_std::{
+    attr, Addr, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage, Uint128,
+};
+use cw20::{Balance, Denom};
+use cw_storage_plus::Bound;
+
+use crate::balance::{single_token, GenericBalance};
+use crate::contract::{
+    add_active_lock, add_locked_balance, load_lock, lock_data, next_cursor, remove_active_lock,
+    send_tokens, sub_locked_total, to_cw20_coins,
+};
+use crate::error::ContractError;
+use crate::events::{self, asset_attrs, lock_attrs};
+use crate::msg::{ListingInfo, ListingsResponse};
+use crate::state::{locks, Listing, Lock, LISTINGS, STATE};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Lists a lock for sale at price of asset, replacing its previous listing. The listing
+/// is only valid while the lock keeps its current funds and expire. Private locks can't be
+/// listed as the listing shows their funds
+pub fn try_list_lock(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    asset: Denom,
+    price: Uint128,
+) -> Result<Response, ContractError> {
+    let lock = load_lock(deps.storage, &info.sender, &id)?;
+    check_transferable(&lock)?;
+    if lock.private {
+        return Err(ContractError::PrivateLockListing {});
+    }
+    if price.is_zero() {
+        return Err(ContractError::InvalidPrice {});
+    }
+    let asset = match asset {
+        Denom::Cw20(address) => Denom::Cw20(deps.api.addr_validate(address.as_str())?),
+        denom => denom,
+    };
+
+    let listing = Listing {
+        asset,
+        price,
+        expire: lock.expire,
+        funds: lock.funds,
+    };
+    LISTINGS.save(deps.storage, (&info.sender, id.to_owned()), &listing)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::LIST_LOCK),
+            attr(events::OWNER, info.sender),
+            attr(events::LOCK_ID, id),
+            attr("price", price),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn try_delist_lock(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    if LISTINGS.may_load(deps.storage, key.clone())?.is_none() {
+        return Err(ContractError::LockNotListed {});
+    }
+    LISTINGS.remove(deps.storage, key);
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::DELIST_LOCK),
+            attr(events::OWNER, info.sender),
+            attr(events::LOCK_ID, id),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Pays the listing price to the owner and moves the lock to buyer under the same id
+pub fn try_buy_lock(
+    deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    buyer: &Addr,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let listing = LISTINGS
+        .may_load(deps.storage, (&owner, id.to_owned()))?
+        .ok_or(ContractError::LockNotListed {})?;
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+    if lock.expire != listing.expire || lock.funds != listing.funds {
+        return Err(ContractError::ListingChanged {});
+    }
+    check_transferable(&lock)?;
+    if single_token(&balance) != Some((listing.asset.clone(), listing.price)) {
+        return Err(ContractError::InvalidPayment {
+            price: listing.price,
+        });
+    }
+
+    // settings made by and for the previous owner don't follow the lock
+    lock.note = None;
+    lock.reminder = None;
+    lock.abandon_warnings = None;
+    lock.approved_lender = None;
+
+    LISTINGS.remove(deps.storage, (&owner, id.to_owned()));
+    move_lock(deps.storage, env.block.height, &owner, buyer, &id, &lock)?;
+
+    let mut attributes = lock_attrs(events::SELL_LOCK, &owner, &id, lock.expire);
+    attributes.extend(asset_attrs(&lock.funds));
+    attributes.extend(lock_attrs(events::BUY_LOCK, buyer, &id, lock.expire));
+    attributes.extend(asset_attrs(&lock.funds));
+    attributes.push(attr("price", listing.price));
+
+    Ok(Response {
+        messages: send_tokens(&owner, &GenericBalance::from(balance))?,
+        attributes,
+        data: Some(lock_data(buyer, id, lock.expire)?),
+        ..Response::default()
+    })
+}
+
+/// Moves lock from owner to new_owner under the same id, failing if new_owner uses the id.
+/// A lender approved by owner isn't approved for new_owner
+pub(crate) fn move_lock(
+    storage: &mut dyn Storage,
+    height: u64,
+    owner: &Addr,
+    new_owner: &Addr,
+    id: &str,
+    lock: &Lock,
+) -> Result<(), ContractError> {
+    locks().remove(storage, (owner, id.to_owned()))?;
+    locks().update(
+        storage,
+        (new_owner, id.to_owned()),
+        |existing| match existing {
+            None => Ok(Lock {
+                approved_lender: None,
+                ..lock.clone()
+            }),
+            Some(_) => Err(ContractError::AlreadyInUse {}),
+        },
+    )?;
+    remove_active_lock(storage, owner)?;
+    let state = STATE.load(storage)?;
+    add_active_lock(storage, &state, new_owner)?;
+    sub_locked_total(storage, owner, height, &lock.funds)?;
+    add_locked_balance(storage, new_owner, height, &lock.funds)?;
+
+    Ok(())
+}
+
+/// Locks binding other parties to the owner, or running owner provided msgs, can't be sold
+pub(crate) fn check_transferable(lock: &Lock) -> Result<(), ContractError> {
+    if lock.joint.is_some()
+        || lock.plan.is_some()
+        || lock.beneficiary.is_some()
+        || lock.beneficiary_proposal.is_some()
+        || lock.guardian.is_some()
+        || lock.encumbrance.is_some()
+        || lock.hardship.is_some()
+        || !lock.allowances.is_empty()
+        || lock.unlock_callback.is_some()
+        || lock.deposit_hook.is_some()
+    {
+        return Err(ContractError::LockNotTransferable {});
+    }
+    Ok(())
+}
+
+/// Funds of locks made private after listing are left out
+pub fn query_listings(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let listings: StdResult<Vec<_>> = LISTINGS
+        .prefix(&owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .map(|item| {
+            let (id, listing) = item?;
+            let id = String::from_utf8(id)?;
+            let private = locks()
+                .may_load(deps.storage, (&owner, id.clone()))?
+                .map_or(false, |lock| lock.private);
+            let GenericBalance { native, cw20 } = if private {
+                GenericBalance::default()
+            } else {
+                listing.funds
+            };
+            Ok(ListingInfo {
+                id,
+                asset: listing.asset,
+                price: listing.price,
+                expire: listing.expire,
+                native_balance: native,
+                cw20_balance: to_cw20_coins(cw20),
+            })
+        })
+        .collect();
+    let mut listings = listings?;
+    let next_start_after = next_cursor(&mut listings, limit, |listing| listing.id.clone());
+
+    Ok(ListingsResponse {
+        listings,
+        next_start_after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use crate::state::LOCK_COUNTS;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, BankMsg, Timestamp};
+
+    #[test]
+    fn buy_listed_lock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(20, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::ListLock {
+            id: "1".into(),
+            asset: Denom::Native("usd".into()),
+            price: Uint128::new(15),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+        let value = query_listings(deps.as_ref(), "anyone".into(), None, None).unwrap();
+        assert_eq!(Uint128::new(15), value.listings[0].price);
+        assert_eq!(coins(20, "token"), value.listings[0].native_balance);
+
+        let buy = ExecuteMsg::BuyLock {
+            owner: "anyone".into(),
+            id: "1".into(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("buyer", &coins(10, "usd")),
+            buy.clone(),
+        );
+        match res {
+            Err(ContractError::InvalidPayment { price }) => assert_eq!(Uint128::new(15), price),
+            _ => panic!("Must return InvalidPayment error"),
+        }
+
+        // a deposit after listing invalidates it
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        let info = mock_info("anyone", &coins(1, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        let info = mock_info("buyer", &coins(15, "usd"));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), buy.clone());
+        match res {
+            Err(ContractError::ListingChanged {}) => {}
+            _ => panic!("Must return ListingChanged error"),
+        }
+
+        let msg = ExecuteMsg::ListLock {
+            id: "1".into(),
+            asset: Denom::Native("usd".into()),
+            price: Uint128::new(15),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+        let res = execute(deps.as_mut(), env, info, buy).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(15, "usd"),
+            }
+            .into()]
+        );
+
+        let lock = locks()
+            .load(&deps.storage, (&Addr::unchecked("buyer"), "1".into()))
+            .unwrap();
+        assert_eq!(coins(21, "token"), lock.funds.native);
+        assert!(locks()
+            .may_load(&deps.storage, (&Addr::unchecked("anyone"), "1".into()))
+            .unwrap()
+            .is_none());
+        let counts = |address: &str| {
+            LOCK_COUNTS
+                .may_load(&deps.storage, &Addr::unchecked(address))
+                .unwrap()
+        };
+        assert_eq!((None, Some(1)), (counts("anyone"), counts("buyer")));
+        let value = query_listings(deps.as_ref(), "anyone".into(), None, None).unwrap();
+        assert!(value.listings.is_empty());
+    }
+
+    #[test]
+    fn private_lock_listing() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(20, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let list = ExecuteMsg::ListLock {
+            id: "1".into(),
+            asset: Denom::Native("usd".into()),
+            price: Uint128::new(15),
+        };
+        let info = mock_info("anyone", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), list.clone()).unwrap();
+
+        // the listing keeps the funds but doesn't show them once private
+        let private = |private: bool| ExecuteMsg::SetPrivate {
+            id: "1".into(),
+            private,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), private(true)).unwrap();
+        let value = query_listings(deps.as_ref(), "anyone".into(), None, None).unwrap();
+        assert_eq!(Uint128::new(15), value.listings[0].price);
+        assert!(value.listings[0].native_balance.is_empty());
+
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), list.clone());
+        match res {
+            Err(ContractError::PrivateLockListing {}) => {}
+            _ => panic!("Must return PrivateLockListing error"),
+        }
+
+        let _res = execute(deps.as_mut(), env, info, private(false)).unwrap();
+        let value = query_listings(deps.as_ref(), "anyone".into(), None, None).unwrap();
+        assert_eq!(coins(20, "token"), value.listings[0].native_balance);
+    }
+
+    #[test]
+    fn sale_drops_lender_approval() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+        let msg = ExecuteMsg::UpdateLenders {
+            add: vec!["lender".into()],
+            remove: vec![],
+        };
+        let _res = execute(deps.as_mut(), mock_env(), owner, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(20, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let seller = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::ApproveLender {
+            id: "1".into(),
+            lender: Some("lender".into()),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), seller.clone(), msg).unwrap();
+        let msg = ExecuteMsg::ListLock {
+            id: "1".into(),
+            asset: Denom::Native("usd".into()),
+            price: Uint128::new(15),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), seller, msg).unwrap();
+        let buy = ExecuteMsg::BuyLock {
+            owner: "anyone".into(),
+            id: "1".into(),
+        };
+        let info = mock_info("buyer", &coins(15, "usd"));
+        let _res = execute(deps.as_mut(), env.clone(), info, buy).unwrap();
+
+        // the lender approved by the seller can't encumber the buyer's lock
+        let encumber = ExecuteMsg::Encumber {
+            owner: "buyer".into(),
+            id: "1".into(),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("lender", &[]), encumber);
+        match res {
+            Err(ContractError::LenderNotApproved {}) => {}
+            _ => panic!("Must return LenderNotApproved error"),
+        }
+    }
+}
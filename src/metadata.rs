@@ -0,0 +1,239 @@
+use cosmwasm_std::{
+    attr, Addr, Coin, Deps, DepsMut, MessageInfo, QuerierWrapper, Response, StdResult, Storage,
+};
+use cw20::{Cw20CoinVerified, Cw20QueryMsg, TokenInfoResponse};
+
+use crate::error::ContractError;
+use crate::msg::{DenomMetadata, DenomMetadataInfo};
+use crate::state::{DENOM_METADATA, STATE, TOKEN_INFO};
+
+/// Sets or clears the display metadata of a native denom, only callable by owner
+pub fn try_set_denom_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    metadata: Option<DenomMetadata>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match &metadata {
+        Some(metadata) => DENOM_METADATA.save(deps.storage, &denom, metadata)?,
+        None => DENOM_METADATA.remove(deps.storage, &denom),
+    }
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_denom_metadata"),
+            attr("denom", denom),
+            attr("cleared", metadata.is_none()),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Registered metadata of the coins denoms, denoms without metadata are skipped
+pub fn coins_metadata(storage: &dyn Storage, coins: &[Coin]) -> StdResult<Vec<DenomMetadataInfo>> {
+    let mut res = vec![];
+    for coin in coins {
+        if let Some(metadata) = DENOM_METADATA.may_load(storage, &coin.denom)? {
+            res.push(DenomMetadataInfo {
+                denom: coin.denom.to_owned(),
+                symbol: metadata.symbol,
+                decimals: metadata.decimals,
+            });
+        }
+    }
+    Ok(res)
+}
+
+/// Queries and stores the TokenInfo of the cw20 tokens, callable by anyone
+pub fn try_cache_token_info(deps: DepsMut, tokens: Vec<String>) -> Result<Response, ContractError> {
+    for token in &tokens {
+        let token = deps.api.addr_validate(token)?;
+        let metadata = query_token_info(&deps.querier, &token)?;
+        TOKEN_INFO.save(deps.storage, &token, &metadata)?;
+    }
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "cache_token_info"),
+            attr("tokens", tokens.len()),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Cached metadata of the cw20 tokens, uncached tokens are queried
+pub fn tokens_metadata(
+    deps: Deps,
+    tokens: &[Cw20CoinVerified],
+) -> StdResult<Vec<DenomMetadataInfo>> {
+    let mut res = vec![];
+    for token in tokens {
+        let metadata = match TOKEN_INFO.may_load(deps.storage, &token.address)? {
+            Some(metadata) => metadata,
+            None => query_token_info(&deps.querier, &token.address)?,
+        };
+        res.push(DenomMetadataInfo {
+            denom: token.address.to_string(),
+            symbol: metadata.symbol,
+            decimals: metadata.decimals,
+        });
+    }
+    Ok(res)
+}
+
+fn query_token_info(querier: &QuerierWrapper, token: &Addr) -> StdResult<DenomMetadata> {
+    let info: TokenInfoResponse = querier.query_wasm_smart(token, &Cw20QueryMsg::TokenInfo {})?;
+    Ok(DenomMetadata {
+        symbol: info.symbol,
+        decimals: info.decimals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::mock_querier::mock_dependencies_with_wasm;
+    use crate::msg::ReceiveMsg;
+    use crate::msg::{ExecuteMsg, InstantiateMsg, LockInfoV2, QueryMsg, QueryMsgV2};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, to_binary, ContractResult, Timestamp, Uint128};
+    use cw20::Cw20ReceiveMsg;
+
+    #[test]
+    fn denom_metadata() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let ibc_denom = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+        let msg = ExecuteMsg::SetDenomMetadata {
+            denom: ibc_denom.into(),
+            metadata: Some(DenomMetadata {
+                symbol: "ATOM".into(),
+                decimals: 6,
+            }),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), owner, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let mut funds = coins(2, ibc_denom);
+        funds.extend(coins(3, "ujuno"));
+        let info = mock_info("anyone", &funds);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let msg = QueryMsg::V2(QueryMsgV2::Lock {
+            address: "anyone".into(),
+            id: "1".into(),
+            viewing_key: None,
+            include_config: false,
+            include_token_info: false,
+        });
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LockInfoV2 = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![DenomMetadataInfo {
+                denom: ibc_denom.into(),
+                symbol: "ATOM".into(),
+                decimals: 6,
+            }],
+            value.denom_metadata
+        );
+    }
+
+    #[test]
+    fn cw20_token_info() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        deps.querier.update_wasm(|contract, msg| {
+            assert_eq!("cw20", contract);
+            let res = match from_binary(msg).unwrap() {
+                Cw20QueryMsg::TokenInfo {} => to_binary(&TokenInfoResponse {
+                    name: "Token".into(),
+                    symbol: "TKN".into(),
+                    decimals: 6,
+                    total_supply: Uint128(1000),
+                }),
+                _ => panic!("Unexpected query"),
+            };
+            ContractResult::Ok(res.unwrap())
+        });
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "anyone".into(),
+            amount: Uint128(5),
+            msg: to_binary(&ReceiveMsg::Lock {
+                id: "1".into(),
+                expire: Some(Timestamp::from_seconds(100)),
+                referrer: None,
+            })
+            .unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env, mock_info("cw20", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::CacheTokenInfo {
+            tokens: vec!["cw20".into()],
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+        let cached = TOKEN_INFO
+            .load(&deps.storage, &Addr::unchecked("cw20"))
+            .unwrap();
+        assert_eq!("TKN", cached.symbol);
+
+        let query_lock = |include_token_info| {
+            let msg = QueryMsg::V2(QueryMsgV2::Lock {
+                address: "anyone".into(),
+                id: "1".into(),
+                viewing_key: None,
+                include_config: false,
+                include_token_info,
+            });
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<LockInfoV2>(&res).unwrap()
+        };
+        assert_eq!(None, query_lock(false).token_info);
+        assert_eq!(
+            Some(vec![DenomMetadataInfo {
+                denom: "cw20".into(),
+                symbol: "TKN".into(),
+                decimals: 6,
+            }]),
+            query_lock(true).token_info
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use cosmwasm_std::{
+    attr, to_binary, Addr, Deps, DepsMut, Env, MessageInfo, Order, QuerierWrapper, ReplyOn,
+    Response, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+
+use crate::balance::GenericBalance;
+use crate::contract::push_payout;
+use crate::error::ContractError;
+use crate::msg::{ConverterExecuteMsg, PayoutInfo, TokenMigrationInfo, TokenMigrationsResponse};
+use crate::state::{
+    PendingConversion, TokenMigration, PENDING_CONVERSIONS, STATE, TOKEN_MIGRATIONS,
+};
+
+pub const CONVERT_REPLY_ID: u64 = 3;
+/// Seconds between registering a migration and releases going through its converter
+pub const MIGRATION_DELAY: u64 = 7 * 24 * 60 * 60;
+
+pub fn try_register_token_migration(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    old_token: String,
+    new_token: String,
+    converter: String,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let old_token = deps.api.addr_validate(&old_token)?;
+    let migration = TokenMigration {
+        new_token: deps.api.addr_validate(&new_token)?,
+        converter: deps.api.addr_validate(&converter)?,
+        active_at: env.block.time.plus_seconds(MIGRATION_DELAY),
+    };
+    TOKEN_MIGRATIONS.save(deps.storage, &old_token, &migration)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "register_token_migration"),
+            attr("old_token", old_token),
+            attr("new_token", migration.new_token),
+            attr("converter", migration.converter),
+            attr("active_at", migration.active_at),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn try_remove_token_migration(
+    deps: DepsMut,
+    info: MessageInfo,
+    old_token: String,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let old_token = deps.api.addr_validate(&old_token)?;
+    TOKEN_MIGRATIONS.remove(deps.storage, &old_token);
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "remove_token_migration"),
+            attr("old_token", old_token),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Sends migrated cw20 tokens in funds to their converter, which pays the new token to this
+/// contract. The reply of each submessage forwards the new tokens to recipient
+pub fn convert_submsgs(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    funds: &mut GenericBalance,
+    recipient: &Addr,
+    payouts: &mut Vec<PayoutInfo>,
+) -> StdResult<Vec<SubMsg>> {
+    let mut submessages = vec![];
+    let mut pending = PENDING_CONVERSIONS.may_load(storage)?.unwrap_or_default();
+    let mut kept = vec![];
+    for coin in funds.cw20.drain(..) {
+        let migration = match TOKEN_MIGRATIONS.may_load(storage, &coin.address)? {
+            Some(migration) if env.block.time >= migration.active_at => migration,
+            _ => {
+                kept.push(coin);
+                continue;
+            }
+        };
+        pending.push(PendingConversion {
+            recipient: recipient.clone(),
+            new_token: migration.new_token.clone(),
+            amount: coin.amount,
+            balance: token_balance(querier, &migration.new_token, &env.contract.address)?,
+        });
+        let msg = ConverterExecuteMsg::Convert {
+            recipient: env.contract.address.to_string(),
+        };
+        submessages.push(SubMsg {
+            id: CONVERT_REPLY_ID,
+            msg: WasmMsg::Execute {
+                contract_addr: coin.address.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: migration.converter.to_string(),
+                    amount: coin.amount,
+                    msg: Some(to_binary(&msg)?),
+                })?,
+                send: vec![],
+            }
+            .into(),
+            gas_limit: None,
+            reply_on: ReplyOn::Success,
+        });
+        let converted = GenericBalance {
+            native: vec![],
+            cw20: vec![coin],
+        };
+        push_payout(payouts, recipient, converted);
+    }
+    funds.cw20 = kept;
+    if !pending.is_empty() {
+        PENDING_CONVERSIONS.save(storage, &pending)?;
+    }
+
+    Ok(submessages)
+}
+
+/// Checks the converter paid at least the old token amount of the new token, then forwards
+/// it to recipient
+pub fn reply_convert(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut pending = PENDING_CONVERSIONS.load(deps.storage)?;
+    let conversion = pending.remove(0);
+    let balance = token_balance(&deps.querier, &conversion.new_token, &env.contract.address)?;
+    let received = balance.checked_sub(conversion.balance).unwrap_or_default();
+    if received < conversion.amount {
+        return Err(ContractError::ConversionShortfall {
+            expected: conversion.amount,
+            received,
+        });
+    }
+
+    if pending.is_empty() {
+        PENDING_CONVERSIONS.remove(deps.storage);
+    } else {
+        PENDING_CONVERSIONS.save(deps.storage, &pending)?;
+    }
+
+    Ok(Response {
+        messages: vec![WasmMsg::Execute {
+            contract_addr: conversion.new_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: conversion.recipient.to_string(),
+                amount: received,
+            })?,
+            send: vec![],
+        }
+        .into()],
+        attributes: vec![
+            attr("action", "convert"),
+            attr("to", conversion.recipient),
+            attr("token", conversion.new_token),
+            attr("amount", received),
+        ],
+        ..Response::default()
+    })
+}
+
+fn token_balance(querier: &QuerierWrapper, token: &Addr, address: &Addr) -> StdResult<Uint128> {
+    let res: BalanceResponse = querier.query_wasm_smart(
+        token,
+        &Cw20QueryMsg::Balance {
+            address: address.to_string(),
+        },
+    )?;
+    Ok(res.balance)
+}
+
+pub fn query_token_migrations(deps: Deps) -> StdResult<TokenMigrationsResponse> {
+    let migrations: StdResult<Vec<_>> = TOKEN_MIGRATIONS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (old_token, migration) = item?;
+            Ok(TokenMigrationInfo {
+                old_token: String::from_utf8(old_token)?,
+                new_token: migration.new_token.into(),
+                converter: migration.converter.into(),
+                active_at: migration.active_at,
+            })
+        })
+        .collect();
+
+    Ok(TokenMigrationsResponse {
+        migrations: migrations?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, reply};
+    use crate::mock_querier::mock_dependencies_with_wasm;
+    use crate::msg::{ExecuteMsg, InstantiateMsg, LockData, ReceiveMsg};
+    use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{from_binary, ContractResult, CosmosMsg, Reply, SubcallResponse, Timestamp};
+    use cw20::{Cw20Coin, Cw20ReceiveMsg};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn unlock_migrated_token() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        let balance = Rc::new(Cell::new(0u128));
+        let new_balance = balance.clone();
+        deps.querier.update_wasm(move |contract, msg| {
+            assert_eq!("new", contract);
+            match from_binary(msg).unwrap() {
+                Cw20QueryMsg::Balance { address } => assert_eq!(MOCK_CONTRACT_ADDR, address),
+                _ => panic!("Unexpected query"),
+            }
+            ContractResult::Ok(
+                to_binary(&BalanceResponse {
+                    balance: Uint128(new_balance.get()),
+                })
+                .unwrap(),
+            )
+        });
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        for id in &["1", "2"] {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "anyone".into(),
+                amount: Uint128(5),
+                msg: to_binary(&ReceiveMsg::Lock {
+                    id: id.to_string(),
+                    expire: Some(Timestamp::from_seconds(100)),
+                    referrer: None,
+                })
+                .unwrap(),
+            });
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("old", &[]), msg).unwrap();
+        }
+
+        let msg = ExecuteMsg::RegisterTokenMigration {
+            old_token: "old".into(),
+            new_token: "new".into(),
+            converter: "converter".into(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), owner, msg).unwrap();
+        let active_at = Timestamp::from_seconds(MIGRATION_DELAY);
+        let value = query_token_migrations(deps.as_ref()).unwrap();
+        assert_eq!(
+            vec![TokenMigrationInfo {
+                old_token: "old".into(),
+                new_token: "new".into(),
+                converter: "converter".into(),
+                active_at,
+            }],
+            value.migrations
+        );
+
+        // the old token is paid until the migration is active
+        env.block.time = Timestamp::from_seconds(101);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+        assert!(res.submessages.is_empty());
+        assert_eq!(1, res.messages.len());
+
+        env.block.time = active_at;
+        let msg = ExecuteMsg::Unlock { id: "2".into() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+        let convert = ConverterExecuteMsg::Convert {
+            recipient: MOCK_CONTRACT_ADDR.into(),
+        };
+        assert_eq!(
+            res.submessages,
+            vec![SubMsg {
+                id: CONVERT_REPLY_ID,
+                msg: WasmMsg::Execute {
+                    contract_addr: "old".into(),
+                    msg: to_binary(&Cw20ExecuteMsg::Send {
+                        contract: "converter".into(),
+                        amount: Uint128(5),
+                        msg: Some(to_binary(&convert).unwrap()),
+                    })
+                    .unwrap(),
+                    send: vec![],
+                }
+                .into(),
+                gas_limit: None,
+                reply_on: ReplyOn::Success,
+            }]
+        );
+        let data: LockData = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(
+            vec![PayoutInfo {
+                recipient: "anyone".into(),
+                native: vec![],
+                cw20: vec![Cw20Coin {
+                    address: "old".into(),
+                    amount: Uint128(5),
+                }],
+            }],
+            data.payouts
+        );
+
+        let converted = || Reply {
+            id: CONVERT_REPLY_ID,
+            result: ContractResult::Ok(SubcallResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        balance.set(3);
+        let res = reply(deps.as_mut(), env.clone(), converted());
+        match res {
+            Err(ContractError::ConversionShortfall { expected, received }) => {
+                assert_eq!(Uint128(5), expected);
+                assert_eq!(Uint128(3), received);
+            }
+            _ => panic!("Must return ConversionShortfall error"),
+        }
+        balance.set(5);
+        let res = reply(deps.as_mut(), env, converted()).unwrap();
+        let transfer: CosmosMsg = WasmMsg::Execute {
+            contract_addr: "new".into(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "anyone".into(),
+                amount: Uint128(5),
+            })
+            .unwrap(),
+            send: vec![],
+        }
+        .into();
+        assert_eq!(vec![transfer], res.messages);
+        assert!(PENDING_CONVERSIONS
+            .may_load(&deps.storage)
+            .unwrap()
+            .is_none());
+    }
+}
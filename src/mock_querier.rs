@@ -0,0 +1,77 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_slice, to_binary, Binary, Coin, ContractInfoResponse, ContractResult, Empty, OwnedDeps,
+    Querier, QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+
+type WasmHandler = Box<dyn Fn(&str, &Binary) -> ContractResult<Binary>>;
+
+/// MockQuerier answering smart queries to other contracts with a custom handler
+pub struct WasmMockQuerier {
+    base: MockQuerier,
+    wasm_handler: WasmHandler,
+    contracts: Vec<String>,
+}
+
+pub fn mock_dependencies_with_wasm(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: WasmMockQuerier {
+            base: MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]),
+            wasm_handler: Box::new(|contract_addr, _| {
+                ContractResult::Err(format!("No such contract: {}", contract_addr))
+            }),
+            contracts: vec![],
+        },
+    }
+}
+
+impl WasmMockQuerier {
+    /// Handler receives the queried contract address and the query msg
+    pub fn update_wasm<WH>(&mut self, handler: WH)
+    where
+        WH: Fn(&str, &Binary) -> ContractResult<Binary> + 'static,
+    {
+        self.wasm_handler = Box::new(handler);
+    }
+
+    /// Answers ContractInfo queries for the address, as an instantiated contract
+    pub fn register_contract(&mut self, contract_addr: &str) {
+        self.contracts.push(contract_addr.to_string());
+    }
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                SystemResult::Ok((self.wasm_handler)(contract_addr, msg))
+            }
+            QueryRequest::Wasm(WasmQuery::ContractInfo { contract_addr })
+                if self.contracts.contains(contract_addr) =>
+            {
+                let info = ContractInfoResponse {
+                    code_id: 1,
+                    creator: "creator".into(),
+                    admin: None,
+                    pinned: false,
+                    ibc_port: None,
+                };
+                SystemResult::Ok(to_binary(&info).into())
+            }
+            _ => self.base.handle_query(&request),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use cosmwasm_std::{from_binary, to_binary, Addr, Coin, DepsMut, Env, Response, Timestamp};
+use cw20::Balance;
+
+use crate::balance::GenericBalance;
+use crate::error::ContractError;
+use crate::msg::{LockData, MultiDenomPolicy};
+use crate::referral::try_lock_referred;
+use crate::state::{State, STATE};
+
+/// Locks native funds following the multi denom policy
+pub fn try_lock_denoms(
+    deps: DepsMut,
+    env: Env,
+    funds: Vec<Coin>,
+    sender: &Addr,
+    id: String,
+    expire: Timestamp,
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    if funds.len() > 1 {
+        match STATE.load(deps.storage)?.multi_denom_policy {
+            MultiDenomPolicy::Allow => {}
+            MultiDenomPolicy::Reject => return Err(ContractError::MultipleDenoms {}),
+            MultiDenomPolicy::Split => {
+                return lock_split(deps, env, funds, sender, id, expire, referrer)
+            }
+        }
+    }
+
+    try_lock_referred(
+        deps,
+        env,
+        Balance::from(funds),
+        sender,
+        id,
+        expire,
+        referrer,
+    )
+}
+
+/// Checks the funds a lock would hold against the multi denom policy. Only Lock funds are
+/// split, any other deposit adding a second asset to a lock is rejected under Split
+pub fn check_denom_policy(state: &State, funds: &GenericBalance) -> Result<(), ContractError> {
+    if funds.native.len() + funds.cw20.len() <= 1 {
+        return Ok(());
+    }
+    match state.multi_denom_policy {
+        MultiDenomPolicy::Allow => Ok(()),
+        MultiDenomPolicy::Reject | MultiDenomPolicy::Split => Err(ContractError::MultipleDenoms {}),
+    }
+}
+
+/// Locks each denom under id-denom, returning the LockData of every lock like UnlockMany
+fn lock_split(
+    mut deps: DepsMut,
+    env: Env,
+    funds: Vec<Coin>,
+    sender: &Addr,
+    id: String,
+    expire: Timestamp,
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut res = Response::default();
+    let mut locked = vec![];
+    for coin in funds {
+        let id = format!("{}-{}", id, coin.denom);
+        let mut sub_res = try_lock_referred(
+            deps.branch(),
+            env.clone(),
+            Balance::from(vec![coin]),
+            sender,
+            id,
+            expire,
+            referrer.clone(),
+        )?;
+        res.submessages.append(&mut sub_res.submessages);
+        res.messages.append(&mut sub_res.messages);
+        res.attributes.append(&mut sub_res.attributes);
+        if let Some(data) = sub_res.data {
+            locked.push(from_binary::<LockData>(&data)?);
+        }
+    }
+    res.data = Some(to_binary(&locked)?);
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, migrate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, ReceiveMsg};
+    use crate::state::locks;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, Uint128};
+    use cw20::Cw20ReceiveMsg;
+
+    #[test]
+    fn multi_denom_policy() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            multi_denom_policy: MultiDenomPolicy::Reject,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let funds = vec![Coin::new(2, "earth"), Coin::new(5, "token")];
+        let info = mock_info("anyone", &funds);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::MultipleDenoms {}) => {}
+            _ => panic!("Must return MultipleDenoms error"),
+        }
+        // a single denom is locked as usual
+        let single = mock_info("anyone", &coins(5, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), single, msg).unwrap();
+
+        // but a second asset can't be added later
+        let increase = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "anyone".into(),
+            amount: Uint128::new(5),
+            msg: to_binary(&ReceiveMsg::IncreaseLock {
+                id: "1".into(),
+                min_remaining: None,
+            })
+            .unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), mock_info("cw20", &[]), increase);
+        match res {
+            Err(ContractError::MultipleDenoms {}) => {}
+            _ => panic!("Must return MultipleDenoms error"),
+        }
+        let msg = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &coins(5, "earth")),
+            msg,
+        );
+        match res {
+            Err(ContractError::MultipleDenoms {}) => {}
+            _ => panic!("Must return MultipleDenoms error"),
+        }
+
+        let msg = MigrateMsg {
+            multi_denom_policy: Some(MultiDenomPolicy::Split),
+            ..MigrateMsg::default()
+        };
+        let _res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let data: Vec<LockData> = from_binary(&res.data.unwrap()).unwrap();
+        let ids: Vec<_> = data.into_iter().map(|lock| lock.id).collect();
+        assert_eq!(vec!["1-earth", "1-token"], ids);
+
+        let owner = Addr::unchecked("anyone");
+        let lock = locks()
+            .load(&deps.storage, (&owner, "1-earth".into()))
+            .unwrap();
+        assert_eq!(coins(2, "earth"), lock.funds.native);
+        let lock = locks()
+            .load(&deps.storage, (&owner, "1-token".into()))
+            .unwrap();
+        assert_eq!(coins(5, "token"), lock.funds.native);
+    }
+}
@@ -0,0 +1,164 @@
+use cosmwasm_std::{attr, Addr, Deps, DepsMut, MessageInfo, Response, StdResult, Storage};
+
+use crate::error::ContractError;
+use crate::msg::IdPrefixResponse;
+use crate::state::{ID_PREFIXES, LOCKERS};
+
+/// Reserves prefix for the sending locker. Prefixes contain a single '/' at
+/// the end, so reserved namespaces never overlap
+pub fn try_reserve_id_prefix(
+    deps: DepsMut,
+    info: MessageInfo,
+    prefix: String,
+) -> Result<Response, ContractError> {
+    if LOCKERS.may_load(deps.storage, &info.sender)?.is_none() {
+        return Err(ContractError::Unauthorized {});
+    }
+    if id_prefix(&prefix) != Some(prefix.as_str()) {
+        return Err(ContractError::InvalidIdPrefix {});
+    }
+
+    ID_PREFIXES.update(deps.storage, &prefix, |existing| match existing {
+        None => Ok(info.sender.clone()),
+        Some(_) => Err(ContractError::ReservedIdPrefix {
+            prefix: prefix.clone(),
+        }),
+    })?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "reserve_id_prefix"),
+            attr("from", info.sender),
+            attr("prefix", prefix),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Fails if id is in a namespace reserved by another address than creator
+pub fn check_id_prefix(
+    storage: &dyn Storage,
+    id: &str,
+    creator: &Addr,
+) -> Result<(), ContractError> {
+    let prefix = match id_prefix(id) {
+        Some(prefix) => prefix,
+        None => return Ok(()),
+    };
+    match ID_PREFIXES.may_load(storage, prefix)? {
+        Some(locker) if locker != *creator => Err(ContractError::ReservedIdPrefix {
+            prefix: prefix.into(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+pub fn query_id_prefix(deps: Deps, prefix: String) -> StdResult<IdPrefixResponse> {
+    let locker = ID_PREFIXES.may_load(deps.storage, &prefix)?;
+
+    Ok(IdPrefixResponse {
+        locker: locker.map(String::from),
+    })
+}
+
+/// Id up to and including the first '/'
+fn id_prefix(id: &str) -> Option<&str> {
+    id.find('/')
+        .map(|pos| &id[..=pos])
+        .filter(|prefix| prefix.len() > 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, Timestamp};
+
+    #[test]
+    fn reserved_prefix() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateLockers {
+            add: vec!["launchpad".into()],
+            remove: vec![],
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let reserve = |prefix: &str| ExecuteMsg::ReserveIdPrefix {
+            prefix: prefix.into(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            reserve("launchpad/"),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        for prefix in &["launchpad", "/", "launch/pad/"] {
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("launchpad", &[]),
+                reserve(*prefix),
+            );
+            match res {
+                Err(ContractError::InvalidIdPrefix {}) => {}
+                _ => panic!("Must return InvalidIdPrefix error"),
+            }
+        }
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("launchpad", &[]),
+            reserve("launchpad/"),
+        )
+        .unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let info = mock_info("anyone", &coins(2, "token"));
+        let msg = ExecuteMsg::Lock {
+            id: "launchpad/1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::ReservedIdPrefix { prefix }) => assert_eq!("launchpad/", prefix),
+            _ => panic!("Must return ReservedIdPrefix error"),
+        }
+
+        // unreserved namespaces are free to use
+        let msg = ExecuteMsg::Lock {
+            id: "mine/1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::LockFor {
+            owner: "anyone".into(),
+            id: "launchpad/1".into(),
+            expire: Timestamp::from_seconds(100),
+        };
+        let info = mock_info("launchpad", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let res = query_id_prefix(deps.as_ref(), "launchpad/".into()).unwrap();
+        assert_eq!(Some("launchpad".into()), res.locker);
+    }
+}
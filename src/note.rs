@@ -0,0 +1,96 @@
+use cosmwasm_std::{attr, Binary, DepsMut, MessageInfo, Response};
+
+use crate::contract::load_lock;
+use crate::error::ContractError;
+use crate::state::locks;
+
+/// Max note size in bytes, it is loaded with the lock on every access
+pub const MAX_NOTE_SIZE: usize = 512;
+
+pub fn try_set_note(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    note: Option<Binary>,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    if let Some(note) = &note {
+        if note.len() > MAX_NOTE_SIZE {
+            return Err(ContractError::NoteTooLarge { max: MAX_NOTE_SIZE });
+        }
+    }
+    lock.note = note;
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_note"),
+            attr("from", info.sender),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, LockInfoV2, QueryMsg, QueryMsgV2};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, Timestamp};
+
+    #[test]
+    fn set_note() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::SetNote {
+            id: "1".into(),
+            note: Some(Binary(vec![0; MAX_NOTE_SIZE + 1])),
+        };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        match res {
+            Err(ContractError::NoteTooLarge { max }) => assert_eq!(MAX_NOTE_SIZE, max),
+            _ => panic!("Must return NoteTooLarge error"),
+        }
+
+        let note = Binary::from(b"sha256:0f1e".to_vec());
+        let msg = ExecuteMsg::SetNote {
+            id: "1".into(),
+            note: Some(note.clone()),
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let msg = QueryMsg::V2(QueryMsgV2::Lock {
+            address: "anyone".into(),
+            id: "1".into(),
+            viewing_key: None,
+            include_config: false,
+            include_token_info: false,
+        });
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: LockInfoV2 = from_binary(&res).unwrap();
+        assert_eq!(Some(note), value.note);
+    }
+}
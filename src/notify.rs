@@ -0,0 +1,155 @@
+use cosmwasm_std::{
+    attr, Addr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage, Timestamp,
+};
+
+use crate::balance::GenericBalance;
+use crate::contract::to_cw20_coins;
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::{NotifyChannelResponse, NotifyPacket};
+use crate::state::{NOTIFY_CHANNEL, NOTIFY_CHANNELS, STATE};
+
+pub fn try_set_notify_channel(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: Option<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match &channel_id {
+        Some(channel_id) => {
+            if NOTIFY_CHANNELS
+                .may_load(deps.storage, channel_id)?
+                .is_none()
+            {
+                return Err(ContractError::UnknownNotifyChannel {
+                    channel_id: channel_id.clone(),
+                });
+            }
+            NOTIFY_CHANNEL.save(deps.storage, channel_id)?;
+        }
+        None => NOTIFY_CHANNEL.remove(deps.storage),
+    }
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::SET_NOTIFY_CHANNEL),
+            attr(events::CHANNEL_ID, channel_id.unwrap_or_default()),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Msg sending packet over the notify channel, none if the owner didn't set one
+pub fn notify_msgs(
+    storage: &dyn Storage,
+    env: &Env,
+    packet: NotifyPacket,
+) -> StdResult<Vec<CosmosMsg>> {
+    match NOTIFY_CHANNEL.may_load(storage)? {
+        Some(channel_id) => Ok(vec![send_packet(env, channel_id, &packet)?]),
+        None => Ok(vec![]),
+    }
+}
+
+#[cfg(feature = "ibc")]
+fn send_packet(env: &Env, channel_id: String, packet: &NotifyPacket) -> StdResult<CosmosMsg> {
+    crate::ibc::send_packet(env, channel_id, packet)
+}
+
+/// Channels only connect with the ibc feature, so there is never a channel to send over
+#[cfg(not(feature = "ibc"))]
+fn send_packet(_env: &Env, _channel_id: String, _packet: &NotifyPacket) -> StdResult<CosmosMsg> {
+    Err(cosmwasm_std::StdError::generic_err(
+        "Notifications need the ibc feature",
+    ))
+}
+
+pub fn created_packet(
+    owner: &Addr,
+    id: &str,
+    expire: Timestamp,
+    funds: &GenericBalance,
+) -> NotifyPacket {
+    NotifyPacket::LockCreated {
+        owner: owner.to_string(),
+        id: id.to_owned(),
+        expire,
+        native_balance: funds.native.clone(),
+        cw20_balance: to_cw20_coins(funds.cw20.clone()),
+    }
+}
+
+pub fn released_packet(owner: &Addr, id: &str, funds: &GenericBalance) -> NotifyPacket {
+    NotifyPacket::LockReleased {
+        owner: owner.to_string(),
+        id: id.to_owned(),
+        native_balance: funds.native.clone(),
+        cw20_balance: to_cw20_coins(funds.cw20.clone()),
+    }
+}
+
+pub fn query_notify_channel(deps: Deps) -> StdResult<NotifyChannelResponse> {
+    Ok(NotifyChannelResponse {
+        channel_id: NOTIFY_CHANNEL.may_load(deps.storage)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::Empty;
+
+    #[test]
+    fn set_notify_channel() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let set = |channel_id: Option<&str>| ExecuteMsg::SetNotifyChannel {
+            channel_id: channel_id.map(String::from),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            set(Some("channel-1")),
+        );
+        match res {
+            Err(ContractError::UnknownNotifyChannel { channel_id }) => {
+                assert_eq!("channel-1", channel_id)
+            }
+            _ => panic!("Must return UnknownNotifyChannel error"),
+        }
+
+        // as if a counterparty connected the channel
+        NOTIFY_CHANNELS
+            .save(&mut deps.storage, "channel-1", &Empty {})
+            .unwrap();
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, set(Some("channel-1")));
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let msg = set(Some("channel-1"));
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+        let value = query_notify_channel(deps.as_ref()).unwrap();
+        assert_eq!(Some("channel-1".into()), value.channel_id);
+
+        let _res = execute(deps.as_mut(), mock_env(), owner, set(None)).unwrap();
+        let value = query_notify_channel(deps.as_ref()).unwrap();
+        assert_eq!(None, value.channel_id);
+    }
+}
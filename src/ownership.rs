@@ -0,0 +1,154 @@
+use cosmwasm_std::{attr, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use sha2::Digest;
+
+use crate::error::ContractError;
+use crate::msg::{OwnershipChallengeResponse, VerifyOwnershipResponse};
+use crate::state::{locks, OWNERSHIP_KEYS};
+
+/// Sets or clears the secp256k1 public key signing the sender ownership challenges
+pub fn try_set_ownership_key(
+    deps: DepsMut,
+    info: MessageInfo,
+    pubkey: Option<Binary>,
+) -> Result<Response, ContractError> {
+    match &pubkey {
+        Some(pubkey) => OWNERSHIP_KEYS.save(deps.storage, &info.sender, pubkey)?,
+        None => OWNERSHIP_KEYS.remove(deps.storage, &info.sender),
+    }
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_ownership_key"),
+            attr("from", info.sender),
+            attr("cleared", pubkey.is_none()),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Payload the owner signs off-chain, bound to this contract so it can't be replayed elsewhere
+pub fn query_ownership_challenge(
+    deps: Deps,
+    env: Env,
+    address: String,
+    id: String,
+    nonce: String,
+) -> StdResult<OwnershipChallengeResponse> {
+    let owner = deps.api.addr_validate(&address)?;
+    let payload = challenge_payload(&env.contract.address, &owner, &id, &nonce);
+
+    Ok(OwnershipChallengeResponse {
+        hash: Binary::from(sha2::Sha256::digest(&payload).to_vec()),
+        payload: Binary::from(payload),
+    })
+}
+
+/// Valid if the lock exists and the signature of the challenge hash matches the owner key
+pub fn query_verify_ownership(
+    deps: Deps,
+    env: Env,
+    address: String,
+    id: String,
+    nonce: String,
+    signature: Binary,
+) -> StdResult<VerifyOwnershipResponse> {
+    let owner = deps.api.addr_validate(&address)?;
+    let exists = locks()
+        .may_load(deps.storage, (&owner, id.to_owned()))?
+        .is_some();
+    let pubkey = OWNERSHIP_KEYS.may_load(deps.storage, &owner)?;
+
+    let valid = match pubkey {
+        Some(pubkey) if exists => {
+            let payload = challenge_payload(&env.contract.address, &owner, &id, &nonce);
+            let hash = sha2::Sha256::digest(&payload);
+            deps.api
+                .secp256k1_verify(&hash, &signature, &pubkey)
+                .unwrap_or(false)
+        }
+        _ => false,
+    };
+
+    Ok(VerifyOwnershipResponse { valid })
+}
+
+fn challenge_payload(contract: &Addr, owner: &Addr, id: &str, nonce: &str) -> Vec<u8> {
+    format!(
+        "cw-lockbox ownership challenge\ncontract: {}\nowner: {}\nid: {}\nnonce: {}",
+        contract, owner, id, nonce
+    )
+    .into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, Timestamp};
+
+    // secp256k1 key signing the sha256 of the "anyone" lock "1" challenge with nonce "perk-42"
+    const PUBKEY: &str = "Aghf4sp6V1iVfqgRvY50PZzua8IAcvFHCoiMQ6EJGo6L";
+    const SIGNATURE: &str =
+        "RPGYDS/dVUOX1Cx4XDa+5d5KXMofXhUhBTA40jA26tUW/ptfvjJg+AmJZE0+uHevs2ZoFAc+lXTV1Ulqz0MVtA==";
+
+    #[test]
+    fn ownership_proof() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let msg = QueryMsg::OwnershipChallenge {
+            address: "anyone".into(),
+            id: "1".into(),
+            nonce: "perk-42".into(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: OwnershipChallengeResponse = from_binary(&res).unwrap();
+        let expected = "cw-lockbox ownership challenge\ncontract: cosmos2contract\n\
+            owner: anyone\nid: 1\nnonce: perk-42";
+        assert_eq!(expected.as_bytes(), value.payload.as_slice());
+
+        let signature = Binary::from_base64(SIGNATURE).unwrap();
+        let verify = |nonce: &str| QueryMsg::VerifyOwnership {
+            address: "anyone".into(),
+            id: "1".into(),
+            nonce: nonce.into(),
+            signature: signature.clone(),
+        };
+        // no key registered yet
+        let res = query(deps.as_ref(), mock_env(), verify("perk-42")).unwrap();
+        let value: VerifyOwnershipResponse = from_binary(&res).unwrap();
+        assert!(!value.valid);
+
+        let msg = ExecuteMsg::SetOwnershipKey {
+            pubkey: Some(Binary::from_base64(PUBKEY).unwrap()),
+        };
+        let info = mock_info("anyone", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), verify("perk-42")).unwrap();
+        let value: VerifyOwnershipResponse = from_binary(&res).unwrap();
+        assert!(value.valid);
+        let res = query(deps.as_ref(), mock_env(), verify("perk-43")).unwrap();
+        let value: VerifyOwnershipResponse = from_binary(&res).unwrap();
+        assert!(!value.valid);
+    }
+}
@@ -0,0 +1,193 @@
+use cosmwasm_std::{
+    attr, Deps, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdResult, Storage,
+};
+
+use crate::error::ContractError;
+use crate::msg::{ParamRegistryMsg, ParamRegistryQueryMsg, ParamRegistryResponse, ParamResponse};
+use crate::state::{
+    CachedParam, ParamRegistry, State, CACHED_MAX_LOCK_TIME, PARAM_REGISTRY, STATE,
+};
+
+/// Sources max_lock_time from a registry contract instead of the config, only callable by owner
+pub fn try_update_param_registry(
+    deps: DepsMut,
+    info: MessageInfo,
+    registry: Option<ParamRegistryMsg>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match registry {
+        Some(registry) => {
+            let registry = ParamRegistry {
+                contract: deps.api.addr_validate(&registry.contract)?,
+                key: registry.key,
+                cache_ttl: registry.cache_ttl,
+            };
+            PARAM_REGISTRY.save(deps.storage, &registry)?;
+        }
+        None => PARAM_REGISTRY.remove(deps.storage),
+    }
+    CACHED_MAX_LOCK_TIME.remove(deps.storage);
+
+    Ok(Response {
+        attributes: vec![attr("action", "update_param_registry")],
+        ..Response::default()
+    })
+}
+
+/// Max lock time at block time, queried from the registry once the cached value is stale
+pub fn load_max_lock_time(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    state: &State,
+) -> StdResult<u64> {
+    let registry = match PARAM_REGISTRY.may_load(storage)? {
+        Some(registry) => registry,
+        None => return Ok(state.max_lock_time),
+    };
+    if let Some(value) = cached_value(storage, env, &registry)? {
+        return Ok(value);
+    }
+
+    let value = fetch(querier, &registry)?;
+    let cached = CachedParam {
+        value,
+        fetched_at: env.block.time,
+    };
+    CACHED_MAX_LOCK_TIME.save(storage, &cached)?;
+
+    Ok(value)
+}
+
+/// Same as load_max_lock_time, a stale value is queried without being cached
+pub fn read_max_lock_time(
+    storage: &dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    state: &State,
+) -> StdResult<u64> {
+    match PARAM_REGISTRY.may_load(storage)? {
+        Some(registry) => match cached_value(storage, env, &registry)? {
+            Some(value) => Ok(value),
+            None => fetch(querier, &registry),
+        },
+        None => Ok(state.max_lock_time),
+    }
+}
+
+fn cached_value(
+    storage: &dyn Storage,
+    env: &Env,
+    registry: &ParamRegistry,
+) -> StdResult<Option<u64>> {
+    let cached = CACHED_MAX_LOCK_TIME.may_load(storage)?;
+    Ok(cached
+        .filter(|cached| env.block.time < cached.fetched_at.plus_seconds(registry.cache_ttl))
+        .map(|cached| cached.value))
+}
+
+fn fetch(querier: &QuerierWrapper, registry: &ParamRegistry) -> StdResult<u64> {
+    let msg = ParamRegistryQueryMsg::Param {
+        key: registry.key.clone(),
+    };
+    let res: ParamResponse = querier.query_wasm_smart(registry.contract.as_str(), &msg)?;
+    Ok(res.value)
+}
+
+pub fn query_param_registry(deps: Deps, env: Env) -> StdResult<ParamRegistryResponse> {
+    let state = STATE.load(deps.storage)?;
+    let registry = PARAM_REGISTRY.may_load(deps.storage)?;
+
+    Ok(ParamRegistryResponse {
+        max_lock_time: read_max_lock_time(deps.storage, &deps.querier, &env, &state)?,
+        registry: registry.map(|registry| ParamRegistryMsg {
+            contract: registry.contract.into(),
+            key: registry.key,
+            cache_ttl: registry.cache_ttl,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::mock_querier::mock_dependencies_with_wasm;
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, to_binary, ContractResult, Timestamp};
+
+    #[test]
+    fn max_lock_time_from_registry() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        deps.querier.update_wasm(|_, _| {
+            ContractResult::Ok(to_binary(&ParamResponse { value: 7200 }).unwrap())
+        });
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateParamRegistry {
+            registry: Some(ParamRegistryMsg {
+                contract: "dao_params".into(),
+                key: "lockbox/max_lock_time".into(),
+                cache_ttl: 600,
+            }),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), owner, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let lock = |id: &str, expire: u64| ExecuteMsg::Lock {
+            id: id.into(),
+            expire: Some(Timestamp::from_seconds(expire)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let msg = lock("1", 5000);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            Some(CachedParam {
+                value: 7200,
+                fetched_at: Timestamp::from_seconds(0),
+            }),
+            CACHED_MAX_LOCK_TIME.may_load(&deps.storage).unwrap()
+        );
+
+        // the cached value is used until it is stale
+        deps.querier.update_wasm(|_, _| {
+            ContractResult::Ok(to_binary(&ParamResponse { value: 1000 }).unwrap())
+        });
+        env.block.time = Timestamp::from_seconds(500);
+        let msg = lock("2", 5000);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(600);
+        let value = query_param_registry(deps.as_ref(), env.clone()).unwrap();
+        assert_eq!(1000, value.max_lock_time);
+        let res = execute(deps.as_mut(), env, info, lock("3", 5000));
+        match res {
+            Err(ContractError::HighExpired {}) => {}
+            _ => panic!("Must return HighExpired error"),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use cosmwasm_std::{
+    attr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage, Timestamp,
+};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, PauseResponse};
+use crate::roles::is_operator;
+use crate::state::{Pause, PAUSE, STATE};
+
+/// Pauses the contract, resuming on its own at until if set. Only callable by owner and operators
+pub fn try_pause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    until: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !is_operator(deps.storage, &state, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    if matches!(until, Some(until) if until <= env.block.time) {
+        return Err(ContractError::LowExpired {});
+    }
+
+    let pause = Pause {
+        paused_at: env.block.time,
+        until,
+    };
+    PAUSE.save(deps.storage, &pause)?;
+
+    let mut attributes = vec![attr("action", "pause"), attr("by", info.sender)];
+    if let Some(until) = until {
+        attributes.push(attr("until", until));
+    }
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
+
+pub fn try_unpause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !is_operator(deps.storage, &state, &info.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    PAUSE.remove(deps.storage);
+
+    Ok(Response {
+        attributes: vec![attr("action", "unpause"), attr("by", info.sender)],
+        ..Response::default()
+    })
+}
+
+/// Rejects msgs while paused, except the pause, role admin, index rebuild and emergency
+/// withdraw msgs. Role admin stays open so the owner can revoke an operator that paused the
+/// contract
+pub fn check_not_paused(
+    storage: &dyn Storage,
+    env: &Env,
+    msg: &ExecuteMsg,
+) -> Result<(), ContractError> {
+    if matches!(
+        msg,
+        ExecuteMsg::Pause { .. }
+            | ExecuteMsg::Unpause {}
+            | ExecuteMsg::GrantRole { .. }
+            | ExecuteMsg::RevokeRole { .. }
+            | ExecuteMsg::RebuildLockIndexes { .. }
+            | ExecuteMsg::AnnounceEmergencyWithdraw { .. }
+            | ExecuteMsg::CancelEmergencyWithdraw {}
+            | ExecuteMsg::EmergencyWithdraw {}
+    ) {
+        return Ok(());
+    }
+    match active_pause(storage, env)? {
+        Some(pause) => Err(ContractError::Paused { until: pause.until }),
+        None => Ok(()),
+    }
+}
+
+/// Stored pause, unless its until is reached
+fn active_pause(storage: &dyn Storage, env: &Env) -> StdResult<Option<Pause>> {
+    let pause = PAUSE.may_load(storage)?;
+    Ok(pause.filter(|pause| !matches!(pause.until, Some(until) if env.block.time >= until)))
+}
+
+pub fn query_pause(deps: Deps, env: Env) -> StdResult<PauseResponse> {
+    let pause = active_pause(deps.storage, &env)?;
+
+    Ok(PauseResponse {
+        paused: pause.is_some(),
+        paused_at: pause.as_ref().map(|pause| pause.paused_at),
+        until: pause.and_then(|pause| pause.until),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::InstantiateMsg;
+    use cosmwasm_std::coins;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    #[test]
+    fn pause_with_auto_expiry() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let pause = ExecuteMsg::Pause {
+            until: Some(Timestamp::from_seconds(50)),
+        };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            pause.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), env.clone(), owner, pause).unwrap();
+
+        let lock = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), lock.clone());
+        match res {
+            Err(ContractError::Paused { until }) => {
+                assert_eq!(Some(Timestamp::from_seconds(50)), until)
+            }
+            _ => panic!("Must return Paused error"),
+        }
+
+        // resumed without the operator
+        env.block.time = Timestamp::from_seconds(50);
+        let value = query_pause(deps.as_ref(), env.clone()).unwrap();
+        assert!(!value.paused);
+        let _res = execute(deps.as_mut(), env, info, lock).unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use cosmwasm_std::{
+    attr, to_binary, Addr, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdResult, WasmMsg,
+};
+use cw20::{Balance, Cw20CoinVerified, Cw20ExecuteMsg};
+
+use crate::contract::{load_lock, try_increase_lock};
+use crate::error::ContractError;
+use crate::msg::PlanMsg;
+use crate::state::{locks, Plan};
+
+pub fn try_set_plan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    plan: Option<PlanMsg>,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    lock.plan = match plan {
+        Some(plan) => {
+            if plan.amount.is_zero() || plan.interval == 0 {
+                return Err(ContractError::InvalidPlan {});
+            }
+            Some(Plan {
+                token: deps.api.addr_validate(&plan.token)?,
+                amount: plan.amount,
+                interval: plan.interval,
+                next: env.block.time,
+            })
+        }
+        None => None,
+    };
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_plan"),
+            attr("from", info.sender),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Pulls the next plan deposit from the owner allowance into the lock, callable by anyone
+pub fn try_crank(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let key = (&owner, id.to_owned());
+    let mut lock = load_lock(deps.storage, &owner, &id)?;
+
+    let plan = lock.plan.as_mut().ok_or(ContractError::NoPlan {})?;
+    if env.block.time < plan.next {
+        return Err(ContractError::PlanNotDue { next: plan.next });
+    }
+    plan.next = plan.next.plus_seconds(plan.interval);
+    let plan = plan.clone();
+    locks().save(deps.storage, key, &lock)?;
+
+    let balance = Balance::Cw20(Cw20CoinVerified {
+        address: plan.token.clone(),
+        amount: plan.amount,
+    });
+    let contract = env.contract.address.clone();
+    let mut res = try_increase_lock(deps, env, balance, &owner, id, None)?;
+    res.messages.push(pull_msg(&plan, &owner, &contract)?);
+    res.attributes.push(attr("crank", info.sender));
+
+    Ok(res)
+}
+
+fn pull_msg(plan: &Plan, owner: &Addr, contract: &Addr) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: plan.token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+            owner: owner.into(),
+            recipient: contract.into(),
+            amount: plan.amount,
+        })?,
+        send: vec![],
+    }
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, LockInfo, QueryMsg, ReceiveMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{from_binary, Timestamp, Uint128};
+    use cw20::{Cw20Coin, Cw20ReceiveMsg};
+
+    #[test]
+    fn crank_plan() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock cw20 tokens
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "anyone".into(),
+            amount: Uint128::new(10),
+            msg: to_binary(&ReceiveMsg::Lock {
+                id: "1".into(),
+                expire: Some(Timestamp::from_seconds(1000)),
+                referrer: None,
+            })
+            .unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("savings", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::SetPlan {
+            id: "1".into(),
+            plan: Some(PlanMsg {
+                token: "savings".into(),
+                amount: Uint128::new(5),
+                interval: 100,
+            }),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+
+        // first deposit is due right away
+        let msg = ExecuteMsg::Crank {
+            owner: "anyone".into(),
+            id: "1".into(),
+        };
+        let info = mock_info("keeper", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "savings".into(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "anyone".into(),
+                    recipient: MOCK_CONTRACT_ADDR.into(),
+                    amount: Uint128::new(5),
+                })
+                .unwrap(),
+                send: vec![],
+            })]
+        );
+
+        env.block.time = Timestamp::from_seconds(99);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::PlanNotDue { .. }) => {}
+            _ => panic!("Must return PlanNotDue error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(100);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let query_msg = QueryMsg::Lock {
+            address: "anyone".into(),
+            id: "1".into(),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+        let value: LockInfo = from_binary(&res).unwrap();
+        assert_eq!(
+            vec![Cw20Coin {
+                address: "savings".into(),
+                amount: Uint128::new(20),
+            }],
+            value.cw20_balance
+        );
+    }
+}
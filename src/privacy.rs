@@ -0,0 +1,236 @@
+use cosmwasm_std::{attr, Addr, Binary, DepsMut, MessageInfo, Order, Response, StdResult, Storage};
+use sha2::Digest;
+
+use crate::contract::load_lock;
+use crate::error::ContractError;
+use crate::state::{locks, Lock, VIEWING_KEYS};
+
+pub fn try_set_private(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    private: bool,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    lock.private = private;
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_private"),
+            attr("from", info.sender),
+            attr("id", id),
+            attr("private", private),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Only the key hash is stored, the key itself is still visible in the tx
+pub fn try_set_viewing_key(
+    deps: DepsMut,
+    info: MessageInfo,
+    key: String,
+) -> Result<Response, ContractError> {
+    VIEWING_KEYS.save(deps.storage, &info.sender, &hash_key(&key))?;
+
+    Ok(Response {
+        attributes: vec![attr("action", "set_viewing_key"), attr("from", info.sender)],
+        ..Response::default()
+    })
+}
+
+/// Balances of private locks are only visible with the owner viewing key
+pub fn can_view(
+    storage: &dyn Storage,
+    owner: &Addr,
+    lock: &Lock,
+    viewing_key: Option<String>,
+) -> StdResult<bool> {
+    if !lock.private {
+        return Ok(true);
+    }
+    is_viewing_key(storage, owner, viewing_key)
+}
+
+/// Checks the key against the owner viewing key, a missing key never matches
+pub fn is_viewing_key(
+    storage: &dyn Storage,
+    owner: &Addr,
+    viewing_key: Option<String>,
+) -> StdResult<bool> {
+    let key = match viewing_key {
+        Some(key) => key,
+        None => return Ok(false),
+    };
+    let hash = VIEWING_KEYS.may_load(storage, owner)?;
+
+    Ok(hash == Some(hash_key(&key)))
+}
+
+/// Whether the owner has any private lock
+pub fn has_private_locks(storage: &dyn Storage, owner: &Addr) -> StdResult<bool> {
+    for item in locks()
+        .prefix(owner)
+        .range(storage, None, None, Order::Ascending)
+    {
+        if item?.1.private {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+fn hash_key(key: &str) -> Binary {
+    Binary::from(sha2::Sha256::digest(key.as_bytes()).to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        ActivityReportResponse, ExecuteMsg, InstantiateMsg, LockInfo, LockedAtHeightResponse,
+        QueryMsg, ReleasePreviewResponse, UnlockScheduleResponse, VestingResponse,
+    };
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, Timestamp};
+
+    #[test]
+    fn private_lock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::SetPrivate {
+            id: "1".into(),
+            private: true,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetViewingKey {
+            key: "secret".into(),
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let query_lock = |viewing_key: Option<&str>| {
+            let msg = QueryMsg::Lock {
+                address: "anyone".into(),
+                id: "1".into(),
+                viewing_key: viewing_key.map(String::from),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<LockInfo>(&res).unwrap()
+        };
+        assert!(query_lock(None).native_balance.is_empty());
+        assert!(query_lock(Some("wrong")).native_balance.is_empty());
+        assert_eq!(coins(2, "token"), query_lock(Some("secret")).native_balance);
+    }
+
+    #[test]
+    fn private_lock_queries() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::SetPrivate {
+            id: "1".into(),
+            private: true,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetViewingKey {
+            key: "secret".into(),
+        };
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let unvested = |viewing_key: Option<&str>| {
+            let msg = QueryMsg::Unvested {
+                address: "anyone".into(),
+                id: "1".into(),
+                t: Some(Timestamp::from_seconds(0)),
+                viewing_key: viewing_key.map(String::from),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<VestingResponse>(&res).unwrap()
+        };
+        assert_eq!(VestingResponse::default(), unvested(None));
+        assert_eq!(coins(2, "token"), unvested(Some("secret")).native_balance);
+
+        let msg = QueryMsg::ReleasePreview {
+            owner: "anyone".into(),
+            id: "1".into(),
+            at_time: Timestamp::from_seconds(0),
+            viewing_key: Some("wrong".into()),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ReleasePreviewResponse = from_binary(&res).unwrap();
+        assert!(value.locked_native.is_empty());
+
+        let locked = |viewing_key: Option<&str>| {
+            let msg = QueryMsg::LockedAtHeight {
+                address: "anyone".into(),
+                height: mock_env().block.height + 1,
+                viewing_key: viewing_key.map(String::from),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<LockedAtHeightResponse>(&res).unwrap()
+        };
+        assert!(locked(None).native_balance.is_empty());
+        assert_eq!(coins(2, "token"), locked(Some("secret")).native_balance);
+
+        let msg = QueryMsg::ActivityReport {
+            address: "anyone".into(),
+            from_time: Timestamp::from_seconds(0),
+            to_time: Timestamp::from_seconds(100),
+            viewing_key: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ActivityReportResponse = from_binary(&res).unwrap();
+        assert_eq!(0, value.created.count);
+
+        // private locks are left out of the unlock schedule
+        let msg = QueryMsg::UnlockSchedule {
+            from: Timestamp::from_seconds(0),
+            to: Timestamp::from_seconds(86_400),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: UnlockScheduleResponse = from_binary(&res).unwrap();
+        assert!(value.days.is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use cosmwasm_std::{attr, Addr, Deps, DepsMut, Env, Response, StdResult, Timestamp};
+use cw20::Balance;
+
+use crate::contract::{to_cw20_coins, try_lock};
+use crate::error::ContractError;
+use crate::msg::ReferrerResponse;
+use crate::state::REFERRALS;
+
+/// Locks funds crediting the lock count and funds to the referrer stats
+pub fn try_lock_referred(
+    mut deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    sender: &Addr,
+    id: String,
+    expire: Timestamp,
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    let referrer = match referrer {
+        Some(referrer) => deps.api.addr_validate(&referrer)?,
+        None => return try_lock(deps, env, balance, sender, id, expire),
+    };
+    if referrer == *sender {
+        return Err(ContractError::SelfReferral {});
+    }
+
+    let mut res = try_lock(deps.branch(), env, balance.clone(), sender, id, expire)?;
+    REFERRALS.update(deps.storage, &referrer, |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.count += 1;
+        stats.volume.add_tokens(balance);
+        Ok(stats)
+    })?;
+    res.attributes.push(attr("referrer", referrer));
+
+    Ok(res)
+}
+
+pub fn query_referrer(deps: Deps, address: String) -> StdResult<ReferrerResponse> {
+    let referrer = deps.api.addr_validate(&address)?;
+    let stats = REFERRALS
+        .may_load(deps.storage, &referrer)?
+        .unwrap_or_default();
+
+    Ok(ReferrerResponse {
+        count: stats.count,
+        native_volume: stats.volume.native,
+        cw20_volume: to_cw20_coins(stats.volume.cw20),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+
+    #[test]
+    fn referrer_stats() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let lock = |id: &str, referrer: &str| ExecuteMsg::Lock {
+            id: id.into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: Some(referrer.into()),
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            lock("1", "anyone"),
+        );
+        match res {
+            Err(ContractError::SelfReferral {}) => {}
+            _ => panic!("Must return SelfReferral error"),
+        }
+
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            lock("1", "wallet"),
+        )
+        .unwrap();
+        let _res = execute(deps.as_mut(), env, info, lock("2", "wallet")).unwrap();
+
+        let msg = QueryMsg::Referrer {
+            address: "wallet".into(),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let value: ReferrerResponse = from_binary(&res).unwrap();
+        assert_eq!(2, value.count);
+        assert_eq!(coins(4, "token"), value.native_volume);
+    }
+}
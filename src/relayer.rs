@@ -0,0 +1,160 @@
+use cosmwasm_std::{attr, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Timestamp};
+
+use crate::contract::{load_lock, unlock};
+use crate::error::ContractError;
+use crate::msg::{RelayerInfo, RelayersResponse};
+use crate::state::RELAYERS;
+
+/// Allows relayer to submit RelayUnlock for the sender locks until expires, replacing
+/// a previous authorization
+pub fn try_authorize_relayer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    relayer: String,
+    expires: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    if matches!(expires, Some(expires) if expires <= env.block.time) {
+        return Err(ContractError::LowExpired {});
+    }
+    let relayer = deps.api.addr_validate(&relayer)?;
+    RELAYERS.save(deps.storage, (&info.sender, &relayer), &expires)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "authorize_relayer"),
+            attr("owner", info.sender),
+            attr("relayer", relayer),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn try_revoke_relayer(
+    deps: DepsMut,
+    info: MessageInfo,
+    relayer: String,
+) -> Result<Response, ContractError> {
+    let relayer = deps.api.addr_validate(&relayer)?;
+    RELAYERS.remove(deps.storage, (&info.sender, &relayer));
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "revoke_relayer"),
+            attr("owner", info.sender),
+            attr("relayer", relayer),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Unlocks an expired lock of owner paying out as the owner unlock, the sender pays the gas
+pub fn try_relay_unlock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    load_lock(deps.storage, &owner, &id)?;
+    let expires = RELAYERS
+        .may_load(deps.storage, (&owner, &info.sender))?
+        .ok_or_else(|| ContractError::LockUnauthorized { id: id.to_owned() })?;
+    if matches!(expires, Some(expires) if expires <= env.block.time) {
+        return Err(ContractError::LockUnauthorized { id });
+    }
+
+    let mut res = unlock(deps, env, &owner, id)?;
+    res.attributes.push(attr("by", info.sender));
+
+    Ok(res)
+}
+
+pub fn query_relayers(deps: Deps, owner: String) -> StdResult<RelayersResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let relayers: StdResult<Vec<_>> = RELAYERS
+        .prefix(&owner)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (relayer, expires) = item?;
+            Ok(RelayerInfo {
+                relayer: String::from_utf8(relayer)?,
+                expires,
+            })
+        })
+        .collect();
+
+    Ok(RelayersResponse {
+        relayers: relayers?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, BankMsg};
+
+    #[test]
+    fn relay_unlock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(101);
+        let relay = ExecuteMsg::RelayUnlock {
+            owner: "anyone".into(),
+            id: "1".into(),
+        };
+        let relayer = mock_info("relayer", &[]);
+        let res = execute(deps.as_mut(), env.clone(), relayer.clone(), relay.clone());
+        match res {
+            Err(ContractError::LockUnauthorized { id }) => assert_eq!("1", id),
+            _ => panic!("Must return LockUnauthorized error"),
+        }
+
+        let msg = ExecuteMsg::AuthorizeRelayer {
+            relayer: "relayer".into(),
+            expires: Some(Timestamp::from_seconds(200)),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+        let value = query_relayers(deps.as_ref(), "anyone".into()).unwrap();
+        assert_eq!(
+            vec![RelayerInfo {
+                relayer: "relayer".into(),
+                expires: Some(Timestamp::from_seconds(200)),
+            }],
+            value.relayers
+        );
+
+        // paid out to the owner
+        let res = execute(deps.as_mut(), env, relayer, relay).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(2, "token"),
+            }
+            .into()]
+        );
+    }
+}
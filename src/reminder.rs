@@ -0,0 +1,183 @@
+use cosmwasm_std::{attr, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Timestamp};
+use cw_storage_plus::{Bound, PrimaryKey};
+
+use crate::contract::{load_lock, next_cursor};
+use crate::error::ContractError;
+use crate::msg::{ExpirationCursor, UpcomingExpiration, UpcomingExpirationsResponse};
+use crate::state::{locks, reminder_bound, split_lock_pk};
+
+/// Max reminder payload size in bytes
+pub const MAX_REMINDER_SIZE: usize = 256;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+pub fn try_set_reminder(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    payload: Option<String>,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    if let Some(payload) = &payload {
+        if payload.len() > MAX_REMINDER_SIZE {
+            return Err(ContractError::ReminderTooLarge {
+                max: MAX_REMINDER_SIZE,
+            });
+        }
+    }
+    lock.reminder = payload;
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_reminder"),
+            attr("from", info.sender),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Locks with a reminder expiring from block time up to hours later, for notifier services
+pub fn query_upcoming_expirations(
+    deps: Deps,
+    env: Env,
+    hours: u64,
+    start_after: Option<ExpirationCursor>,
+    limit: Option<u32>,
+) -> StdResult<UpcomingExpirationsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let now = env.block.time.seconds();
+    let end = now.saturating_add(hours.saturating_mul(3600));
+
+    let min = match start_after {
+        Some(cursor) => {
+            let owner = deps.api.addr_validate(&cursor.owner)?;
+            let pk = (&owner, cursor.id).joined_key();
+            Bound::exclusive(reminder_bound(cursor.expire.seconds(), pk))
+        }
+        None => Bound::inclusive(reminder_bound(now, vec![])),
+    };
+    let max = Bound::exclusive(reminder_bound(end.saturating_add(1), vec![]));
+
+    let expirations: StdResult<Vec<_>> = locks()
+        .idx
+        .reminder
+        .range(deps.storage, Some(min), Some(max))
+        .take(limit + 1)
+        .map(|(expire, pk)| {
+            let (owner, id) = split_lock_pk(&pk)?;
+            let lock = locks().load(deps.storage, (&owner, id.to_owned()))?;
+            Ok(UpcomingExpiration {
+                expire: Timestamp::from_seconds(expire),
+                owner: owner.into(),
+                id,
+                payload: lock.reminder.unwrap_or_default(),
+            })
+        })
+        .collect();
+
+    let mut expirations = expirations?;
+    let next_start_after = next_cursor(&mut expirations, limit, |exp| ExpirationCursor {
+        expire: exp.expire,
+        owner: exp.owner.clone(),
+        id: exp.id.clone(),
+    });
+
+    Ok(UpcomingExpirationsResponse {
+        expirations,
+        next_start_after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary};
+
+    #[test]
+    fn upcoming_expirations() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 100_000,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        for (id, expire) in &[("1", 7200), ("2", 3600), ("3", 1800), ("4", 60_000)] {
+            let msg = ExecuteMsg::Lock {
+                id: id.to_string(),
+                expire: Some(Timestamp::from_seconds(*expire)),
+                referrer: None,
+                amount: None,
+            };
+            let info = mock_info("anyone", &coins(2, "token"));
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+        // lock 3 is not registered
+        for id in &["1", "2", "4"] {
+            let msg = ExecuteMsg::SetReminder {
+                id: id.to_string(),
+                payload: Some(format!("hook-{}", id)),
+            };
+            let info = mock_info("anyone", &[]);
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+
+        let msg = ExecuteMsg::SetReminder {
+            id: "3".into(),
+            payload: Some("x".repeat(MAX_REMINDER_SIZE + 1)),
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg);
+        match res {
+            Err(ContractError::ReminderTooLarge { .. }) => {}
+            _ => panic!("Must return ReminderTooLarge error"),
+        }
+
+        let msg = QueryMsg::UpcomingExpirations {
+            hours: 2,
+            start_after: None,
+            limit: Some(1),
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: UpcomingExpirationsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.expirations,
+            vec![UpcomingExpiration {
+                expire: Timestamp::from_seconds(3600),
+                owner: "anyone".into(),
+                id: "2".into(),
+                payload: "hook-2".into(),
+            }]
+        );
+        assert_eq!(
+            Some(ExpirationCursor {
+                expire: Timestamp::from_seconds(3600),
+                owner: "anyone".into(),
+                id: "2".into(),
+            }),
+            value.next_start_after
+        );
+
+        let msg = QueryMsg::UpcomingExpirations {
+            hours: 2,
+            start_after: value.next_start_after,
+            limit: None,
+        };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        let value: UpcomingExpirationsResponse = from_binary(&res).unwrap();
+        assert_eq!(None, value.next_start_after);
+        let ids: Vec<_> = value.expirations.into_iter().map(|exp| exp.id).collect();
+        assert_eq!(vec!["1".to_string()], ids);
+    }
+}
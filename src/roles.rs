@@ -0,0 +1,234 @@
+use cosmwasm_std::{attr, Addr, Deps, DepsMut, MessageInfo, Order, Response, StdResult, Storage};
+
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::{Role, RoleInfo, RolesResponse};
+use crate::state::{State, ROLES, STATE};
+
+pub fn try_grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.save(deps.storage, &address, &role)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::GRANT_ROLE),
+            attr(events::ADDRESS, address),
+            attr(events::ROLE, role.as_str()),
+        ],
+        ..Response::default()
+    })
+}
+
+pub fn try_revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let address = deps.api.addr_validate(&address)?;
+    ROLES.remove(deps.storage, &address);
+
+    Ok(Response {
+        attributes: vec![
+            attr(events::ACTION, events::REVOKE_ROLE),
+            attr(events::ADDRESS, address),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Sender can use operator powers, such as freezing tokens. The owner is always an operator,
+/// viewers never are
+pub fn is_operator(storage: &dyn Storage, state: &State, sender: &Addr) -> StdResult<bool> {
+    if state.immutable {
+        return Ok(false);
+    }
+    if *sender == state.owner {
+        return Ok(true);
+    }
+    Ok(ROLES.may_load(storage, sender)? == Some(Role::Operator))
+}
+
+pub fn query_roles(deps: Deps) -> StdResult<RolesResponse> {
+    let roles: StdResult<Vec<_>> = ROLES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (address, role) = item?;
+            Ok(RoleInfo {
+                address: String::from_utf8(address)?,
+                role,
+            })
+        })
+        .collect();
+
+    Ok(RolesResponse { roles: roles? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    #[test]
+    fn operator_role() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let grant = |address: &str, role: Role| ExecuteMsg::GrantRole {
+            address: address.into(),
+            role,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("ops", &[]),
+            grant("ops", Role::Operator),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let msg = grant("ops", Role::Operator);
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+        let msg = grant("support", Role::Operator);
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let value = query_roles(deps.as_ref()).unwrap();
+        assert_eq!(2, value.roles.len());
+
+        // operators can freeze tokens but not use owner powers
+        let freeze = ExecuteMsg::FreezeToken {
+            token: "cw20".into(),
+            hold_unlocks: false,
+        };
+        let ops = mock_info("ops", &[]);
+        let _res = execute(deps.as_mut(), mock_env(), ops.clone(), freeze.clone()).unwrap();
+        let msg = grant("anyone", Role::Operator);
+        let res = execute(deps.as_mut(), mock_env(), ops, msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+
+        let msg = ExecuteMsg::RevokeRole {
+            address: "support".into(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), owner, msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), mock_info("support", &[]), freeze);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let value = query_roles(deps.as_ref()).unwrap();
+        assert_eq!(
+            vec![RoleInfo {
+                address: "ops".into(),
+                role: Role::Operator,
+            }],
+            value.roles
+        );
+    }
+
+    #[test]
+    fn viewer_role() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+        let msg = ExecuteMsg::GrantRole {
+            address: "staff".into(),
+            role: Role::Viewer,
+        };
+        let res = execute(deps.as_mut(), mock_env(), owner, msg).unwrap();
+        assert_eq!(attr(events::ROLE, "viewer"), res.attributes[2]);
+
+        let value = query_roles(deps.as_ref()).unwrap();
+        assert_eq!(
+            vec![RoleInfo {
+                address: "staff".into(),
+                role: Role::Viewer,
+            }],
+            value.roles
+        );
+
+        // viewers are listed but have no execute powers
+        let staff = mock_info("staff", &[]);
+        let msg = ExecuteMsg::FreezeToken {
+            token: "cw20".into(),
+            hold_unlocks: false,
+        };
+        let res = execute(deps.as_mut(), mock_env(), staff.clone(), msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            staff,
+            ExecuteMsg::Pause { until: None },
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+    }
+
+    #[test]
+    fn revoke_role_while_paused() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+        let msg = ExecuteMsg::GrantRole {
+            address: "ops".into(),
+            role: Role::Operator,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        // a rogue operator pauses the contract, the owner can still revoke it
+        let ops = mock_info("ops", &[]);
+        let msg = ExecuteMsg::Pause { until: None };
+        let _res = execute(deps.as_mut(), mock_env(), ops.clone(), msg).unwrap();
+        let msg = ExecuteMsg::RevokeRole {
+            address: "ops".into(),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), ops, ExecuteMsg::Unpause {});
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), owner, ExecuteMsg::Unpause {}).unwrap();
+    }
+}
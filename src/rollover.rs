@@ -0,0 +1,253 @@
+use cosmwasm_std::{
+    attr, to_binary, Addr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    Timestamp, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+
+use crate::balance::GenericBalance;
+use crate::contract::{load_lock, remove_expired_lock};
+use crate::error::ContractError;
+use crate::events::{self, asset_attrs, lock_attrs};
+use crate::guardian::check_guardian;
+use crate::msg::{ExecuteMsg, ReceiveMsg, SuccessorResponse};
+use crate::state::{locks, STATE, SUCCESSOR};
+use crate::yield_adapter::withdraw_msgs;
+
+/// Sets the lockbox expired locks opted in are moved into, only callable by owner.
+/// This contract must be a locker of the successor
+pub fn try_update_successor(
+    deps: DepsMut,
+    info: MessageInfo,
+    successor: Option<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match successor {
+        Some(successor) => {
+            let successor = deps.api.addr_validate(&successor)?;
+            SUCCESSOR.save(deps.storage, &successor)?;
+        }
+        None => SUCCESSOR.remove(deps.storage),
+    }
+
+    Ok(Response {
+        attributes: vec![attr("action", "update_successor")],
+        ..Response::default()
+    })
+}
+
+/// Opts the lock in or out of the rollover into the current successor on expire
+pub fn try_set_rollover(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    rollover: bool,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    lock.rollover_successor = None;
+    if rollover {
+        check_movable(&lock.funds)?;
+        let successor = SUCCESSOR
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoSuccessor {})?;
+        lock.rollover_successor = Some(successor);
+    }
+    lock.rollover = rollover;
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_rollover"),
+            attr("from", info.sender),
+            attr("id", id),
+            attr("rollover", rollover),
+            attr(
+                "successor",
+                lock.rollover_successor
+                    .map(String::from)
+                    .unwrap_or_default(),
+            ),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Moves an expired lock opted in into the successor saved at opt-in, locked again for its
+/// original duration. Callable by anyone, the new lock is owned by the lock recipient
+pub fn try_process_rollover(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    id: String,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let successor = load_lock(deps.storage, &owner, &id)?
+        .rollover_successor
+        .ok_or(ContractError::RolloverNotSet {})?;
+
+    let mut lock = remove_expired_lock(deps.storage, &env, &owner, id.to_owned())?;
+    check_guardian(&deps.querier, &owner, &id, &lock)?;
+    let duration = lock.expire.seconds() - lock.create.seconds();
+    let expire = env.block.time.plus_seconds(duration);
+
+    let mut funds = lock.funds.clone();
+    let mut messages = withdraw_msgs(
+        deps.storage,
+        &deps.querier,
+        &env.contract.address,
+        &mut lock,
+        &mut funds,
+    )?;
+    let recipient = lock.recipient(&owner);
+    messages.push(lock_for_msg(&successor, recipient, &id, expire, funds)?);
+
+    let mut attributes = lock_attrs(events::ROLLOVER, &owner, &id, lock.expire);
+    attributes.extend(asset_attrs(&lock.funds));
+    attributes.push(attr("successor", successor));
+    attributes.push(attr("new_expire", expire));
+    attributes.push(attr("by", info.sender));
+    Ok(Response {
+        messages,
+        attributes,
+        ..Response::default()
+    })
+}
+
+/// The successor lock is created by a single LockFor, so the lock must hold either
+/// native coins only or a single cw20 token
+fn check_movable(funds: &GenericBalance) -> Result<(), ContractError> {
+    if funds.cw20.is_empty() || (funds.native.is_empty() && funds.cw20.len() == 1) {
+        Ok(())
+    } else {
+        Err(ContractError::RolloverUnsupported {})
+    }
+}
+
+fn lock_for_msg(
+    successor: &Addr,
+    owner: &Addr,
+    id: &str,
+    expire: Timestamp,
+    funds: GenericBalance,
+) -> Result<CosmosMsg, ContractError> {
+    check_movable(&funds)?;
+    let msg = match funds.cw20.into_iter().next() {
+        Some(token) => WasmMsg::Execute {
+            contract_addr: token.address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: successor.to_string(),
+                amount: token.amount,
+                msg: to_binary(&ReceiveMsg::LockFor {
+                    owner: owner.to_string(),
+                    id: id.to_owned(),
+                    expire,
+                })?,
+            })?,
+            send: vec![],
+        },
+        None => WasmMsg::Execute {
+            contract_addr: successor.to_string(),
+            msg: to_binary(&ExecuteMsg::LockFor {
+                owner: owner.to_string(),
+                id: id.to_owned(),
+                expire,
+            })?,
+            send: funds.native,
+        },
+    };
+    Ok(msg.into())
+}
+
+pub fn query_successor(deps: Deps) -> StdResult<SuccessorResponse> {
+    let successor = SUCCESSOR.may_load(deps.storage)?;
+
+    Ok(SuccessorResponse {
+        successor: successor.map(String::from),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::InstantiateMsg;
+    use cosmwasm_std::coins;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    #[test]
+    fn rollover_into_successor() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let owner = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let rollover = ExecuteMsg::SetRollover {
+            id: "1".into(),
+            rollover: true,
+        };
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), rollover.clone());
+        match res {
+            Err(ContractError::NoSuccessor {}) => {}
+            _ => panic!("Must return NoSuccessor error"),
+        }
+
+        let msg = ExecuteMsg::UpdateSuccessor {
+            successor: Some("lockbox_v2".into()),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), owner.clone(), msg).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), info, rollover).unwrap();
+        // opted in locks keep the successor they agreed to
+        let msg = ExecuteMsg::UpdateSuccessor {
+            successor: Some("other_lockbox".into()),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), owner, msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(150);
+        let process = ExecuteMsg::ProcessRollover {
+            owner: "anyone".into(),
+            id: "1".into(),
+        };
+        let keeper = mock_info("keeper", &[]);
+        let res = execute(deps.as_mut(), env, keeper, process).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![WasmMsg::Execute {
+                contract_addr: "lockbox_v2".into(),
+                msg: to_binary(&ExecuteMsg::LockFor {
+                    owner: "anyone".into(),
+                    id: "1".into(),
+                    expire: Timestamp::from_seconds(250),
+                })
+                .unwrap(),
+                send: coins(2, "token"),
+            }
+            .into()]
+        );
+        let lock = locks()
+            .may_load(&deps.storage, (&Addr::unchecked("anyone"), "1".into()))
+            .unwrap();
+        assert_eq!(None, lock);
+    }
+}
@@ -0,0 +1,197 @@
+use cosmwasm_std::{Deps, Empty, Env, StdResult, Storage};
+
+use crate::error::ContractError;
+use crate::msg::{ExecutionGuardResponse, TimeHighWaterResponse};
+use crate::state::{EXECUTION_GUARD, TIME_HIGH_WATER};
+
+/// Records the block time as high-water mark, failing if the chain time went backwards
+pub fn check_block_time(storage: &mut dyn Storage, env: &Env) -> Result<(), ContractError> {
+    if let Some(high_water) = TIME_HIGH_WATER.may_load(storage)? {
+        if env.block.time < high_water {
+            return Err(ContractError::TimeRegressed { high_water });
+        }
+        if env.block.time == high_water {
+            return Ok(());
+        }
+    }
+    TIME_HIGH_WATER.save(storage, &env.block.time)?;
+
+    Ok(())
+}
+
+/// Sets the execution guard, failing if an execution is already in progress.
+/// Msgs returned by execute only run once it returns, so this can only fail if a
+/// handler calls back into execute instead of the dispatch its nested msgs use
+pub fn enter_execution(storage: &mut dyn Storage) -> Result<(), ContractError> {
+    if EXECUTION_GUARD.may_load(storage)?.is_some() {
+        return Err(ContractError::ReentrantExecution {});
+    }
+    EXECUTION_GUARD.save(storage, &Empty {})?;
+
+    Ok(())
+}
+
+/// Clears the execution guard, failed executions don't need it as their writes are reverted
+pub fn exit_execution(storage: &mut dyn Storage) {
+    EXECUTION_GUARD.remove(storage);
+}
+
+pub fn query_time_high_water(deps: Deps) -> StdResult<TimeHighWaterResponse> {
+    Ok(TimeHighWaterResponse {
+        time: TIME_HIGH_WATER.may_load(deps.storage)?,
+    })
+}
+
+pub fn query_execution_guard(deps: Deps) -> StdResult<ExecutionGuardResponse> {
+    Ok(ExecutionGuardResponse {
+        entered: EXECUTION_GUARD.may_load(deps.storage)?.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::error::ContractError;
+    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coin, coins, from_binary, Timestamp};
+    use cw20::Denom;
+
+    #[test]
+    fn time_regression() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock funds
+        let info = mock_info("anyone", &coins(2, "token"));
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::Lock {
+            id: "2".into(),
+            expire: Some(Timestamp::from_seconds(2000)),
+            referrer: None,
+            amount: None,
+        };
+        env.block.time = Timestamp::from_seconds(1000);
+        let _res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &coins(2, "token")),
+            msg,
+        )
+        .unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::TimeHighWater {}).unwrap();
+        let value: TimeHighWaterResponse = from_binary(&res).unwrap();
+        assert_eq!(Some(Timestamp::from_seconds(1000)), value.time);
+
+        // unlock rejected while block time is behind the high-water mark
+        env.block.time = Timestamp::from_seconds(500);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::TimeRegressed { .. }) => {}
+            _ => panic!("Must return TimeRegressed error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(1000);
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn execution_guard() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+
+        // as if execute was called while another execution is in progress
+        enter_execution(&mut deps.storage).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        match res {
+            Err(ContractError::ReentrantExecution {}) => {}
+            _ => panic!("Must return ReentrantExecution error"),
+        }
+        exit_execution(&mut deps.storage);
+
+        let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ExecutionGuard {}).unwrap();
+        let value: ExecutionGuardResponse = from_binary(&res).unwrap();
+        assert!(!value.entered);
+    }
+
+    #[test]
+    fn state_written_before_payouts() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let lock = |id: &str| ExecuteMsg::Lock {
+            id: id.into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let funds = [coin(2, "token"), coin(3, "other")];
+        for id in ["1", "2", "3"].iter() {
+            let info = mock_info("anyone", &funds);
+            let _res = execute(deps.as_mut(), env.clone(), info, lock(id)).unwrap();
+        }
+
+        // the payouts only run once execute returns, a recipient calling back from them
+        // sees the written state and must not be paid again
+        env.block.time = Timestamp::from_seconds(100);
+        let info = mock_info("anyone", &[]);
+        let mut assert_paid_once = |msg: ExecuteMsg| {
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+            assert!(!res.messages.is_empty());
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+            assert!(res.map_or(true, |res| res.messages.is_empty()));
+        };
+        assert_paid_once(ExecuteMsg::ClaimAsset {
+            id: "1".into(),
+            asset: Denom::Native("token".into()),
+        });
+        assert_paid_once(ExecuteMsg::Unlock { id: "1".into() });
+        assert_paid_once(ExecuteMsg::UnlockMany {
+            ids: vec!["2".into(), "3".into()],
+        });
+    }
+}
@@ -0,0 +1,98 @@
+use cosmwasm_std::{Deps, StdResult, Timestamp};
+
+use crate::contract::to_cw20_coins;
+use crate::msg::{ScheduledUnlock, UnlockScheduleResponse};
+use crate::state::{locks, schedule_bucket};
+
+/// Locked funds by expire day, locks released early by a goal or hardship approval are
+/// counted on their expire day until unlocked
+pub fn query_unlock_schedule(
+    deps: Deps,
+    from: Timestamp,
+    to: Timestamp,
+) -> StdResult<UnlockScheduleResponse> {
+    let days: StdResult<Vec<_>> = locks()
+        .idx
+        .schedule
+        .range(deps.storage, schedule_bucket(from), to.seconds())
+        .map(|item| {
+            let (start, funds) = item?;
+            Ok(ScheduledUnlock {
+                start: Timestamp::from_seconds(start),
+                native_balance: funds.native,
+                cw20_balance: to_cw20_coins(funds.cw20),
+            })
+        })
+        .collect();
+
+    Ok(UnlockScheduleResponse { days: days? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use crate::state::SCHEDULE_BUCKET;
+    use cosmwasm_std::coins;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    #[test]
+    fn unlock_schedule() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 10 * SCHEDULE_BUCKET,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let day = |days: u64| Timestamp::from_seconds(days * SCHEDULE_BUCKET);
+        for (id, expire, amount) in &[("1", day(1), 2), ("2", day(1).plus_seconds(60), 3)] {
+            let msg = ExecuteMsg::Lock {
+                id: id.to_string(),
+                expire: Some(*expire),
+                referrer: None,
+                amount: None,
+            };
+            let info = mock_info("anyone", &coins(*amount, "token"));
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+        let msg = ExecuteMsg::Lock {
+            id: "3".into(),
+            expire: Some(day(3)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(4, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let value = query_unlock_schedule(deps.as_ref(), day(0), day(3)).unwrap();
+        assert_eq!(
+            vec![ScheduledUnlock {
+                start: day(1),
+                native_balance: coins(5, "token"),
+                cw20_balance: vec![],
+            }],
+            value.days
+        );
+
+        // unlocked funds leave the schedule
+        env.block.time = day(1).plus_seconds(1);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let _res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        let value = query_unlock_schedule(deps.as_ref(), day(1), day(4)).unwrap();
+        let amounts: Vec<_> = value
+            .days
+            .iter()
+            .map(|day| (day.start, day.native_balance.clone()))
+            .collect();
+        assert_eq!(
+            vec![(day(1), coins(3, "token")), (day(3), coins(4, "token"))],
+            amounts
+        );
+    }
+}
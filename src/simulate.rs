@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Addr, Coin, Deps, DepsMut, Env, MessageInfo, Order, Pair, StdResult, Storage};
+
+use crate::contract::execute;
+use crate::msg::{ExecuteMsg, SimulateError, SimulateResponse};
+
+/// Writes kept in memory on top of the contract storage, dropped with the simulation
+struct OverlayStorage<'a> {
+    base: &'a dyn Storage,
+    /// None marks a removed key
+    writes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl Storage for OverlayStorage<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.writes.get(key) {
+            Some(value) => value.clone(),
+            None => self.base.get(key),
+        }
+    }
+
+    fn range<'a>(
+        &'a self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Pair> + 'a> {
+        let mut merged: BTreeMap<_, _> = self.base.range(start, end, Order::Ascending).collect();
+        let in_range = |key: &[u8]| {
+            start.map_or(true, |start| key >= start) && end.map_or(true, |end| key < end)
+        };
+        for (key, value) in self.writes.iter().filter(|(key, _)| in_range(key)) {
+            match value {
+                Some(value) => merged.insert(key.clone(), value.clone()),
+                None => merged.remove(key),
+            };
+        }
+
+        let pairs: Vec<Pair> = merged.into_iter().collect();
+        match order {
+            Order::Ascending => Box::new(pairs.into_iter()),
+            Order::Descending => Box::new(pairs.into_iter().rev()),
+        }
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.writes.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.writes.insert(key.to_vec(), None);
+    }
+}
+
+/// Runs msg as sent by sender with funds, discarding its state changes. Replies are not run
+pub fn query_simulate(
+    deps: Deps,
+    env: Env,
+    sender: String,
+    funds: Vec<Coin>,
+    msg: ExecuteMsg,
+) -> StdResult<SimulateResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let mut storage = OverlayStorage {
+        base: deps.storage,
+        writes: BTreeMap::new(),
+    };
+    let deps = DepsMut {
+        storage: &mut storage,
+        api: deps.api,
+        querier: deps.querier,
+    };
+    let info = MessageInfo { sender, funds };
+
+    let res = match execute(deps, env, info, msg) {
+        Ok(res) => res,
+        Err(err) => {
+            return Ok(SimulateResponse {
+                ok: false,
+                error: Some(SimulateError {
+                    kind: err.kind().into(),
+                    message: err.to_string(),
+                }),
+                ..SimulateResponse::default()
+            });
+        }
+    };
+
+    let mut messages: Vec<_> = res.submessages.into_iter().map(|sub| sub.msg).collect();
+    messages.extend(res.messages);
+    Ok(SimulateResponse {
+        ok: true,
+        error: None,
+        messages,
+        attributes: res.attributes,
+        data: res.data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{instantiate, query};
+    use crate::msg::{InstantiateMsg, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{attr, coins, from_binary, Timestamp};
+
+    #[test]
+    fn simulate() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let lock = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let msg = QueryMsg::Simulate {
+            sender: "anyone".into(),
+            funds: coins(2, "token"),
+            msg: lock,
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let value: SimulateResponse = from_binary(&res).unwrap();
+        assert!(value.ok);
+        assert_eq!(attr("action", "lock"), value.attributes[0]);
+
+        // nothing was stored
+        let value = query_simulate(
+            deps.as_ref(),
+            env,
+            "anyone".into(),
+            vec![],
+            ExecuteMsg::Unlock { id: "1".into() },
+        )
+        .unwrap();
+        assert!(!value.ok);
+        assert_eq!("LockNotFound", value.error.unwrap().kind);
+    }
+}
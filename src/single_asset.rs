@@ -0,0 +1,104 @@
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+use cw20::Balance;
+
+use crate::balance::GenericBalance;
+use crate::contract::load_lock;
+use crate::error::ContractError;
+use crate::state::locks;
+
+/// Restricts the lock to the only asset it holds, deposits of any other asset are rejected
+pub fn try_set_single_asset(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    single_asset: bool,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    if single_asset && asset_count(&lock.funds) > 1 {
+        return Err(ContractError::AssetMismatch {});
+    }
+    lock.single_asset = single_asset;
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_single_asset"),
+            attr("from", info.sender),
+            attr("id", id),
+            attr("single_asset", single_asset),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Deposits must use the only asset held by funds, mixed positions come from UI bugs
+pub fn check_single_asset(funds: &GenericBalance, balance: &Balance) -> Result<(), ContractError> {
+    let mut merged = funds.clone();
+    merged.add_tokens(balance.clone());
+    if asset_count(&merged) > 1 {
+        return Err(ContractError::AssetMismatch {});
+    }
+    Ok(())
+}
+
+fn asset_count(funds: &GenericBalance) -> usize {
+    funds.native.len() + funds.cw20.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, Addr, Timestamp};
+
+    #[test]
+    fn single_asset_lock() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::SetSingleAsset {
+            id: "1".into(),
+            single_asset: true,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let increase = ExecuteMsg::IncreaseLock {
+            id: "1".into(),
+            min_remaining: None,
+        };
+        let info = mock_info("anyone", &coins(3, "other"));
+        let res = execute(deps.as_mut(), env.clone(), info, increase.clone());
+        match res {
+            Err(ContractError::AssetMismatch {}) => {}
+            _ => panic!("Must return AssetMismatch error"),
+        }
+        let info = mock_info("anyone", &coins(3, "token"));
+        let _res = execute(deps.as_mut(), env, info, increase).unwrap();
+
+        let owner = Addr::unchecked("anyone");
+        let lock = locks().load(&deps.storage, (&owner, "1".into())).unwrap();
+        assert_eq!(coins(5, "token"), lock.funds.native);
+    }
+}
@@ -0,0 +1,364 @@
+use cosmwasm_std::{attr, coin, Addr, Deps, Env, Order, Response, StdError, StdResult};
+use cw20::{BalanceResponse, Cw20QueryMsg, Denom};
+use cw_storage_plus::{Bound, U64Key};
+
+use crate::balance::{denom_balance, GenericBalance};
+use crate::contract::next_cursor;
+use crate::error::ContractError;
+use crate::msg::{AssetSolvency, SolvencyResponse, YieldAdapterQueryMsg, YieldBalanceResponse};
+use crate::state::{
+    locks, AIRDROPS, EXTENSION_BONUSES, EXTENSION_CAMPAIGNS, LOCKED_SUM, LOCKED_TOTALS, STREAMS,
+    VESTING_GROUPS, YIELD_ADAPTERS, YIELD_PRINCIPAL,
+};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Compares per asset what the contract owes to locks, airdrops, streams, vesting groups and
+/// extension campaigns with what it holds, native coins deposited into yield adapters count as held
+pub fn query_solvency(deps: Deps, env: Env) -> StdResult<SolvencyResponse> {
+    let liabilities = total_liabilities(deps)?;
+    let contract = env.contract.address;
+
+    let mut assets = vec![];
+    let mut natives = deps.querier.query_all_balances(&contract)?;
+    for locked in liabilities.native.iter() {
+        if !natives.iter().any(|held| held.denom == locked.denom) {
+            natives.push(coin(0, &locked.denom));
+        }
+    }
+    for held in natives {
+        let denom = Denom::Native(held.denom.clone());
+        let mut balance = held.amount;
+        if let Some(adapter) = YIELD_ADAPTERS.may_load(deps.storage, &held.denom)? {
+            let res: YieldBalanceResponse = deps.querier.query_wasm_smart(
+                adapter,
+                &YieldAdapterQueryMsg::Balance {
+                    address: contract.to_string(),
+                },
+            )?;
+            balance += res.amount;
+        }
+        assets.push(AssetSolvency {
+            liabilities: liabilities.amount_of(&denom),
+            denom,
+            balance,
+        });
+    }
+    for token in liabilities.cw20.iter() {
+        let res: BalanceResponse = deps.querier.query_wasm_smart(
+            &token.address,
+            &Cw20QueryMsg::Balance {
+                address: contract.to_string(),
+            },
+        )?;
+        assets.push(AssetSolvency {
+            denom: Denom::Cw20(token.address.clone()),
+            liabilities: token.amount,
+            balance: res.balance,
+        });
+    }
+
+    Ok(SolvencyResponse { assets })
+}
+
+/// Sum of active lock funds, remaining airdrop allocations, stream funds not withdrawn yet,
+/// vesting group deposits and extension campaign budgets, reserved bonuses included
+pub fn total_liabilities(deps: Deps) -> StdResult<GenericBalance> {
+    let mut total = LOCKED_SUM.may_load(deps.storage)?.unwrap_or_default();
+    for item in AIRDROPS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, airdrop) = item?;
+        total.add_tokens(denom_balance(&airdrop.denom, airdrop.remaining));
+    }
+    for item in STREAMS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, stream) = item?;
+        let remaining = stream.deposit.checked_sub(stream.withdrawn)?;
+        total.add_tokens(denom_balance(&stream.denom, remaining));
+    }
+    for item in VESTING_GROUPS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, group) = item?;
+        total.add_tokens(denom_balance(&group.denom, group.deposit));
+    }
+    for item in EXTENSION_CAMPAIGNS.range(deps.storage, None, None, Order::Ascending) {
+        let (key, campaign) = item?;
+        let mut reserved = campaign.remaining;
+        let mut campaign_id = [0u8; 8];
+        campaign_id.copy_from_slice(&key);
+        for bonus in EXTENSION_BONUSES
+            .prefix(U64Key::new(u64::from_be_bytes(campaign_id)))
+            .range(deps.storage, None, None, Order::Ascending)
+        {
+            reserved += bonus?.1.amount;
+        }
+        if !reserved.is_zero() {
+            total.add_tokens(denom_balance(&campaign.bonus_denom, reserved));
+        }
+    }
+
+    Ok(total)
+}
+
+/// Fails if the contract is insolvent or the locked totals of the limit owners after
+/// start_after disagree with their locks. The contract wide checks only run with the first
+/// page, the next one starts after the next_start_after attribute
+pub fn try_assert_invariants(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if start_after.is_none() {
+        for asset in query_solvency(deps, env)?.assets {
+            if !is_solvent(&asset) {
+                return Err(ContractError::InvariantViolated {
+                    reason: format!("insolvent {}", denom_label(&asset.denom)),
+                });
+            }
+        }
+
+        // adapter principal is backed by locks
+        let locked = LOCKED_SUM.may_load(deps.storage)?.unwrap_or_default();
+        for item in YIELD_PRINCIPAL.range(deps.storage, None, None, Order::Ascending) {
+            let (denom, principal) = item?;
+            let denom = Denom::Native(String::from_utf8(denom).map_err(StdError::from)?);
+            if principal > locked.amount_of(&denom) {
+                return Err(ContractError::InvariantViolated {
+                    reason: format!("yield principal {}", denom_label(&denom)),
+                });
+            }
+        }
+    }
+
+    // locked totals match the locks of each owner
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let page: StdResult<Vec<_>> = LOCKED_TOTALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect();
+    let mut page = page?;
+    let next_start_after = next_cursor(&mut page, limit, |(owner, _)| owner.clone());
+    for (owner, totals) in page {
+        let owner = Addr::unchecked(String::from_utf8(owner).map_err(StdError::from)?);
+        let mut funds = GenericBalance::default();
+        for lock in locks()
+            .prefix(&owner)
+            .range(deps.storage, None, None, Order::Ascending)
+        {
+            funds.add_balance(lock?.1.funds);
+        }
+        if !funds.same_amounts(&totals) {
+            return Err(ContractError::InvariantViolated {
+                reason: format!("locked totals of {}", owner),
+            });
+        }
+    }
+
+    let mut attributes = vec![attr("action", "assert_invariants")];
+    if let Some(owner) = next_start_after {
+        let owner = String::from_utf8(owner).map_err(StdError::from)?;
+        attributes.push(attr("next_start_after", owner));
+    }
+    Ok(Response {
+        attributes,
+        ..Response::default()
+    })
+}
+
+fn denom_label(denom: &Denom) -> String {
+    match denom {
+        Denom::Native(denom) => denom.to_owned(),
+        Denom::Cw20(address) => address.to_string(),
+    }
+}
+
+/// Held funds cover the liabilities
+pub fn is_solvent(asset: &AssetSolvency) -> bool {
+    asset.balance >= asset.liabilities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::mock_querier::mock_dependencies_with_wasm;
+    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, to_binary, ContractResult, Timestamp, Uint128};
+    use cw20::Cw20ReceiveMsg;
+
+    #[test]
+    fn solvency() {
+        let mut deps = mock_dependencies_with_wasm(&coins(5, "token"));
+        deps.querier.update_wasm(|_, _| {
+            ContractResult::Ok(
+                to_binary(&BalanceResponse {
+                    balance: Uint128::new(7),
+                })
+                .unwrap(),
+            )
+        });
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(5, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "anyone".into(),
+            amount: Uint128::new(10),
+            msg: to_binary(&ReceiveMsg::Lock {
+                id: "2".into(),
+                expire: Some(Timestamp::from_seconds(400)),
+                referrer: None,
+            })
+            .unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env, mock_info("savings", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+        let value: SolvencyResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.assets,
+            vec![
+                AssetSolvency {
+                    denom: Denom::Native("token".into()),
+                    liabilities: Uint128::new(5),
+                    balance: Uint128::new(5),
+                },
+                AssetSolvency {
+                    denom: Denom::Cw20(Addr::unchecked("savings")),
+                    liabilities: Uint128::new(10),
+                    balance: Uint128::new(7),
+                }
+            ]
+        );
+        assert!(is_solvent(&value.assets[0]));
+        assert!(!is_solvent(&value.assets[1]));
+
+        let msg = ExecuteMsg::AssertInvariants {
+            start_after: None,
+            limit: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::InvariantViolated { .. }) => {}
+            _ => panic!("Must return InvariantViolated error"),
+        }
+
+        deps.querier.update_wasm(|_, _| {
+            ContractResult::Ok(
+                to_binary(&BalanceResponse {
+                    balance: Uint128::new(10),
+                })
+                .unwrap(),
+            )
+        });
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("bot", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn vesting_group_liabilities() {
+        let mut deps = mock_dependencies_with_wasm(&coins(1000, "token"));
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::CreateVestingGroup {
+            admin: None,
+            schedules: vec![],
+        };
+        let info = mock_info("team", &coins(1000, "token"));
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+        let value: SolvencyResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.assets,
+            vec![AssetSolvency {
+                denom: Denom::Native("token".into()),
+                liabilities: Uint128::new(1000),
+                balance: Uint128::new(1000),
+            }]
+        );
+        let msg = ExecuteMsg::AssertInvariants {
+            start_after: None,
+            limit: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("bot", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn paged_invariants() {
+        let mut deps = mock_dependencies_with_wasm(&coins(5, "token"));
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        for (owner, amount) in [("alice", 2), ("bob", 3)].iter() {
+            let info = mock_info(owner, &coins(*amount, "token"));
+            let _res = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+        }
+        let sum = LOCKED_SUM.load(&deps.storage).unwrap();
+        assert_eq!(coins(5, "token"), sum.native);
+
+        // as if bob's totals missed a deposit
+        let bob = Addr::unchecked("bob");
+        let totals = GenericBalance {
+            native: coins(2, "token"),
+            cw20: vec![],
+        };
+        LOCKED_TOTALS
+            .save(&mut deps.storage, &bob, &totals, env.block.height)
+            .unwrap();
+
+        let check = |start_after: Option<&str>| ExecuteMsg::AssertInvariants {
+            start_after: start_after.map(String::from),
+            limit: Some(1),
+        };
+        let info = mock_info("bot", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), check(None)).unwrap();
+        assert_eq!(attr("next_start_after", "alice"), res.attributes[1]);
+        let res = execute(deps.as_mut(), env, info, check(Some("alice")));
+        match res {
+            Err(ContractError::InvariantViolated { reason }) => {
+                assert_eq!("locked totals of bob", reason)
+            }
+            _ => panic!("Must return InvariantViolated error"),
+        }
+    }
+}
@@ -1,14 +1,83 @@
+use std::collections::BTreeSet;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::balance::GenericBalance;
-use cosmwasm_std::{Addr, Timestamp};
-use cw_storage_plus::{Item, Map};
+use crate::msg::{DenomMetadata, HardshipRequest, MultiDenomPolicy, RateLimit, Role};
+use cosmwasm_std::{Addr, Binary, Coin, Empty, Order, StdResult, Storage, Timestamp, Uint128};
+use cw20::{Balance, Denom};
+use cw_storage_plus::{
+    Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, Prefix, PrimaryKey, SnapshotMap,
+    Strategy, U64Key,
+};
+
+pub const DEFAULT_MAX_ASSETS: u32 = 10;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub max_lock_time: u64,
     pub owner: Addr,
+    /// Max native coins per lock
+    #[serde(default = "default_max_assets")]
+    pub max_native_coins: u32,
+    /// Max cw20 tokens per lock
+    #[serde(default = "default_max_assets")]
+    pub max_cw20_tokens: u32,
+    /// Seconds after expire reserved to the lock owner
+    #[serde(default)]
+    pub grace_period: u64,
+    /// Max lock creations per owner per window
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+    /// Max active locks in the contract, zero is unlimited
+    #[serde(default)]
+    pub max_active_locks: u64,
+    /// Lock time used when Lock omits expire, zero requires expire
+    #[serde(default)]
+    pub default_lock_time: u64,
+    /// Min amount of each asset deposited by IncreaseLock
+    #[serde(default)]
+    pub min_increase: Uint128,
+    /// Seconds between announcing and executing EmergencyWithdraw, none disables it for good
+    #[serde(default)]
+    pub emergency_delay: Option<u64>,
+    /// Owner powers are disabled for the contract lifetime
+    #[serde(default)]
+    pub immutable: bool,
+    /// Contracts must be in RECIPIENT_ALLOWLIST to receive lock funds, see check_recipient
+    #[serde(default)]
+    pub restrict_contract_recipients: bool,
+    /// Cw20 token reported by the Balance query
+    #[serde(default)]
+    pub gating_token: Option<Addr>,
+    /// Cw20 increases must use the only asset the lock holds, see Lock.single_asset
+    #[serde(default)]
+    pub single_asset_mode: bool,
+    /// Locks are expired at their expire second instead of after it, see Lock.is_expired
+    #[serde(default)]
+    pub inclusive_expire: bool,
+    /// Handling of locks holding more than one asset
+    #[serde(default)]
+    pub multi_denom_policy: MultiDenomPolicy,
+}
+
+impl State {
+    /// Sender can use owner powers, never true in immutable deployments
+    pub fn is_owner(&self, sender: &Addr) -> bool {
+        !self.immutable && *sender == self.owner
+    }
+}
+
+/// Locks created by owner in the current window
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateWindow {
+    pub start: Timestamp,
+    pub count: u32,
+}
+
+fn default_max_assets() -> u32 {
+    DEFAULT_MAX_ASSETS
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -16,7 +85,1056 @@ pub struct Lock {
     pub create: Timestamp,
     pub expire: Timestamp,
     pub funds: GenericBalance,
+    /// Swap executed on unlock
+    #[serde(default)]
+    pub swap: Option<Swap>,
+    /// Deposit into another contract on unlock
+    #[serde(default)]
+    pub deposit_hook: Option<DepositHook>,
+    /// Contract notified on unlock
+    #[serde(default)]
+    pub unlock_callback: Option<UnlockCallback>,
+    /// Contract approving the release of the lock funds, see guardian
+    #[serde(default)]
+    pub guardian: Option<Addr>,
+    /// Moved into the successor lockbox once expired instead of paid out, see rollover
+    #[serde(default)]
+    pub rollover: bool,
+    /// Successor lockbox when the lock opted in to rollover, later changes don't apply
+    #[serde(default)]
+    pub rollover_successor: Option<Addr>,
+    /// Lending contract holding the lock as collateral, see lending
+    #[serde(default)]
+    pub encumbrance: Option<Addr>,
+    /// Lending contract the owner allows to encumber the lock
+    #[serde(default)]
+    pub approved_lender: Option<Addr>,
+    /// Owners required to approve unlock
+    #[serde(default)]
+    pub joint: Option<Joint>,
+    /// Recurring cw20 deposits pulled from the owner allowance
+    #[serde(default)]
+    pub plan: Option<Plan>,
+    /// Target amount allowing unlock before expire
+    #[serde(default)]
+    pub goal: Option<Goal>,
+    /// Receives the funds on unlock instead of the owner
+    #[serde(default)]
+    pub beneficiary: Option<Addr>,
+    /// Beneficiary change waiting for the other party approval
+    #[serde(default)]
+    pub beneficiary_proposal: Option<BeneficiaryProposal>,
+    /// Opaque note set by the owner, see note::MAX_NOTE_SIZE
+    #[serde(default)]
+    pub note: Option<Binary>,
+    /// Balances are only queryable with the owner viewing key
+    #[serde(default)]
+    pub private: bool,
+    /// Payload pulled by notifiers before expire, see reminder::MAX_REMINDER_SIZE
+    #[serde(default)]
+    pub reminder: Option<String>,
+    /// Early unlock request, the lock is releasable once approved
+    #[serde(default)]
+    pub hardship: Option<HardshipRequest>,
+    /// Deposits must use the only asset the lock holds
+    #[serde(default)]
+    pub single_asset: bool,
+    /// Partial withdrawals granted to spenders after expire
+    #[serde(default)]
+    pub allowances: Vec<WithdrawAllowance>,
+    /// Assets released first on partial claims
+    #[serde(default)]
+    pub asset_order: Vec<Denom>,
+    /// Warnings issued while the lock is abandoned, see escheat
+    #[serde(default)]
+    pub abandon_warnings: Option<AbandonWarnings>,
+    /// Native coins of the lock deposited into yield adapters
+    #[serde(default)]
+    pub yield_principal: Vec<Coin>,
+}
+
+/// Fee rewards accrued by a lock and not credited yet
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LpRewards {
+    /// Recipient of the lock when the rewards last accrued, paid once the lock is released
+    pub recipient: Addr,
+    pub rewards: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AbandonWarnings {
+    pub count: u32,
+    pub first: Timestamp,
+    pub last: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WithdrawAllowance {
+    pub spender: Addr,
+    pub asset: Denom,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BeneficiaryProposal {
+    pub beneficiary: Addr,
+    /// Owner or current beneficiary
+    pub proposer: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Goal {
+    pub denom: Denom,
+    pub amount: Uint128,
+    /// Unlock requires both expire and goal instead of either
+    pub require_expire: bool,
+}
+
+impl Goal {
+    pub fn is_reached(&self, funds: &GenericBalance) -> bool {
+        funds.amount_of(&self.denom) >= self.amount
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Plan {
+    pub token: Addr,
+    pub amount: Uint128,
+    /// Seconds between deposits
+    pub interval: u64,
+    /// Next deposit is due at this time
+    pub next: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Joint {
+    pub owners: Vec<Addr>,
+    pub threshold: u32,
+    pub approvals: Vec<Addr>,
+}
+
+impl Joint {
+    pub fn is_approved(&self) -> bool {
+        self.approvals.len() >= self.threshold as usize
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositHook {
+    pub contract: Addr,
+    /// Locked token deposited into contract
+    pub denom: Denom,
+    /// Execute msg template, see hook::AMOUNT_PLACEHOLDER
+    pub msg: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnlockCallback {
+    pub contract: Addr,
+    /// Payload passed back to contract, see callback::MAX_CALLBACK_MSG_SIZE
+    pub msg: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Swap {
+    pub router: Addr,
+    /// Locked token sent to the router
+    pub offer: Denom,
+    /// Token received from the router
+    pub ask: Denom,
+    pub min_receive: Uint128,
+}
+
+/// Announced drain of the contract funds, executable once the delay is over
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmergencyWithdrawal {
+    pub recipient: Addr,
+    pub executable_at: Timestamp,
+}
+
+/// Replacement of a defunct cw20 token, paid on unlock through the converter
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenMigration {
+    pub new_token: Addr,
+    pub converter: Addr,
+    /// Releases go through the converter from this time on
+    #[serde(default)]
+    pub active_at: Timestamp,
+}
+
+/// Old tokens sent to a converter paying the new token to this contract, which forwards
+/// the new tokens to recipient once balance grows by at least amount
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingConversion {
+    pub recipient: Addr,
+    pub new_token: Addr,
+    pub amount: Uint128,
+    /// Contract balance of the new token before the conversion
+    pub balance: Uint128,
+}
+
+/// Lock index rebuild in progress, msgs but RebuildLockIndexes fail until it is done
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IndexRebuild {
+    /// Primary key of the last indexed lock
+    pub start_after: Option<Vec<u8>>,
+}
+
+/// Funds sent to the swap router, paid back to recipient if the swap fails
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingSwap {
+    pub recipient: Addr,
+    pub funds: GenericBalance,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Airdrop {
+    /// Hex encoded sha256 merkle root of (address, amount) leaves
+    pub merkle_root: String,
+    /// Seconds a claimed allocation stays locked
+    pub lock_time: u64,
+    pub denom: Denom,
+    /// Amount left to be claimed
+    pub remaining: Uint128,
+}
+
+/// Tokens paid to recipient at a constant rate, sablier-style
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Stream {
+    pub funder: Addr,
+    pub recipient: Addr,
+    pub denom: Denom,
+    /// Total amount streamed once the stream ends
+    pub deposit: Uint128,
+    /// Tokens per second
+    pub rate: Uint128,
+    /// Streaming at rate since start, it moves on every top-up or rate change
+    pub start: Timestamp,
+    /// Tokens streamed before start
+    pub settled: Uint128,
+    pub withdrawn: Uint128,
+    /// Lower rate proposed by the funder, waiting for the recipient consent
+    pub pending_rate: Option<Uint128>,
+}
+
+impl Stream {
+    /// Tokens streamed to the recipient by time, including the withdrawn ones.
+    /// Times before the last top-up or rate change return the tokens settled on it
+    pub fn streamed(&self, time: Timestamp) -> Uint128 {
+        let elapsed = time.seconds().saturating_sub(self.start.seconds());
+        let streamed = self
+            .rate
+            .u128()
+            .saturating_mul(elapsed.into())
+            .saturating_add(self.settled.u128());
+        Uint128::from(streamed.min(self.deposit.u128()))
+    }
+
+    /// Restarts the stream at time keeping the tokens streamed so far,
+    /// so changing deposit or rate only affects the tokens streamed from now on
+    pub fn settle(&mut self, time: Timestamp) {
+        if time > self.start {
+            self.settled = self.streamed(time);
+            self.start = time;
+        }
+    }
+
+    pub fn withdrawable(&self, time: Timestamp) -> Uint128 {
+        Uint128::from(self.streamed(time).u128() - self.withdrawn.u128())
+    }
+}
+
+/// Pool of a single token split among recipients, each vesting on its own schedule
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingGroup {
+    pub admin: Addr,
+    pub denom: Denom,
+    /// Tokens held for the group, claimed and withdrawn tokens are deducted
+    pub deposit: Uint128,
+    /// Tokens owed to the recipients and not claimed yet
+    pub allocated: Uint128,
+}
+
+impl VestingGroup {
+    /// Tokens not allocated to a schedule, withdrawable by the admin
+    pub fn available(&self) -> Uint128 {
+        Uint128::from(self.deposit.u128().saturating_sub(self.allocated.u128()))
+    }
+}
+
+/// Linear vesting from start to end, nothing vests before the cliff
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingSchedule {
+    pub amount: Uint128,
+    pub start: Timestamp,
+    pub cliff: Option<Timestamp>,
+    pub end: Timestamp,
+    pub claimed: Uint128,
+}
+
+impl VestingSchedule {
+    pub fn vested(&self, time: Timestamp) -> Uint128 {
+        if time < self.cliff.unwrap_or(self.start) || time <= self.start {
+            return Uint128::zero();
+        }
+        if time >= self.end {
+            return self.amount;
+        }
+        let elapsed = time.seconds() - self.start.seconds();
+        let duration = self.end.seconds() - self.start.seconds();
+        self.amount.multiply_ratio(elapsed, duration)
+    }
+
+    pub fn claimable(&self, time: Timestamp) -> Uint128 {
+        Uint128::from(self.vested(time).u128().saturating_sub(self.claimed.u128()))
+    }
+}
+
+impl Lock {
+    /// Funds can be released once block time is past expire, or reaches it if inclusive
+    pub fn is_expired(&self, time: Timestamp, inclusive: bool) -> bool {
+        if inclusive {
+            time.ge(&self.expire)
+        } else {
+            time.gt(&self.expire)
+        }
+    }
+
+    /// Address receiving the funds on unlock
+    pub fn recipient<'a>(&'a self, owner: &'a Addr) -> &'a Addr {
+        self.beneficiary.as_ref().unwrap_or(owner)
+    }
+
+    /// Funds can be released once expired and/or the goal is reached, or on approved hardship
+    pub fn is_releasable(&self, time: Timestamp, inclusive: bool) -> bool {
+        if matches!(&self.hardship, Some(hardship) if hardship.approval.is_some()) {
+            return true;
+        }
+        match &self.goal {
+            Some(goal) if goal.require_expire => {
+                self.is_expired(time, inclusive) && goal.is_reached(&self.funds)
+            }
+            Some(goal) => self.is_expired(time, inclusive) || goal.is_reached(&self.funds),
+            None => self.is_expired(time, inclusive),
+        }
+    }
 }
 
 pub const STATE: Item<State> = Item::new("state");
-pub const LOCKS: Map<(&Addr, String), Lock> = Map::new("locks");
+
+pub struct LockIndexes<'a> {
+    /// Locks by owner address
+    pub owner: MultiIndex<'a, (Vec<u8>, Vec<u8>), Lock>,
+    /// Locks by expire seconds
+    pub expire: MultiIndex<'a, (U64Key, Vec<u8>), Lock>,
+    /// Locks by every held native denom and cw20 token
+    pub asset: AssetIndex<'a>,
+    /// Locks with a reminder by expire seconds
+    pub reminder: ReminderIndex<'a>,
+    /// Lock funds summed by expire day
+    pub schedule: ScheduleIndex<'a>,
+    /// LP token locked in total and fee rewards accrued per lock
+    pub lp_rewards: LpRewardIndex<'a>,
+}
+
+impl<'a> IndexList<Lock> for LockIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Lock>> + '_> {
+        let v: Vec<&dyn Index<Lock>> = vec![
+            &self.owner,
+            &self.expire,
+            &self.asset,
+            &self.reminder,
+            &self.schedule,
+            &self.lp_rewards,
+        ];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Locks by (owner, id), stored under the same namespace as the former plain map
+pub fn locks<'a>() -> IndexedMap<'a, (&'a Addr, String), Lock, LockIndexes<'a>> {
+    let indexes = LockIndexes {
+        owner: MultiIndex::new(|_, pk| (pk_owner(&pk), pk), "locks", "locks__owner"),
+        expire: MultiIndex::new(
+            |lock, pk| (U64Key::new(lock.expire.seconds()), pk),
+            "locks",
+            "locks__expire",
+        ),
+        asset: AssetIndex::new("locks__asset"),
+        reminder: ReminderIndex::new("locks__reminder"),
+        schedule: ScheduleIndex::new("locks__schedule"),
+        lp_rewards: LpRewardIndex::new(
+            "locks__lp_totals",
+            "locks__lp_checkpoints",
+            "locks__lp_owed",
+        ),
+    };
+    IndexedMap::new("locks", indexes)
+}
+
+/// Owner address of a joined (owner, id) primary key
+fn pk_owner(pk: &[u8]) -> Vec<u8> {
+    let len = u16::from_be_bytes([pk[0], pk[1]]) as usize;
+    pk[2..2 + len].to_vec()
+}
+
+/// Owner and id of a joined (owner, id) primary key
+pub fn split_lock_pk(pk: &[u8]) -> StdResult<(Addr, String)> {
+    let owner = pk_owner(pk);
+    let id = pk[2 + owner.len()..].to_vec();
+
+    Ok((
+        Addr::unchecked(String::from_utf8(owner)?),
+        String::from_utf8(id)?,
+    ))
+}
+
+/// Starts indexing and counting every lock again, locks stored before the indexes have none
+pub fn start_index_rebuild(storage: &mut dyn Storage) -> StdResult<()> {
+    // the schedule and LP totals sum the saved locks, so they are cleared rather than saved over
+    locks().idx.schedule.clear(storage)?;
+    locks().idx.lp_rewards.clear_totals(storage)?;
+    ACTIVE_LOCKS.save(storage, &0)?;
+    LOCKED_SUM.save(storage, &GenericBalance::default())?;
+    INDEX_REBUILD.save(storage, &IndexRebuild { start_after: None })
+}
+
+/// Saves the index entries, owner counts and totals, active count and locked sum of the next
+/// limit locks of a started rebuild, returning how many were indexed. The rebuild is removed
+/// once every lock is indexed
+pub fn rebuild_lock_indexes(
+    storage: &mut dyn Storage,
+    height: u64,
+    limit: usize,
+) -> StdResult<u64> {
+    let rebuild = match INDEX_REBUILD.may_load(storage)? {
+        Some(rebuild) => rebuild,
+        None => return Ok(0),
+    };
+    let locks = locks();
+    let start = rebuild.start_after.map(Bound::exclusive);
+    let page: StdResult<Vec<_>> = locks
+        .range(storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect();
+    let mut page = page?;
+    let done = page.len() <= limit;
+    page.truncate(limit);
+
+    let mut owners = BTreeSet::new();
+    let mut sum = LOCKED_SUM.may_load(storage)?.unwrap_or_default();
+    for (pk, lock) in page.iter() {
+        for index in locks.idx.get_indexes() {
+            index.save(storage, pk, lock)?;
+        }
+        owners.insert(split_lock_pk(pk)?.0);
+        sum.add_balance(lock.funds.clone());
+    }
+    LOCKED_SUM.save(storage, &sum)?;
+    // an owner's locks can span pages, so they are counted and summed from storage
+    for owner in owners {
+        let mut count = 0u64;
+        let mut totals = GenericBalance::default();
+        for item in locks()
+            .prefix(&owner)
+            .range(storage, None, None, Order::Ascending)
+        {
+            count += 1;
+            totals.add_balance(item?.1.funds);
+        }
+        LOCK_COUNTS.save(storage, &owner, &count)?;
+        LOCKED_TOTALS.save(storage, &owner, &totals, height)?;
+    }
+    ACTIVE_LOCKS.update(storage, |count| -> StdResult<_> {
+        Ok(count + page.len() as u64)
+    })?;
+
+    match page.last() {
+        Some((pk, _)) if !done => INDEX_REBUILD.save(
+            storage,
+            &IndexRebuild {
+                start_after: Some(pk.clone()),
+            },
+        )?,
+        _ => INDEX_REBUILD.remove(storage),
+    }
+    Ok(page.len() as u64)
+}
+
+/// Indexes a lock once per held asset, (asset_key, pk) -> Empty
+pub struct AssetIndex<'a> {
+    idx_map: Map<'a, (Vec<u8>, Vec<u8>), Empty>,
+}
+
+impl<'a> AssetIndex<'a> {
+    pub const fn new(idx_namespace: &'a str) -> Self {
+        AssetIndex {
+            idx_map: Map::new(idx_namespace),
+        }
+    }
+
+    /// Primary keys of the locks holding asset
+    pub fn prefix(&self, asset: &Denom) -> Prefix<Empty> {
+        self.idx_map.prefix(asset_key(asset))
+    }
+}
+
+impl<'a> Index<Lock> for AssetIndex<'a> {
+    fn save(&self, store: &mut dyn Storage, pk: &[u8], data: &Lock) -> StdResult<()> {
+        for asset in lock_assets(data) {
+            self.idx_map.save(store, (asset, pk.to_vec()), &Empty {})?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, pk: &[u8], old_data: &Lock) -> StdResult<()> {
+        for asset in lock_assets(old_data) {
+            self.idx_map.remove(store, (asset, pk.to_vec()));
+        }
+        Ok(())
+    }
+}
+
+/// Indexes the locks with a reminder, (expire, pk) -> Empty
+pub struct ReminderIndex<'a> {
+    idx_map: Map<'a, (U64Key, Vec<u8>), Empty>,
+}
+
+impl<'a> ReminderIndex<'a> {
+    pub const fn new(idx_namespace: &'a str) -> Self {
+        ReminderIndex {
+            idx_map: Map::new(idx_namespace),
+        }
+    }
+
+    /// Expire seconds and primary key of the locks with a reminder, bounds from reminder_bound
+    pub fn range<'c>(
+        &self,
+        store: &'c dyn Storage,
+        min: Option<Bound>,
+        max: Option<Bound>,
+    ) -> Box<dyn Iterator<Item = (u64, Vec<u8>)> + 'c> {
+        let keys = self
+            .idx_map
+            .keys(store, min, max, Order::Ascending)
+            .map(|key| {
+                let mut expire = [0u8; 8];
+                expire.copy_from_slice(&key[2..10]);
+                (u64::from_be_bytes(expire), key[10..].to_vec())
+            });
+        Box::new(keys)
+    }
+}
+
+impl<'a> Index<Lock> for ReminderIndex<'a> {
+    fn save(&self, store: &mut dyn Storage, pk: &[u8], data: &Lock) -> StdResult<()> {
+        if data.reminder.is_some() {
+            let key = (U64Key::new(data.expire.seconds()), pk.to_vec());
+            self.idx_map.save(store, key, &Empty {})?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, pk: &[u8], old_data: &Lock) -> StdResult<()> {
+        let key = (U64Key::new(old_data.expire.seconds()), pk.to_vec());
+        self.idx_map.remove(store, key);
+        Ok(())
+    }
+}
+
+/// Seconds of the schedule buckets, locks are summed by expire day
+pub const SCHEDULE_BUCKET: u64 = 86_400;
+
+/// Sums the lock funds by expire bucket, bucket start seconds -> funds
+pub struct ScheduleIndex<'a> {
+    idx_map: Map<'a, U64Key, GenericBalance>,
+}
+
+impl<'a> ScheduleIndex<'a> {
+    pub const fn new(idx_namespace: &'a str) -> Self {
+        ScheduleIndex {
+            idx_map: Map::new(idx_namespace),
+        }
+    }
+
+    /// Start seconds and funds of the buckets starting from min up to max, exclusive
+    pub fn range<'c>(
+        &self,
+        store: &'c dyn Storage,
+        min: u64,
+        max: u64,
+    ) -> Box<dyn Iterator<Item = StdResult<(u64, GenericBalance)>> + 'c> {
+        let min = Bound::inclusive(U64Key::new(min).wrapped);
+        let max = Bound::exclusive(U64Key::new(max).wrapped);
+        let buckets = self
+            .idx_map
+            .range(store, Some(min), Some(max), Order::Ascending)
+            .map(|item| {
+                let (key, funds) = item?;
+                let mut start = [0u8; 8];
+                start.copy_from_slice(&key);
+                Ok((u64::from_be_bytes(start), funds))
+            });
+        Box::new(buckets)
+    }
+
+    pub fn clear(&self, store: &mut dyn Storage) -> StdResult<()> {
+        let keys: Vec<_> = self
+            .idx_map
+            .keys(store, None, None, Order::Ascending)
+            .collect();
+        for key in keys {
+            self.idx_map.remove(store, U64Key::from(key));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Index<Lock> for ScheduleIndex<'a> {
+    fn save(&self, store: &mut dyn Storage, _pk: &[u8], data: &Lock) -> StdResult<()> {
+        if data.private {
+            return Ok(());
+        }
+        let key = U64Key::new(schedule_bucket(data.expire));
+        self.idx_map.update(store, key, |funds| -> StdResult<_> {
+            let mut funds = funds.unwrap_or_default();
+            funds.add_balance(data.funds.clone());
+            Ok(funds)
+        })?;
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, _pk: &[u8], old_data: &Lock) -> StdResult<()> {
+        if old_data.private {
+            return Ok(());
+        }
+        let start = schedule_bucket(old_data.expire);
+        let mut funds = self
+            .idx_map
+            .may_load(store, U64Key::new(start))?
+            .unwrap_or_default();
+        let mut old_funds = old_data.funds.clone();
+        old_funds.native.retain(|coin| !coin.amount.is_zero());
+        old_funds.cw20.retain(|token| !token.amount.is_zero());
+        funds.sub_balance(&old_funds)?;
+
+        if funds.native.is_empty() && funds.cw20.is_empty() {
+            self.idx_map.remove(store, U64Key::new(start));
+        } else {
+            self.idx_map.save(store, U64Key::new(start), &funds)?;
+        }
+        Ok(())
+    }
+}
+
+/// Keeps the total of each cw20 token held by the locks, and accrues the fee rewards of the
+/// locks holding an LP token whenever their balance changes. Rewards are accrued from the
+/// index of the token since the lock checkpoint, so no other lock is loaded
+pub struct LpRewardIndex<'a> {
+    /// token -> locked total
+    totals: Map<'a, Vec<u8>, Uint128>,
+    /// (pk, token) -> reward index when the lock last accrued
+    checkpoints: Map<'a, (Vec<u8>, Vec<u8>), Vec<Coin>>,
+    /// pk -> accrued rewards
+    owed: Map<'a, Vec<u8>, LpRewards>,
+}
+
+impl<'a> LpRewardIndex<'a> {
+    pub const fn new(
+        totals_namespace: &'a str,
+        checkpoints_namespace: &'a str,
+        owed_namespace: &'a str,
+    ) -> Self {
+        LpRewardIndex {
+            totals: Map::new(totals_namespace),
+            checkpoints: Map::new(checkpoints_namespace),
+            owed: Map::new(owed_namespace),
+        }
+    }
+
+    /// Amount of token held by all the locks
+    pub fn total(&self, store: &dyn Storage, token: &Addr) -> StdResult<Uint128> {
+        let total = self.totals.may_load(store, token.as_bytes().to_vec())?;
+        Ok(total.unwrap_or_default())
+    }
+
+    /// Removes and returns the rewards accrued by the lock under pk
+    pub fn take_owed(&self, store: &mut dyn Storage, pk: &[u8]) -> StdResult<Option<LpRewards>> {
+        let owed = self.owed.may_load(store, pk.to_vec())?;
+        self.owed.remove(store, pk.to_vec());
+        Ok(owed)
+    }
+
+    pub fn clear_totals(&self, store: &mut dyn Storage) -> StdResult<()> {
+        let keys: Vec<_> = self
+            .totals
+            .keys(store, None, None, Order::Ascending)
+            .collect();
+        for key in keys {
+            self.totals.remove(store, key);
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Index<Lock> for LpRewardIndex<'a> {
+    fn save(&self, store: &mut dyn Storage, pk: &[u8], data: &Lock) -> StdResult<()> {
+        for token in &data.funds.cw20 {
+            let key = token.address.as_bytes().to_vec();
+            self.totals
+                .update(store, key.clone(), |total| -> StdResult<_> {
+                    Ok(total.unwrap_or_default() + token.amount)
+                })?;
+            // a rebuild saves the locks again without removing them first
+            let checkpoint = (pk.to_vec(), key);
+            if self
+                .checkpoints
+                .may_load(store, checkpoint.clone())?
+                .is_none()
+            {
+                let index = REWARD_INDEXES
+                    .may_load(store, &token.address)?
+                    .unwrap_or_default();
+                self.checkpoints.save(store, checkpoint, &index)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, pk: &[u8], old_data: &Lock) -> StdResult<()> {
+        let mut accrued = vec![];
+        for token in &old_data.funds.cw20 {
+            let key = token.address.as_bytes().to_vec();
+            let total = self
+                .totals
+                .may_load(store, key.clone())?
+                .unwrap_or_default();
+            match total.checked_sub(token.amount)? {
+                total if total.is_zero() => self.totals.remove(store, key.clone()),
+                total => self.totals.save(store, key.clone(), &total)?,
+            }
+
+            let checkpoint = (pk.to_vec(), key);
+            let paid = self
+                .checkpoints
+                .may_load(store, checkpoint.clone())?
+                .unwrap_or_default();
+            self.checkpoints.remove(store, checkpoint);
+            let index = REWARD_INDEXES
+                .may_load(store, &token.address)?
+                .unwrap_or_default();
+            for coin in index {
+                let paid = paid
+                    .iter()
+                    .find(|paid| paid.denom == coin.denom)
+                    .map_or_else(Uint128::zero, |paid| paid.amount);
+                let amount = token
+                    .amount
+                    .multiply_ratio(coin.amount.checked_sub(paid)?, REWARD_SCALE);
+                if !amount.is_zero() {
+                    accrued.push(Coin {
+                        denom: coin.denom,
+                        amount,
+                    });
+                }
+            }
+        }
+        if accrued.is_empty() {
+            return Ok(());
+        }
+
+        let (owner, _) = split_lock_pk(pk)?;
+        let mut owed = GenericBalance::default();
+        if let Some(previous) = self.owed.may_load(store, pk.to_vec())? {
+            owed.add_tokens(Balance::from(previous.rewards));
+        }
+        owed.add_tokens(Balance::from(accrued));
+        let owed = LpRewards {
+            recipient: old_data.recipient(&owner).clone(),
+            rewards: owed.native,
+        };
+        self.owed.save(store, pk.to_vec(), &owed)
+    }
+}
+
+/// Start seconds of the schedule bucket holding expire
+pub fn schedule_bucket(expire: Timestamp) -> u64 {
+    expire.seconds() - expire.seconds() % SCHEDULE_BUCKET
+}
+
+/// Raw key of the reminder index, pk can be empty to bound a whole expire second
+pub fn reminder_bound(expire: u64, pk: Vec<u8>) -> Vec<u8> {
+    (U64Key::new(expire), pk).joined_key()
+}
+
+/// Keeps native denoms and cw20 addresses apart
+pub fn asset_key(asset: &Denom) -> Vec<u8> {
+    match asset {
+        Denom::Native(denom) => [&b"native:"[..], denom.as_bytes()].concat(),
+        Denom::Cw20(address) => [&b"cw20:"[..], address.as_str().as_bytes()].concat(),
+    }
+}
+
+fn lock_assets(lock: &Lock) -> Vec<Vec<u8>> {
+    let native = lock
+        .funds
+        .native
+        .iter()
+        .map(|coin| asset_key(&Denom::Native(coin.denom.clone())));
+    let cw20 = lock
+        .funds
+        .cw20
+        .iter()
+        .map(|token| asset_key(&Denom::Cw20(token.address.clone())));
+    native.chain(cw20).collect()
+}
+
+/// Contracts allowed to create locks on behalf of other addresses
+pub const LOCKERS: Map<&Addr, Empty> = Map::new("lockers");
+/// Lockbox the locks opted in to rollover are moved into
+pub const SUCCESSOR: Item<Addr> = Item::new("successor");
+/// Locker contracts allowed to migrate their locks in
+pub const LEGACY_LOCKERS: Map<&Addr, Empty> = Map::new("legacy_lockers");
+/// Lending contracts allowed to encumber locks as loan collateral
+pub const LENDERS: Map<&Addr, Empty> = Map::new("lenders");
+/// Lock id prefixes reserved by lockers
+pub const ID_PREFIXES: Map<&str, Addr> = Map::new("id_prefixes");
+/// Total locked funds per owner, snapshotted every block
+pub const LOCKED_TOTALS: SnapshotMap<&Addr, GenericBalance> = SnapshotMap::new(
+    "locked_totals",
+    "locked_totals__checkpoints",
+    "locked_totals__changelog",
+    Strategy::EveryBlock,
+);
+/// Sum of the locked totals of every owner, kept along with them
+pub const LOCKED_SUM: Item<GenericBalance> = Item::new("locked_sum");
+pub const STREAM_COUNT: Item<u64> = Item::new("stream_count");
+pub const STREAMS: Map<U64Key, Stream> = Map::new("streams");
+pub const VESTING_GROUPS: Map<U64Key, VestingGroup> = Map::new("vesting_groups");
+pub const VESTING_GROUP_COUNT: Item<u64> = Item::new("vesting_group_count");
+/// Schedules by group and recipient
+pub const VESTING_SCHEDULES: Map<(U64Key, &Addr), VestingSchedule> = Map::new("vesting_schedules");
+
+pub const AIRDROP_STAGE: Item<u64> = Item::new("airdrop_stage");
+pub const AIRDROPS: Map<U64Key, Airdrop> = Map::new("airdrops");
+pub const AIRDROP_CLAIMS: Map<(U64Key, &Addr), Empty> = Map::new("airdrop_claims");
+/// Locks abandoned after expire can be escheated, disabled if not set
+pub const ESCHEAT_CONFIG: Item<EscheatConfig> = Item::new("escheat_config");
+/// Contract governing max_lock_time, State.max_lock_time is used if not set
+pub const PARAM_REGISTRY: Item<ParamRegistry> = Item::new("param_registry");
+pub const CACHED_MAX_LOCK_TIME: Item<CachedParam> = Item::new("cached_max_lock_time");
+pub const EXTENSION_CAMPAIGN_COUNT: Item<u64> = Item::new("extension_campaign_count");
+pub const EXTENSION_CAMPAIGNS: Map<U64Key, ExtensionCampaign> = Map::new("extension_campaigns");
+/// Bonus reserved by campaign and lock
+pub const EXTENSION_BONUSES: Map<(U64Key, (&Addr, String)), ExtensionBonus> =
+    Map::new("extension_bonuses");
+/// Highest block time seen by lock and unlock
+pub const TIME_HIGH_WATER: Item<Timestamp> = Item::new("time_high_water");
+
+/// Set while execute runs, an execute entering with it set is a reentrant call
+pub const EXECUTION_GUARD: Item<Empty> = Item::new("execution_guard");
+/// Seconds added to the block time, set by sudo on testnets
+#[cfg(feature = "testing")]
+pub const TIME_OFFSET: Item<u64> = Item::new("time_offset");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EscheatConfig {
+    /// Seconds after expire the lock is abandoned
+    pub abandon_after: u64,
+    pub destination: Addr,
+    /// Warnings required before escheat
+    pub warnings: u32,
+    /// Min seconds between warnings and between the last warning and escheat
+    pub warning_interval: u64,
+}
+
+/// Bonus offered to lockers extending their locks, see extension
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExtensionCampaign {
+    /// Locked asset the bonus is computed on
+    pub asset: Denom,
+    /// Bonus in basis points of the locked asset amount
+    pub bonus_bps: u64,
+    /// Locks must be extended to at least this time
+    pub min_expire: Timestamp,
+    /// Extensions are accepted until this time
+    pub deadline: Timestamp,
+    pub bonus_denom: Denom,
+    /// Bonus not reserved by accepted extensions
+    pub remaining: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExtensionBonus {
+    pub amount: Uint128,
+    /// Extended expire of the lock
+    pub claimable_at: Timestamp,
+    /// Create time of the extended lock, telling it apart from a lock later created with its id
+    pub lock_create: Timestamp,
+}
+
+/// Sale offer of a lock, valid while the lock keeps expire and funds
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Listing {
+    pub asset: Denom,
+    pub price: Uint128,
+    pub expire: Timestamp,
+    pub funds: GenericBalance,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParamRegistry {
+    pub contract: Addr,
+    pub key: String,
+    /// Seconds a queried value is reused
+    pub cache_ttl: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CachedParam {
+    pub value: u64,
+    pub fetched_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct YieldConfig {
+    /// Yield is paid with the lock funds instead of harvested to the collector
+    pub to_lock: bool,
+    /// Receives harvested yield, defaults to the contract owner
+    pub collector: Option<Addr>,
+}
+
+/// Yield adapter by native denom
+pub const YIELD_ADAPTERS: Map<&str, Addr> = Map::new("yield_adapters");
+/// Locked amount deposited into the adapter by native denom
+pub const YIELD_PRINCIPAL: Map<&str, Uint128> = Map::new("yield_principal");
+
+/// Adapter msgs returned by the current execution, which balance queries don't see yet
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct YieldFlow {
+    pub deposited: Uint128,
+    pub withdrawn: Uint128,
+}
+
+/// Yield flows by native denom, cleared once execute returns
+pub const YIELD_FLOWS: Map<&str, YieldFlow> = Map::new("yield_flows");
+
+/// Fee-share contract paying protocol fees to the holders of an LP token, keyed by the token
+pub const FEE_SHARES: Map<&Addr, Addr> = Map::new("fee_shares");
+/// Scale of the reward indexes, keeps the per LP token reward precise for small rewards
+pub const REWARD_SCALE: u128 = 1_000_000_000_000;
+/// Fee rewards paid per locked LP token, scaled by REWARD_SCALE, keyed by the token
+pub const REWARD_INDEXES: Map<&Addr, Vec<Coin>> = Map::new("reward_indexes");
+pub const YIELD_CONFIG: Item<YieldConfig> = Item::new("yield_config");
+
+/// Display info for explorers and wallets
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Profile {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub logo_url: Option<String>,
+}
+
+pub const ACTIVE_LOCKS: Item<u64> = Item::new("active_locks");
+pub const INDEX_REBUILD: Item<IndexRebuild> = Item::new("index_rebuild");
+/// Locks held by each owner, owners without locks have no entry
+pub const LOCK_COUNTS: Map<&Addr, u64> = Map::new("lock_counts");
+/// Owners allowed to create locks over max_active_locks
+pub const CAP_BYPASS: Map<&Addr, Empty> = Map::new("cap_bypass");
+
+/// Contracts allowed as beneficiary in deployments restricting contract recipients
+pub const RECIPIENT_ALLOWLIST: Map<&Addr, Empty> = Map::new("recipient_allowlist");
+
+/// Cw20 tokens rejected by new locks and increases, the value holds their unlocks too
+pub const FROZEN_TOKENS: Map<&Addr, bool> = Map::new("frozen_tokens");
+/// Contract wide pause, see pause
+pub const PAUSE: Item<Pause> = Item::new("pause");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Pause {
+    pub paused_at: Timestamp,
+    /// Pause ends on its own at this time, none lasts until Unpause
+    pub until: Option<Timestamp>,
+}
+
+/// Keyed by the old token
+pub const TOKEN_MIGRATIONS: Map<&Addr, TokenMigration> = Map::new("token_migrations");
+
+/// Staff roles granted by the owner, see roles::is_operator
+pub const ROLES: Map<&Addr, Role> = Map::new("roles");
+/// Relayers allowed to unlock on behalf of the owner, by owner and relayer, until expires
+pub const RELAYERS: Map<(&Addr, &Addr), Option<Timestamp>> = Map::new("relayers");
+/// Owner of the locks managed by a Polytone voice proxy or ICA host account, by controller
+pub const CONTROLLERS: Map<&Addr, Addr> = Map::new("controllers");
+/// Connected channels of the notify protocol, by channel id
+pub const NOTIFY_CHANNELS: Map<&str, Empty> = Map::new("notify_channels");
+/// Channel the lock notifications are sent over, set by the owner
+pub const NOTIFY_CHANNEL: Item<String> = Item::new("notify_channel");
+
+/// Display metadata of native and IBC denoms, set by the owner
+pub const DENOM_METADATA: Map<&str, DenomMetadata> = Map::new("denom_metadata");
+
+/// Symbol and decimals of cw20 tokens, cached from their TokenInfo query
+pub const TOKEN_INFO: Map<&Addr, DenomMetadata> = Map::new("token_info");
+
+pub const LOCK_RATE: Map<&Addr, RateWindow> = Map::new("lock_rate");
+/// Locks created by owner with LockDerived, mixed into the derived id
+pub const LOCK_NONCE: Map<&Addr, u64> = Map::new("lock_nonce");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Deposit {
+    pub from: Addr,
+    /// Cw20 contract relaying the Receive, none for native deposits
+    #[serde(default)]
+    pub token_contract: Option<Addr>,
+    pub funds: GenericBalance,
+    pub time: Timestamp,
+}
+
+/// Deposit history by lock and index
+pub const DEPOSITS: Map<((&Addr, String), U64Key), Deposit> = Map::new("deposits");
+pub const DEPOSIT_COUNT: Map<(&Addr, String), u64> = Map::new("deposit_count");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    Create,
+    Deposit,
+    Withdraw,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Activity {
+    pub kind: ActivityKind,
+    pub id: String,
+    pub funds: GenericBalance,
+}
+
+/// Lock creations, deposits and withdrawals by owner, time in seconds and sequence
+pub const ACTIVITY: Map<(&Addr, (U64Key, U64Key)), Activity> = Map::new("activity");
+pub const ACTIVITY_COUNT: Item<u64> = Item::new("activity_count");
+
+/// Locks listed for sale by owner and id
+pub const LISTINGS: Map<(&Addr, String), Listing> = Map::new("listings");
+
+/// Locks created through a referrer and the funds they locked
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ReferralStats {
+    pub count: u64,
+    pub volume: GenericBalance,
+}
+
+pub const REFERRALS: Map<&Addr, ReferralStats> = Map::new("referrals");
+
+pub const PROFILE: Item<Profile> = Item::new("profile");
+
+/// Sha256 of the owner viewing key
+pub const VIEWING_KEYS: Map<&Addr, Binary> = Map::new("viewing_keys");
+
+/// Secp256k1 public keys verifying the ownership challenges signed by each owner
+pub const OWNERSHIP_KEYS: Map<&Addr, Binary> = Map::new("ownership_keys");
+pub const PENDING_SWAP: Item<PendingSwap> = Item::new("pending_swap");
+/// Conversions in the order their submessages run, each reply takes the first one
+pub const PENDING_CONVERSIONS: Item<Vec<PendingConversion>> = Item::new("pending_conversions");
+pub const EMERGENCY_WITHDRAWAL: Item<EmergencyWithdrawal> = Item::new("emergency_withdrawal");
@@ -0,0 +1,421 @@
+use cosmwasm_std::{
+    attr, Addr, CosmosMsg, Deps, DepsMut, Env, Response, StdError, StdResult, Timestamp, Uint128,
+};
+use cw20::{Balance, Denom};
+use cw_storage_plus::U64Key;
+
+use crate::balance::{denom_balance, single_token, GenericBalance};
+use crate::contract::send_tokens;
+use crate::error::ContractError;
+use crate::freeze::check_deposit;
+use crate::msg::StreamResponse;
+use crate::state::{Stream, STREAMS, STREAM_COUNT};
+
+/// Streams the funded token to recipient at rate tokens per second from start
+pub fn try_create_stream(
+    deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    sender: &Addr,
+    recipient: String,
+    rate: Uint128,
+    start: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    let (denom, deposit) = match single_token(&balance) {
+        Some(token) if !token.1.is_zero() => token,
+        _ => return Err(ContractError::InvalidStreamFunds {}),
+    };
+    if rate.is_zero() {
+        return Err(ContractError::ZeroStreamRate {});
+    }
+    check_deposit(deps.storage, &balance)?;
+
+    let stream = Stream {
+        funder: sender.clone(),
+        recipient: deps.api.addr_validate(&recipient)?,
+        denom,
+        deposit,
+        rate,
+        start: start.unwrap_or(env.block.time),
+        settled: Uint128::zero(),
+        withdrawn: Uint128::zero(),
+        pending_rate: None,
+    };
+    let id = STREAM_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    STREAMS.save(deps.storage, U64Key::from(id), &stream)?;
+    STREAM_COUNT.save(deps.storage, &id)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "create_stream"),
+            attr("stream_id", id),
+            attr("funder", sender),
+            attr("recipient", stream.recipient),
+            attr("amount", deposit),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Adds the funded tokens to the stream deposit, only callable by the funder
+pub fn try_top_up_stream(
+    deps: DepsMut,
+    env: Env,
+    balance: Balance,
+    sender: &Addr,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut stream = STREAMS.load(deps.storage, U64Key::from(id))?;
+    if stream.funder != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let amount = match single_token(&balance) {
+        Some((denom, amount)) if denom == stream.denom && !amount.is_zero() => amount,
+        _ => return Err(ContractError::StreamDenomMismatch {}),
+    };
+    check_deposit(deps.storage, &balance)?;
+
+    // an ended stream must not stream the elapsed time since it ended
+    stream.settle(env.block.time);
+    stream.deposit += amount;
+    STREAMS.save(deps.storage, U64Key::from(id), &stream)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "top_up_stream"),
+            attr("stream_id", id),
+            attr("amount", amount),
+            attr("deposit", stream.deposit),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Raises the rate right away, a lower rate waits for the recipient to accept it
+pub fn try_change_stream_rate(
+    deps: DepsMut,
+    env: Env,
+    sender: &Addr,
+    id: u64,
+    rate: Uint128,
+) -> Result<Response, ContractError> {
+    let mut stream = STREAMS.load(deps.storage, U64Key::from(id))?;
+    if stream.funder != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if rate.is_zero() {
+        return Err(ContractError::ZeroStreamRate {});
+    }
+
+    let pending = rate < stream.rate;
+    if pending {
+        stream.pending_rate = Some(rate);
+    } else {
+        stream.settle(env.block.time);
+        stream.rate = rate;
+        stream.pending_rate = None;
+    }
+    STREAMS.save(deps.storage, U64Key::from(id), &stream)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "change_stream_rate"),
+            attr("stream_id", id),
+            attr("rate", rate),
+            attr("pending", pending),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Applies the lower rate proposed by the funder, only callable by the recipient
+pub fn try_accept_stream_rate(
+    deps: DepsMut,
+    env: Env,
+    sender: &Addr,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut stream = STREAMS.load(deps.storage, U64Key::from(id))?;
+    if stream.recipient != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let rate = stream
+        .pending_rate
+        .take()
+        .ok_or(ContractError::NoPendingStreamRate {})?;
+
+    stream.settle(env.block.time);
+    stream.rate = rate;
+    STREAMS.save(deps.storage, U64Key::from(id), &stream)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "accept_stream_rate"),
+            attr("stream_id", id),
+            attr("rate", rate),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Sends the recipient up to the streamed tokens not withdrawn yet
+pub fn try_withdraw_stream(
+    deps: DepsMut,
+    env: Env,
+    sender: &Addr,
+    id: u64,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut stream = STREAMS.load(deps.storage, U64Key::from(id))?;
+    if stream.recipient != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let withdrawable = stream.withdrawable(env.block.time);
+    let amount = amount.unwrap_or(withdrawable);
+    if amount.is_zero() || amount > withdrawable {
+        return Err(ContractError::InsufficientStreamed { withdrawable });
+    }
+    stream.withdrawn += amount;
+    if stream.withdrawn == stream.deposit {
+        STREAMS.remove(deps.storage, U64Key::from(id));
+    } else {
+        STREAMS.save(deps.storage, U64Key::from(id), &stream)?;
+    }
+
+    Ok(Response {
+        messages: stream_msgs(&stream.denom, &stream.recipient, amount)?,
+        attributes: vec![
+            attr("action", "withdraw_stream"),
+            attr("stream_id", id),
+            attr("amount", amount),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Pays the streamed tokens to the recipient and refunds the rest to the funder
+pub fn try_cancel_stream(
+    deps: DepsMut,
+    env: Env,
+    sender: &Addr,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let stream = STREAMS.load(deps.storage, U64Key::from(id))?;
+    if stream.funder != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    STREAMS.remove(deps.storage, U64Key::from(id));
+
+    let streamed = stream.streamed(env.block.time);
+    let paid = stream.withdrawable(env.block.time);
+    let refund = stream
+        .deposit
+        .checked_sub(streamed)
+        .map_err(StdError::from)?;
+    let mut messages = stream_msgs(&stream.denom, &stream.recipient, paid)?;
+    messages.append(&mut stream_msgs(&stream.denom, &stream.funder, refund)?);
+
+    Ok(Response {
+        messages,
+        attributes: vec![
+            attr("action", "cancel_stream"),
+            attr("stream_id", id),
+            attr("paid", paid),
+            attr("refund", refund),
+        ],
+        ..Response::default()
+    })
+}
+
+fn stream_msgs(denom: &Denom, to: &Addr, amount: Uint128) -> StdResult<Vec<CosmosMsg>> {
+    if amount.is_zero() {
+        return Ok(vec![]);
+    }
+    send_tokens(to, &GenericBalance::from(denom_balance(denom, amount)))
+}
+
+/// Streamed and withdrawable amounts are computed at time, the block time by default
+pub fn query_stream(
+    deps: Deps,
+    env: Env,
+    id: u64,
+    at_time: Option<Timestamp>,
+) -> StdResult<StreamResponse> {
+    let stream = STREAMS.load(deps.storage, U64Key::from(id))?;
+    let time = at_time.unwrap_or(env.block.time);
+
+    Ok(StreamResponse {
+        id,
+        time,
+        streamed: stream.streamed(time),
+        withdrawable: stream.withdrawable(time),
+        funder: stream.funder.into(),
+        recipient: stream.recipient.into(),
+        denom: stream.denom,
+        deposit: stream.deposit,
+        rate: stream.rate,
+        start: stream.start,
+        withdrawn: stream.withdrawn,
+        pending_rate: stream.pending_rate,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, BankMsg};
+
+    #[test]
+    fn stream_payments() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::CreateStream {
+            recipient: "employee".into(),
+            rate: Uint128::from(1u128),
+            start: None,
+        };
+        let funder = mock_info("employer", &coins(100, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), funder.clone(), msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(30);
+        let employee = mock_info("employee", &[]);
+        let msg = ExecuteMsg::WithdrawStream {
+            id: 1,
+            amount: Some(Uint128::from(31u128)),
+        };
+        let res = execute(deps.as_mut(), env.clone(), employee.clone(), msg);
+        match res {
+            Err(ContractError::InsufficientStreamed { withdrawable }) => {
+                assert_eq!(Uint128::from(30u128), withdrawable)
+            }
+            _ => panic!("Must return InsufficientStreamed error"),
+        }
+        let msg = ExecuteMsg::WithdrawStream {
+            id: 1,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), employee, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "employee".into(),
+                amount: coins(30, "token"),
+            }
+            .into()]
+        );
+
+        env.block.time = Timestamp::from_seconds(50);
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Stream {
+                id: 1,
+                at_time: None,
+            },
+        )
+        .unwrap();
+        let value: StreamResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(20u128), value.withdrawable);
+
+        let msg = ExecuteMsg::CancelStream { id: 1 };
+        let res = execute(deps.as_mut(), env, funder, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                BankMsg::Send {
+                    to_address: "employee".into(),
+                    amount: coins(20, "token"),
+                }
+                .into(),
+                BankMsg::Send {
+                    to_address: "employer".into(),
+                    amount: coins(50, "token"),
+                }
+                .into(),
+            ]
+        );
+        assert!(STREAMS
+            .may_load(&deps.storage, U64Key::from(1))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn stream_top_up_and_rate() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::CreateStream {
+            recipient: "employee".into(),
+            rate: Uint128::from(2u128),
+            start: None,
+        };
+        let funder = mock_info("employer", &coins(100, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), funder, msg).unwrap();
+
+        // ended at 50, the top-up streams from 60 on
+        env.block.time = Timestamp::from_seconds(60);
+        let msg = ExecuteMsg::TopUpStream { id: 1 };
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("employer", &coins(100, "other")),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::StreamDenomMismatch {}) => {}
+            _ => panic!("Must return StreamDenomMismatch error"),
+        }
+        let funder = mock_info("employer", &coins(100, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), funder.clone(), msg).unwrap();
+        let value = query_stream(deps.as_ref(), env.clone(), 1, None).unwrap();
+        assert_eq!(Uint128::from(100u128), value.streamed);
+
+        // a lower rate needs the recipient consent
+        let msg = ExecuteMsg::ChangeStreamRate {
+            id: 1,
+            rate: Uint128::from(1u128),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), funder, msg).unwrap();
+        env.block.time = Timestamp::from_seconds(70);
+        let value = query_stream(deps.as_ref(), env.clone(), 1, None).unwrap();
+        assert_eq!(Some(Uint128::from(1u128)), value.pending_rate);
+        assert_eq!(Uint128::from(120u128), value.streamed);
+
+        let msg = ExecuteMsg::AcceptStreamRate { id: 1 };
+        let employee = mock_info("employee", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), employee.clone(), msg.clone()).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), employee, msg);
+        match res {
+            Err(ContractError::NoPendingStreamRate {}) => {}
+            _ => panic!("Must return NoPendingStreamRate error"),
+        }
+
+        let at_time = Some(Timestamp::from_seconds(100));
+        let value = query_stream(deps.as_ref(), env, 1, at_time).unwrap();
+        assert_eq!(None, value.pending_rate);
+        assert_eq!(Uint128::from(150u128), value.streamed);
+        assert_eq!(Uint128::from(150u128), value.withdrawable);
+    }
+}
@@ -0,0 +1,229 @@
+use cosmwasm_std::{
+    attr, coins, to_binary, Addr, ContractResult, CosmosMsg, DepsMut, MessageInfo, ReplyOn,
+    Response, StdResult, Storage, SubMsg, SubcallResponse, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Denom};
+
+use crate::allowlist::check_recipient;
+use crate::balance::{denom_balance, GenericBalance};
+use crate::contract::{load_lock, push_payout, send_tokens};
+use crate::error::ContractError;
+use crate::msg::{PayoutInfo, RouterExecuteMsg, SwapMsg};
+use crate::state::{locks, PendingSwap, Swap, PENDING_SWAP};
+
+pub const SWAP_REPLY_ID: u64 = 1;
+
+pub fn try_set_swap(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: String,
+    swap: Option<SwapMsg>,
+) -> Result<Response, ContractError> {
+    let key = (&info.sender, id.to_owned());
+    let mut lock = load_lock(deps.storage, &info.sender, &id)?;
+
+    // the router would receive the beneficiary payout
+    if swap.is_some() && lock.beneficiary.is_some() {
+        return Err(ContractError::BeneficiaryPayout {});
+    }
+    lock.swap = swap
+        .map(|swap| -> Result<_, ContractError> {
+            let offer = match swap.offer {
+                Denom::Cw20(address) => Denom::Cw20(deps.api.addr_validate(address.as_str())?),
+                denom => denom,
+            };
+            let router = deps.api.addr_validate(&swap.router)?;
+            check_recipient(deps.as_ref(), &router)?;
+            Ok(Swap {
+                router,
+                offer,
+                ask: swap.ask,
+                min_receive: swap.min_receive,
+            })
+        })
+        .transpose()?;
+    locks().save(deps.storage, key, &lock)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "set_swap"),
+            attr("from", info.sender),
+            attr("id", id),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Takes the swap offer out of funds and returns the router submessage,
+/// the offer is kept as pending so it can be refunded if the swap fails
+pub fn swap_submsg(
+    storage: &mut dyn Storage,
+    swap: &Swap,
+    funds: &mut GenericBalance,
+    recipient: &Addr,
+    payouts: &mut Vec<PayoutInfo>,
+) -> StdResult<Option<SubMsg>> {
+    let amount = match funds.remove_denom(&swap.offer) {
+        Some(amount) => amount,
+        None => return Ok(None),
+    };
+    push_payout(
+        payouts,
+        &swap.router,
+        denom_balance(&swap.offer, amount).into(),
+    );
+
+    let swap_msg = RouterExecuteMsg::Swap {
+        ask: swap.ask.clone(),
+        min_receive: swap.min_receive,
+        recipient: recipient.into(),
+    };
+    let msg: CosmosMsg = match &swap.offer {
+        Denom::Native(denom) => WasmMsg::Execute {
+            contract_addr: swap.router.to_string(),
+            msg: to_binary(&swap_msg)?,
+            send: coins(amount.u128(), denom),
+        }
+        .into(),
+        Denom::Cw20(address) => WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: swap.router.to_string(),
+                amount,
+                msg: Some(to_binary(&swap_msg)?),
+            })?,
+            send: vec![],
+        }
+        .into(),
+    };
+
+    let pending = PendingSwap {
+        recipient: recipient.clone(),
+        funds: denom_balance(&swap.offer, amount).into(),
+    };
+    PENDING_SWAP.save(storage, &pending)?;
+
+    Ok(Some(SubMsg {
+        id: SWAP_REPLY_ID,
+        msg,
+        gas_limit: None,
+        reply_on: ReplyOn::Always,
+    }))
+}
+
+pub fn reply_swap(
+    deps: DepsMut,
+    result: ContractResult<SubcallResponse>,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_SWAP.load(deps.storage)?;
+    PENDING_SWAP.remove(deps.storage);
+
+    match result {
+        ContractResult::Ok(_) => Ok(Response {
+            attributes: vec![attr("action", "swap"), attr("to", pending.recipient)],
+            ..Response::default()
+        }),
+        // fallback to plain transfer
+        ContractResult::Err(err) => Ok(Response {
+            messages: send_tokens(&pending.recipient, &pending.funds)?,
+            attributes: vec![
+                attr("action", "swap_failed"),
+                attr("to", pending.recipient),
+                attr("error", err),
+            ],
+            ..Response::default()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, reply};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{BankMsg, Coin, Reply, Timestamp, Uint128};
+
+    #[test]
+    fn unlock_with_swap() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // lock funds
+        let funds = vec![Coin::new(2, "token"), Coin::new(5, "earth")];
+        let info = mock_info("anyone", &funds);
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // swap token into uusd on unlock
+        let msg = ExecuteMsg::SetSwap {
+            id: "1".into(),
+            swap: Some(SwapMsg {
+                router: "router".into(),
+                offer: Denom::Native("token".into()),
+                ask: Denom::Native("uusd".into()),
+                min_receive: Uint128::new(10),
+            }),
+        };
+        let info = mock_info("anyone", &[]);
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(401);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.submessages,
+            vec![SubMsg {
+                id: SWAP_REPLY_ID,
+                msg: WasmMsg::Execute {
+                    contract_addr: "router".into(),
+                    msg: to_binary(&RouterExecuteMsg::Swap {
+                        ask: Denom::Native("uusd".into()),
+                        min_receive: Uint128::new(10),
+                        recipient: "anyone".into(),
+                    })
+                    .unwrap(),
+                    send: coins(2, "token"),
+                }
+                .into(),
+                gas_limit: None,
+                reply_on: ReplyOn::Always,
+            }]
+        );
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(5, "earth"),
+            })]
+        );
+
+        // failed swap falls back to plain transfer
+        let msg = Reply {
+            id: SWAP_REPLY_ID,
+            result: ContractResult::Err("slippage".into()),
+        };
+        let res = reply(deps.as_mut(), env, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "anyone".into(),
+                amount: coins(2, "token"),
+            })]
+        );
+        assert!(PENDING_SWAP.may_load(&deps.storage).unwrap().is_none());
+    }
+}
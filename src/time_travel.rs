@@ -0,0 +1,121 @@
+//! Testnet only time travel, compiled with the testing feature and never on mainnet builds
+use cosmwasm_std::{attr, entry_point, DepsMut, Env, Response, StdResult, Storage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+use crate::state::TIME_OFFSET;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    /// Shift the block time seen by execute and query msgs. Lowering it fails with
+    /// TimeRegressed until the chain time catches up with the high-water mark
+    SetTimeOffset { seconds: u64 },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::SetTimeOffset { seconds } => {
+            TIME_OFFSET.save(deps.storage, &seconds)?;
+            Ok(Response {
+                attributes: vec![attr("action", "set_time_offset"), attr("seconds", seconds)],
+                ..Response::default()
+            })
+        }
+    }
+}
+
+/// Env with the block time shifted by the configured offset
+pub fn shifted_env(storage: &dyn Storage, mut env: Env) -> StdResult<Env> {
+    let offset = TIME_OFFSET.may_load(storage)?.unwrap_or_default();
+    env.block.time = env.block.time.plus_seconds(offset);
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, Timestamp};
+
+    #[test]
+    fn time_offset() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        env.block.time = Timestamp::from_seconds(10);
+        let unlock = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), unlock.clone());
+        match res {
+            Err(ContractError::LockNotExpired {}) => {}
+            _ => panic!("Must return LockNotExpired error"),
+        }
+
+        let msg = SudoMsg::SetTimeOffset { seconds: 100 };
+        let _res = sudo(deps.as_mut(), mock_env(), msg).unwrap();
+        let res = execute(deps.as_mut(), env, info, unlock).unwrap();
+        assert_eq!(1, res.messages.len());
+    }
+
+    #[test]
+    fn batch_time_offset() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(100)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(2, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = SudoMsg::SetTimeOffset { seconds: 60 };
+        let _res = sudo(deps.as_mut(), mock_env(), msg).unwrap();
+
+        // the batched msgs see the offset once
+        let batch = ExecuteMsg::Batch {
+            msgs: vec![ExecuteMsg::Unlock { id: "1".into() }],
+        };
+        let info = mock_info("anyone", &[]);
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), batch.clone());
+        match res {
+            Err(ContractError::LockNotExpired {}) => {}
+            _ => panic!("Must return LockNotExpired error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(40);
+        let res = execute(deps.as_mut(), env, info, batch).unwrap();
+        assert_eq!(1, res.messages.len());
+    }
+}
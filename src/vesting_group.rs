@@ -0,0 +1,396 @@
+use cosmwasm_std::{
+    attr, Addr, Api, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    Storage, Timestamp, Uint128,
+};
+use cw20::{Balance, Denom};
+use cw_storage_plus::U64Key;
+
+use crate::balance::{denom_balance, single_token, GenericBalance};
+use crate::contract::send_tokens;
+use crate::error::ContractError;
+use crate::freeze::check_deposit;
+use crate::msg::{VestingGroupResponse, VestingScheduleMsg, VestingScheduleResponse};
+use crate::state::{
+    VestingGroup, VestingSchedule, VESTING_GROUPS, VESTING_GROUP_COUNT, VESTING_SCHEDULES,
+};
+
+/// Funds a pool of a single token split among the schedule recipients, administered by admin
+/// (the sender by default). Funds not allocated to a schedule stay with the admin
+pub fn try_create_vesting_group(
+    deps: DepsMut,
+    balance: Balance,
+    sender: &Addr,
+    admin: Option<String>,
+    schedules: Vec<VestingScheduleMsg>,
+) -> Result<Response, ContractError> {
+    let (denom, deposit) = match single_token(&balance) {
+        Some(token) if !token.1.is_zero() => token,
+        _ => return Err(ContractError::InvalidVestingFunds {}),
+    };
+    check_deposit(deps.storage, &balance)?;
+
+    let mut group = VestingGroup {
+        admin: match admin {
+            Some(admin) => deps.api.addr_validate(&admin)?,
+            None => sender.clone(),
+        },
+        denom,
+        deposit,
+        allocated: Uint128::zero(),
+    };
+    let id = VESTING_GROUP_COUNT
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1;
+    let count = schedules.len();
+    for schedule in schedules {
+        add_schedule(deps.storage, deps.api, id, &mut group, schedule)?;
+    }
+    VESTING_GROUPS.save(deps.storage, U64Key::from(id), &group)?;
+    VESTING_GROUP_COUNT.save(deps.storage, &id)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "create_vesting_group"),
+            attr("group_id", id),
+            attr("admin", group.admin),
+            attr("amount", deposit),
+            attr("schedules", count),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Allocates part of the unallocated pool to a new recipient, only callable by the admin
+pub fn try_add_vesting_schedule(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    schedule: VestingScheduleMsg,
+) -> Result<Response, ContractError> {
+    let mut group = load_group(deps.as_ref(), &info.sender, id)?;
+    let recipient = schedule.recipient.clone();
+    let amount = schedule.amount;
+    add_schedule(deps.storage, deps.api, id, &mut group, schedule)?;
+    VESTING_GROUPS.save(deps.storage, U64Key::from(id), &group)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "add_vesting_schedule"),
+            attr("group_id", id),
+            attr("recipient", recipient),
+            attr("amount", amount),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Sends the recipient its vested tokens not claimed yet
+pub fn try_claim_vesting(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut group = VESTING_GROUPS.load(deps.storage, U64Key::from(id))?;
+    let key = (U64Key::from(id), &info.sender);
+    let mut schedule = VESTING_SCHEDULES.load(deps.storage, key.clone())?;
+
+    let amount = schedule.claimable(env.block.time);
+    if amount.is_zero() {
+        return Err(ContractError::NothingVested {});
+    }
+    schedule.claimed += amount;
+    if schedule.claimed == schedule.amount {
+        VESTING_SCHEDULES.remove(deps.storage, key);
+    } else {
+        VESTING_SCHEDULES.save(deps.storage, key, &schedule)?;
+    }
+    group.deposit = group.deposit.checked_sub(amount).map_err(StdError::from)?;
+    group.allocated = group
+        .allocated
+        .checked_sub(amount)
+        .map_err(StdError::from)?;
+    VESTING_GROUPS.save(deps.storage, U64Key::from(id), &group)?;
+
+    Ok(Response {
+        messages: vesting_msgs(&group.denom, &info.sender, amount)?,
+        attributes: vec![
+            attr("action", "claim_vesting"),
+            attr("group_id", id),
+            attr("recipient", info.sender),
+            attr("amount", amount),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Stops the recipient schedule at the block time, its unvested tokens go back to the
+/// unallocated pool. Vested tokens stay claimable. Only callable by the admin
+pub fn try_clawback_vesting(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let mut group = load_group(deps.as_ref(), &info.sender, id)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let key = (U64Key::from(id), &recipient);
+    let mut schedule = VESTING_SCHEDULES.load(deps.storage, key.clone())?;
+
+    let now = env.block.time;
+    let vested = schedule.vested(now);
+    let clawback = schedule
+        .amount
+        .checked_sub(vested)
+        .map_err(StdError::from)?;
+    schedule.amount = vested;
+    if now < schedule.end {
+        schedule.end = now;
+        schedule.cliff = None;
+    }
+    if schedule.claimed == schedule.amount {
+        VESTING_SCHEDULES.remove(deps.storage, key);
+    } else {
+        VESTING_SCHEDULES.save(deps.storage, key, &schedule)?;
+    }
+    group.allocated = group
+        .allocated
+        .checked_sub(clawback)
+        .map_err(StdError::from)?;
+    VESTING_GROUPS.save(deps.storage, U64Key::from(id), &group)?;
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "clawback_vesting"),
+            attr("group_id", id),
+            attr("recipient", recipient),
+            attr("amount", clawback),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Sends the admin up to the unallocated pool, all of it if amount is not set
+pub fn try_withdraw_vesting_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut group = load_group(deps.as_ref(), &info.sender, id)?;
+
+    let available = group.available();
+    let amount = amount.unwrap_or(available);
+    if amount.is_zero() || amount > available {
+        return Err(ContractError::InsufficientVestingPool { available });
+    }
+    group.deposit = group.deposit.checked_sub(amount).map_err(StdError::from)?;
+    VESTING_GROUPS.save(deps.storage, U64Key::from(id), &group)?;
+
+    Ok(Response {
+        messages: vesting_msgs(&group.denom, &info.sender, amount)?,
+        attributes: vec![
+            attr("action", "withdraw_vesting_pool"),
+            attr("group_id", id),
+            attr("amount", amount),
+        ],
+        ..Response::default()
+    })
+}
+
+fn load_group(deps: Deps, sender: &Addr, id: u64) -> Result<VestingGroup, ContractError> {
+    let group = VESTING_GROUPS.load(deps.storage, U64Key::from(id))?;
+    if group.admin != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(group)
+}
+
+fn add_schedule(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    id: u64,
+    group: &mut VestingGroup,
+    schedule: VestingScheduleMsg,
+) -> Result<(), ContractError> {
+    let VestingScheduleMsg {
+        recipient,
+        amount,
+        start,
+        cliff,
+        end,
+    } = schedule;
+    if amount.is_zero()
+        || end <= start
+        || matches!(cliff, Some(cliff) if cliff < start || cliff > end)
+    {
+        return Err(ContractError::InvalidVestingSchedule {});
+    }
+    let available = group.available();
+    if amount > available {
+        return Err(ContractError::InsufficientVestingPool { available });
+    }
+
+    let recipient = api.addr_validate(&recipient)?;
+    let key = (U64Key::from(id), &recipient);
+    if VESTING_SCHEDULES.may_load(storage, key.clone())?.is_some() {
+        return Err(ContractError::AlreadyInUse {});
+    }
+    let schedule = VestingSchedule {
+        amount,
+        start,
+        cliff,
+        end,
+        claimed: Uint128::zero(),
+    };
+    VESTING_SCHEDULES.save(storage, key, &schedule)?;
+    group.allocated += amount;
+
+    Ok(())
+}
+
+fn vesting_msgs(denom: &Denom, to: &Addr, amount: Uint128) -> StdResult<Vec<CosmosMsg>> {
+    send_tokens(to, &GenericBalance::from(denom_balance(denom, amount)))
+}
+
+pub fn query_vesting_group(deps: Deps, id: u64) -> StdResult<VestingGroupResponse> {
+    let group = VESTING_GROUPS.load(deps.storage, U64Key::from(id))?;
+
+    Ok(VestingGroupResponse {
+        id,
+        available: group.available(),
+        admin: group.admin.into(),
+        denom: group.denom,
+        deposit: group.deposit,
+        allocated: group.allocated,
+    })
+}
+
+/// Vested and claimable amounts are computed at time, the block time by default
+pub fn query_vesting_schedule(
+    deps: Deps,
+    env: Env,
+    id: u64,
+    recipient: String,
+    at_time: Option<Timestamp>,
+) -> StdResult<VestingScheduleResponse> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let schedule = VESTING_SCHEDULES.load(deps.storage, (U64Key::from(id), &recipient))?;
+    let time = at_time.unwrap_or(env.block.time);
+
+    Ok(VestingScheduleResponse {
+        group: id,
+        recipient: recipient.into(),
+        amount: schedule.amount,
+        start: schedule.start,
+        cliff: schedule.cliff,
+        end: schedule.end,
+        claimed: schedule.claimed,
+        time,
+        vested: schedule.vested(time),
+        claimable: schedule.claimable(time),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, BankMsg};
+
+    #[test]
+    fn vesting_group() {
+        let mut deps = mock_dependencies(&[]);
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let schedule = |recipient: &str, amount: u128| VestingScheduleMsg {
+            recipient: recipient.into(),
+            amount: Uint128::new(amount),
+            start: Timestamp::from_seconds(0),
+            cliff: Some(Timestamp::from_seconds(25)),
+            end: Timestamp::from_seconds(100),
+        };
+        let msg = ExecuteMsg::CreateVestingGroup {
+            admin: None,
+            schedules: vec![schedule("alice", 400), schedule("bob", 400)],
+        };
+        let admin = mock_info("team", &coins(1000, "token"));
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::AddVestingSchedule {
+            group: 1,
+            schedule: schedule("carol", 300),
+        };
+        let res = execute(deps.as_mut(), env.clone(), admin.clone(), msg);
+        match res {
+            Err(ContractError::InsufficientVestingPool { available }) => {
+                assert_eq!(Uint128::new(200), available)
+            }
+            _ => panic!("Must return InsufficientVestingPool error"),
+        }
+
+        let claim = ExecuteMsg::ClaimVesting { group: 1 };
+        env.block.time = Timestamp::from_seconds(20);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            claim.clone(),
+        );
+        match res {
+            Err(ContractError::NothingVested {}) => {}
+            _ => panic!("Must return NothingVested error"),
+        }
+
+        env.block.time = Timestamp::from_seconds(50);
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), claim).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "alice".into(),
+                amount: coins(200, "token"),
+            }
+            .into()]
+        );
+
+        // bob keeps the vested half, the other half goes back to the pool
+        let msg = ExecuteMsg::ClawbackVesting {
+            group: 1,
+            recipient: "bob".into(),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+        let value = query_vesting_schedule(deps.as_ref(), env.clone(), 1, "bob".into(), None);
+        assert_eq!(Uint128::new(200), value.unwrap().claimable);
+        let value = query_vesting_group(deps.as_ref(), 1).unwrap();
+        assert_eq!(
+            (Uint128::new(800), Uint128::new(400)),
+            (value.deposit, value.available)
+        );
+
+        let msg = ExecuteMsg::WithdrawVestingPool {
+            group: 1,
+            amount: None,
+        };
+        let res = execute(deps.as_mut(), env, admin, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![BankMsg::Send {
+                to_address: "team".into(),
+                amount: coins(400, "token"),
+            }
+            .into()]
+        );
+    }
+}
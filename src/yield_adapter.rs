@@ -0,0 +1,486 @@
+use cosmwasm_std::{
+    attr, coins, to_binary, Addr, BankMsg, CosmosMsg, Deps, DepsMut, MessageInfo, Order,
+    QuerierWrapper, Response, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::{Balance, Denom};
+
+use crate::balance::GenericBalance;
+use crate::error::ContractError;
+use crate::msg::{
+    YieldAdapterExecuteMsg, YieldAdapterQueryMsg, YieldAdapterResponse, YieldBalanceResponse,
+};
+use crate::state::{
+    locks, Lock, YieldConfig, YieldFlow, STATE, YIELD_ADAPTERS, YIELD_CONFIG, YIELD_FLOWS,
+    YIELD_PRINCIPAL,
+};
+
+/// Sets or clears the adapter of a native denom, only callable by owner.
+/// Locks holding the denom were not deposited, so it can't change while any is live
+pub fn try_register_yield_adapter(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    adapter: Option<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let principal = YIELD_PRINCIPAL.may_load(deps.storage, &denom)?;
+    if !principal.unwrap_or_default().is_zero() {
+        return Err(ContractError::AdapterInUse {});
+    }
+    let asset = Denom::Native(denom.clone());
+    let mut holders =
+        locks()
+            .idx
+            .asset
+            .prefix(&asset)
+            .keys(deps.storage, None, None, Order::Ascending);
+    if holders.next().is_some() {
+        return Err(ContractError::DenomLocked {});
+    }
+
+    match &adapter {
+        Some(adapter) => {
+            let adapter = deps.api.addr_validate(adapter)?;
+            YIELD_ADAPTERS.save(deps.storage, &denom, &adapter)?;
+        }
+        None => YIELD_ADAPTERS.remove(deps.storage, &denom),
+    }
+
+    Ok(Response {
+        attributes: vec![
+            attr("action", "register_yield_adapter"),
+            attr("denom", denom),
+            attr("adapter", adapter.unwrap_or_default()),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Sets where the yield goes, only callable by owner
+pub fn try_update_yield_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    to_lock: bool,
+    collector: Option<String>,
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if !state.is_owner(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config = YieldConfig {
+        to_lock,
+        collector: collector
+            .map(|collector| deps.api.addr_validate(&collector))
+            .transpose()?,
+    };
+    YIELD_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response {
+        attributes: vec![attr("action", "update_yield_config")],
+        ..Response::default()
+    })
+}
+
+/// Sends the yield accrued by the adapter of denom to the collector, callable by anyone
+pub fn try_harvest_yield(
+    deps: DepsMut,
+    contract: &Addr,
+    denom: String,
+) -> Result<Response, ContractError> {
+    let config = YIELD_CONFIG.may_load(deps.storage)?.unwrap_or_default();
+    if config.to_lock {
+        return Err(ContractError::YieldToLock {});
+    }
+    let adapter = YIELD_ADAPTERS.load(deps.storage, &denom)?;
+    let principal = YIELD_PRINCIPAL
+        .may_load(deps.storage, &denom)?
+        .unwrap_or_default();
+    let balance = available_balance(deps.storage, &deps.querier, &adapter, contract, &denom)?;
+    let amount = balance.saturating_sub(principal);
+    if amount.is_zero() {
+        return Err(ContractError::EmptyBalance {});
+    }
+    record_withdraw(deps.storage, &denom, amount)?;
+
+    let collector = match config.collector {
+        Some(collector) => collector,
+        None => STATE.load(deps.storage)?.owner,
+    };
+    let withdraw = withdraw_msg(&adapter, amount)?;
+    let send = BankMsg::Send {
+        to_address: collector.to_string(),
+        amount: coins(amount.u128(), &denom),
+    };
+
+    Ok(Response {
+        messages: vec![withdraw, send.into()],
+        attributes: vec![
+            attr("action", "harvest_yield"),
+            attr("denom", denom),
+            attr("amount", amount),
+            attr("to", collector),
+        ],
+        ..Response::default()
+    })
+}
+
+/// Deposits the native coins having an adapter, tracking them as principal of the lock,
+/// which must be saved by the caller
+pub fn deposit_msgs(
+    storage: &mut dyn Storage,
+    lock: &mut Lock,
+    balance: &Balance,
+) -> StdResult<Vec<CosmosMsg>> {
+    let native = match balance {
+        Balance::Native(native) => &native.0,
+        Balance::Cw20(_) => return Ok(vec![]),
+    };
+
+    let mut messages = vec![];
+    for coin in native {
+        let adapter = match YIELD_ADAPTERS.may_load(storage, &coin.denom)? {
+            Some(adapter) => adapter,
+            None => continue,
+        };
+        YIELD_PRINCIPAL.update(storage, &coin.denom, |principal| -> StdResult<_> {
+            Ok(principal.unwrap_or_default() + coin.amount)
+        })?;
+        match lock
+            .yield_principal
+            .iter_mut()
+            .find(|deposited| deposited.denom == coin.denom)
+        {
+            Some(deposited) => deposited.amount += coin.amount,
+            None => lock.yield_principal.push(coin.clone()),
+        }
+        update_flow(storage, &coin.denom, |flow| flow.deposited += coin.amount)?;
+        messages.push(
+            WasmMsg::Execute {
+                contract_addr: adapter.to_string(),
+                msg: to_binary(&YieldAdapterExecuteMsg::Deposit {})?,
+                send: vec![coin.clone()],
+            }
+            .into(),
+        );
+    }
+
+    Ok(messages)
+}
+
+/// Withdraws the released coins the lock deposited into adapters back to the contract,
+/// adding the lock share of the yield to funds when configured.
+/// Yield is shared pro-rata on the principal at withdraw time
+pub fn withdraw_msgs(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    contract: &Addr,
+    lock: &mut Lock,
+    funds: &mut GenericBalance,
+) -> StdResult<Vec<CosmosMsg>> {
+    let config = YIELD_CONFIG.may_load(storage)?.unwrap_or_default();
+
+    let mut messages = vec![];
+    for coin in funds.native.iter_mut() {
+        let adapter = match YIELD_ADAPTERS.may_load(storage, &coin.denom)? {
+            Some(adapter) => adapter,
+            None => continue,
+        };
+        let deposited = match lock
+            .yield_principal
+            .iter_mut()
+            .find(|deposited| deposited.denom == coin.denom)
+        {
+            Some(deposited) => {
+                let amount = deposited.amount.min(coin.amount);
+                deposited.amount = deposited.amount.checked_sub(amount)?;
+                amount
+            }
+            None => continue,
+        };
+        lock.yield_principal
+            .retain(|deposited| !deposited.amount.is_zero());
+        if deposited.is_zero() {
+            continue;
+        }
+        let principal = YIELD_PRINCIPAL
+            .may_load(storage, &coin.denom)?
+            .unwrap_or_default();
+        YIELD_PRINCIPAL.save(storage, &coin.denom, &principal.checked_sub(deposited)?)?;
+
+        let mut amount = deposited;
+        if config.to_lock {
+            let balance = available_balance(storage, querier, &adapter, contract, &coin.denom)?;
+            amount = lock_share(deposited, principal, balance);
+            coin.amount = coin.amount.checked_sub(deposited)? + amount;
+        }
+        record_withdraw(storage, &coin.denom, amount)?;
+        messages.push(withdraw_msg(&adapter, amount)?);
+    }
+
+    Ok(messages)
+}
+
+/// Removes the yield flows of the execution, called once execute returns
+pub fn clear_yield_flows(storage: &mut dyn Storage) -> StdResult<()> {
+    let denoms = YIELD_FLOWS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, _)| denom))
+        .collect::<StdResult<Vec<_>>>()?;
+    for denom in denoms {
+        YIELD_FLOWS.remove(storage, &String::from_utf8_lossy(&denom));
+    }
+    Ok(())
+}
+
+fn lock_share(deposited: Uint128, principal: Uint128, balance: Uint128) -> Uint128 {
+    if principal.is_zero() || balance <= principal {
+        return deposited;
+    }
+    deposited.multiply_ratio(balance, principal)
+}
+
+/// Adapter balance once the msgs already returned by this execution run
+fn available_balance(
+    storage: &dyn Storage,
+    querier: &QuerierWrapper,
+    adapter: &Addr,
+    contract: &Addr,
+    denom: &str,
+) -> StdResult<Uint128> {
+    let balance = adapter_balance(querier, adapter, contract)?;
+    let flow = YIELD_FLOWS.may_load(storage, denom)?.unwrap_or_default();
+    Ok((balance + flow.deposited).saturating_sub(flow.withdrawn))
+}
+
+fn record_withdraw(storage: &mut dyn Storage, denom: &str, amount: Uint128) -> StdResult<()> {
+    update_flow(storage, denom, |flow| flow.withdrawn += amount)
+}
+
+fn update_flow(
+    storage: &mut dyn Storage,
+    denom: &str,
+    action: impl FnOnce(&mut YieldFlow),
+) -> StdResult<()> {
+    YIELD_FLOWS.update(storage, denom, |flow| -> StdResult<_> {
+        let mut flow = flow.unwrap_or_default();
+        action(&mut flow);
+        Ok(flow)
+    })?;
+    Ok(())
+}
+
+fn withdraw_msg(adapter: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: adapter.to_string(),
+        msg: to_binary(&YieldAdapterExecuteMsg::Withdraw { amount })?,
+        send: vec![],
+    }
+    .into())
+}
+
+fn adapter_balance(
+    querier: &QuerierWrapper,
+    adapter: &Addr,
+    contract: &Addr,
+) -> StdResult<Uint128> {
+    let res: YieldBalanceResponse = querier.query_wasm_smart(
+        adapter,
+        &YieldAdapterQueryMsg::Balance {
+            address: contract.to_string(),
+        },
+    )?;
+    Ok(res.amount)
+}
+
+pub fn query_yield_adapter(deps: Deps, denom: String) -> StdResult<YieldAdapterResponse> {
+    let adapter = YIELD_ADAPTERS.may_load(deps.storage, &denom)?;
+    let principal = YIELD_PRINCIPAL.may_load(deps.storage, &denom)?;
+
+    Ok(YieldAdapterResponse {
+        adapter: adapter.map(String::from),
+        principal: principal.unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::mock_querier::mock_dependencies_with_wasm;
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{ContractResult, Timestamp};
+
+    #[test]
+    fn lock_into_adapter() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        deps.querier.update_wasm(|_, _| {
+            ContractResult::Ok(
+                to_binary(&YieldBalanceResponse {
+                    amount: Uint128::new(120),
+                })
+                .unwrap(),
+            )
+        });
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg = ExecuteMsg::RegisterYieldAdapter {
+            denom: "token".into(),
+            adapter: Some("market".into()),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return Unauthorized error"),
+        }
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateYieldConfig {
+            to_lock: true,
+            collector: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // locked coins are deposited into the adapter
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "1".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(100, "token"));
+        let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "market".into(),
+                msg: to_binary(&YieldAdapterExecuteMsg::Deposit {}).unwrap(),
+                send: coins(100, "token"),
+            })]
+        );
+
+        // principal plus yield is withdrawn before paying out
+        env.block.time = Timestamp::from_seconds(401);
+        let msg = ExecuteMsg::Unlock { id: "1".into() };
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                withdraw_msg(&Addr::unchecked("market"), Uint128::new(120)).unwrap(),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "anyone".into(),
+                    amount: coins(120, "token"),
+                })
+            ]
+        );
+        assert_eq!(
+            Uint128::zero(),
+            YIELD_PRINCIPAL.load(&deps.storage, "token").unwrap()
+        );
+    }
+
+    #[test]
+    fn unlock_many_from_adapter() {
+        let mut deps = mock_dependencies_with_wasm(&[]);
+        deps.querier.update_wasm(|_, _| {
+            ContractResult::Ok(
+                to_binary(&YieldBalanceResponse {
+                    amount: Uint128::new(240),
+                })
+                .unwrap(),
+            )
+        });
+
+        let msg = InstantiateMsg {
+            max_lock_time: 3600,
+            ..InstantiateMsg::default()
+        };
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        let msg = ExecuteMsg::Lock {
+            id: "earth".into(),
+            expire: Some(Timestamp::from_seconds(400)),
+            referrer: None,
+            amount: None,
+        };
+        let info = mock_info("anyone", &coins(100, "earth"));
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // live locks of earth were never deposited
+        let msg = ExecuteMsg::RegisterYieldAdapter {
+            denom: "earth".into(),
+            adapter: Some("market".into()),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg);
+        match res {
+            Err(ContractError::DenomLocked {}) => {}
+            _ => panic!("Must return DenomLocked error"),
+        }
+
+        let msg = ExecuteMsg::RegisterYieldAdapter {
+            denom: "token".into(),
+            adapter: Some("market".into()),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::UpdateYieldConfig {
+            to_lock: true,
+            collector: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        for id in ["1", "2"].iter() {
+            let msg = ExecuteMsg::Lock {
+                id: id.to_string(),
+                expire: Some(Timestamp::from_seconds(400)),
+                referrer: None,
+                amount: None,
+            };
+            let info = mock_info("anyone", &coins(100, "token"));
+            let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+        let owner = Addr::unchecked("anyone");
+        let lock = locks().load(&deps.storage, (&owner, "1".into())).unwrap();
+        assert_eq!(coins(100, "token"), lock.yield_principal);
+
+        // the second share sees the balance left by the first withdraw
+        env.block.time = Timestamp::from_seconds(401);
+        let msg = ExecuteMsg::UnlockMany {
+            ids: vec!["1".into(), "2".into()],
+        };
+        let res = execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap();
+        let adapter = Addr::unchecked("market");
+        assert_eq!(
+            res.messages,
+            vec![
+                withdraw_msg(&adapter, Uint128::new(120)).unwrap(),
+                withdraw_msg(&adapter, Uint128::new(120)).unwrap(),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "anyone".into(),
+                    amount: coins(240, "token"),
+                })
+            ]
+        );
+        assert_eq!(None, YIELD_FLOWS.may_load(&deps.storage, "token").unwrap());
+    }
+}
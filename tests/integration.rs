@@ -0,0 +1,183 @@
+use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
+use cosmwasm_std::{coins, to_binary, Addr, Coin, Empty, Timestamp, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, SimpleBank};
+
+use cw_lockbox::msg::{ExecuteMsg, InstantiateMsg, LockInfo, QueryMsg, ReceiveMsg};
+
+const OWNER: &str = "owner";
+const ALICE: &str = "alice";
+
+fn mock_app() -> App {
+    let env = mock_env();
+    let api = Box::new(MockApi::default());
+    let bank = SimpleBank {};
+
+    App::new(api, env.block, bank, || Box::new(MockStorage::new()))
+}
+
+fn contract_lockbox() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw_lockbox::contract::execute,
+        cw_lockbox::contract::instantiate,
+        cw_lockbox::contract::query,
+    );
+    Box::new(contract)
+}
+
+fn contract_cw20() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    );
+    Box::new(contract)
+}
+
+fn instantiate_lockbox(router: &mut App) -> Addr {
+    let code_id = router.store_code(contract_lockbox());
+    let msg = InstantiateMsg {
+        max_lock_time: 3600,
+        ..InstantiateMsg::default()
+    };
+    router
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &msg, &[], "lockbox")
+        .unwrap()
+}
+
+fn instantiate_cw20(router: &mut App, balance: u128) -> Addr {
+    let code_id = router.store_code(contract_cw20());
+    let msg = cw20_base::msg::InstantiateMsg {
+        name: "Savings".into(),
+        symbol: "SAVE".into(),
+        decimals: 6,
+        initial_balances: vec![Cw20Coin {
+            address: ALICE.into(),
+            amount: Uint128::new(balance),
+        }],
+        mint: None,
+    };
+    router
+        .instantiate_contract(code_id, Addr::unchecked(OWNER), &msg, &[], "savings")
+        .unwrap()
+}
+
+fn cw20_balance(router: &App, token: &Addr, address: &Addr) -> Uint128 {
+    let res: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            token,
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap();
+    res.balance
+}
+
+fn expire_in(router: &App, seconds: u64) -> Timestamp {
+    router.block_info().time.plus_seconds(seconds)
+}
+
+fn advance_time(router: &mut App, seconds: u64) {
+    router.update_block(|block| {
+        block.time = block.time.plus_seconds(seconds);
+        block.height += 1;
+    });
+}
+
+#[test]
+fn cw20_lock_round_trip() {
+    let mut router = mock_app();
+    let lockbox = instantiate_lockbox(&mut router);
+    let token = instantiate_cw20(&mut router, 1000);
+    let alice = Addr::unchecked(ALICE);
+
+    // lock through cw20 Send
+    let msg = Cw20ExecuteMsg::Send {
+        contract: lockbox.to_string(),
+        amount: Uint128::new(400),
+        msg: Some(
+            to_binary(&ReceiveMsg::Lock {
+                id: "1".into(),
+                expire: Some(expire_in(&router, 100)),
+                referrer: None,
+            })
+            .unwrap(),
+        ),
+    };
+    router
+        .execute_contract(alice.clone(), token.clone(), &msg, &[])
+        .unwrap();
+    assert_eq!(Uint128::new(600), cw20_balance(&router, &token, &alice));
+    assert_eq!(Uint128::new(400), cw20_balance(&router, &token, &lockbox));
+
+    // increase through cw20 Send
+    let msg = Cw20ExecuteMsg::Send {
+        contract: lockbox.to_string(),
+        amount: Uint128::new(100),
+        msg: Some(
+            to_binary(&ReceiveMsg::IncreaseLock {
+                id: "1".into(),
+                min_remaining: None,
+            })
+            .unwrap(),
+        ),
+    };
+    router
+        .execute_contract(alice.clone(), token.clone(), &msg, &[])
+        .unwrap();
+
+    let query = QueryMsg::Lock {
+        address: ALICE.into(),
+        id: "1".into(),
+        viewing_key: None,
+    };
+    let info: LockInfo = router.wrap().query_wasm_smart(&lockbox, &query).unwrap();
+    assert_eq!(Uint128::new(500), info.cw20_balance[0].amount);
+
+    // unlock fails before expire and moves no funds
+    let unlock = ExecuteMsg::Unlock { id: "1".into() };
+    router
+        .execute_contract(alice.clone(), lockbox.clone(), &unlock, &[])
+        .unwrap_err();
+    assert_eq!(Uint128::new(500), cw20_balance(&router, &token, &lockbox));
+
+    // unlock transfers the tokens back
+    advance_time(&mut router, 101);
+    router
+        .execute_contract(alice.clone(), lockbox.clone(), &unlock, &[])
+        .unwrap();
+    assert_eq!(Uint128::new(1000), cw20_balance(&router, &token, &alice));
+    assert_eq!(Uint128::zero(), cw20_balance(&router, &token, &lockbox));
+}
+
+#[test]
+fn native_lock_round_trip() {
+    let mut router = mock_app();
+    let lockbox = instantiate_lockbox(&mut router);
+    let alice = Addr::unchecked(ALICE);
+    router
+        .set_bank_balance(&alice, coins(1000, "ujuno"))
+        .unwrap();
+
+    let msg = ExecuteMsg::Lock {
+        id: "1".into(),
+        expire: Some(expire_in(&router, 100)),
+        referrer: None,
+        amount: None,
+    };
+    router
+        .execute_contract(alice.clone(), lockbox.clone(), &msg, &coins(300, "ujuno"))
+        .unwrap();
+    let balance: Coin = router.wrap().query_balance(&lockbox, "ujuno").unwrap();
+    assert_eq!(Uint128::new(300), balance.amount);
+
+    advance_time(&mut router, 101);
+    let unlock = ExecuteMsg::Unlock { id: "1".into() };
+    router
+        .execute_contract(alice.clone(), lockbox.clone(), &unlock, &[])
+        .unwrap();
+    let balance: Coin = router.wrap().query_balance(&alice, "ujuno").unwrap();
+    assert_eq!(Uint128::new(1000), balance.amount);
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+use cosmwasm_std::{
+    coins, from_binary, BankMsg, CosmosMsg, Env, MemoryStorage, Order, OwnedDeps, Response,
+    Storage, Timestamp,
+};
+use proptest::prelude::*;
+
+use cw_lockbox::contract::{execute, instantiate, query};
+use cw_lockbox::error::ContractError;
+use cw_lockbox::msg::{ExecuteMsg, InstantiateMsg, LockInfo, QueryMsg};
+
+const MAX_LOCK_TIME: u64 = 1000;
+const DENOM: &str = "token";
+const OWNERS: [&str; 2] = ["alice", "bob"];
+const IDS: [&str; 3] = ["1", "2", "3"];
+
+type Deps = OwnedDeps<MemoryStorage, MockApi, MockQuerier>;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Lock {
+        owner: usize,
+        id: usize,
+        amount: u128,
+        duration: u64,
+    },
+    Increase {
+        owner: usize,
+        id: usize,
+        amount: u128,
+    },
+    Unlock {
+        owner: usize,
+        id: usize,
+    },
+    Advance {
+        seconds: u64,
+    },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        (0..2usize, 0..3usize, 0..50u128, 0..1200u64).prop_map(|(owner, id, amount, duration)| {
+            Op::Lock {
+                owner,
+                id,
+                amount,
+                duration,
+            }
+        }),
+        (0..2usize, 0..3usize, 0..50u128).prop_map(|(owner, id, amount)| Op::Increase {
+            owner,
+            id,
+            amount
+        }),
+        (0..2usize, 0..3usize).prop_map(|(owner, id)| Op::Unlock { owner, id }),
+        (0..600u64).prop_map(|seconds| Op::Advance { seconds }),
+    ]
+}
+
+/// Executes msg reverting storage on error, as the chain would
+fn execute_atomic(
+    deps: &mut Deps,
+    env: &Env,
+    sender: &str,
+    amount: u128,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let snapshot: Vec<_> = deps.storage.range(None, None, Order::Ascending).collect();
+    let funds = if amount > 0 {
+        coins(amount, DENOM)
+    } else {
+        vec![]
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(sender, &funds), msg);
+    if res.is_err() {
+        let keys: Vec<_> = deps
+            .storage
+            .range(None, None, Order::Ascending)
+            .map(|(key, _)| key)
+            .collect();
+        for key in keys {
+            deps.storage.remove(&key);
+        }
+        for (key, value) in snapshot {
+            deps.storage.set(&key, &value);
+        }
+    }
+    res
+}
+
+fn released(res: &Response) -> u128 {
+    res.messages
+        .iter()
+        .map(|msg| match msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. }) => {
+                amount.iter().map(|coin| coin.amount.u128()).sum()
+            }
+            _ => 0,
+        })
+        .sum()
+}
+
+proptest! {
+    #[test]
+    fn lock_model(ops in prop::collection::vec(op(), 1..40)) {
+        let mut deps = mock_dependencies(&[]);
+        let msg = InstantiateMsg {
+            max_lock_time: MAX_LOCK_TIME,
+            ..InstantiateMsg::default()
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(0);
+        // (owner, id) => (amount, expire)
+        let mut model: HashMap<(usize, usize), (u128, u64)> = HashMap::new();
+        let mut deposited = 0u128;
+        let mut withdrawn = 0u128;
+
+        for op in ops {
+            let now = env.block.time.seconds();
+            match op {
+                Op::Lock { owner, id, amount, duration } => {
+                    let msg = ExecuteMsg::Lock {
+                        id: IDS[id].into(),
+                        expire: Some(Timestamp::from_seconds(now + duration)),
+                        referrer: None,
+                        amount: None,
+                    };
+                    let res = execute_atomic(&mut deps, &env, OWNERS[owner], amount, msg);
+                    let valid = amount > 0
+                        && duration > 0
+                        && duration < MAX_LOCK_TIME
+                        && !model.contains_key(&(owner, id));
+                    prop_assert_eq!(valid, res.is_ok());
+                    if valid {
+                        model.insert((owner, id), (amount, now + duration));
+                        deposited += amount;
+                    }
+                }
+                Op::Increase { owner, id, amount } => {
+                    let msg = ExecuteMsg::IncreaseLock {
+                        id: IDS[id].into(),
+                        min_remaining: None,
+                    };
+                    let res = execute_atomic(&mut deps, &env, OWNERS[owner], amount, msg);
+                    let valid = amount > 0
+                        && matches!(model.get(&(owner, id)), Some((_, expire)) if now <= *expire);
+                    prop_assert_eq!(valid, res.is_ok());
+                    if valid {
+                        model.get_mut(&(owner, id)).unwrap().0 += amount;
+                        deposited += amount;
+                    }
+                }
+                Op::Unlock { owner, id } => {
+                    let msg = ExecuteMsg::Unlock { id: IDS[id].into() };
+                    let res = execute_atomic(&mut deps, &env, OWNERS[owner], 0, msg);
+                    // only expired locks are released, and only once
+                    match model.get(&(owner, id)) {
+                        Some((amount, expire)) if now > *expire => {
+                            prop_assert_eq!(*amount, released(&res.unwrap()));
+                            withdrawn += amount;
+                            model.remove(&(owner, id));
+                        }
+                        _ => prop_assert!(res.is_err()),
+                    }
+                }
+                Op::Advance { seconds } => {
+                    env.block.time = env.block.time.plus_seconds(seconds);
+                    env.block.height += 1;
+                }
+            }
+
+            // balances are conserved
+            let locked: u128 = model.values().map(|(amount, _)| amount).sum();
+            prop_assert_eq!(deposited, withdrawn + locked);
+        }
+
+        for ((owner, id), (amount, expire)) in model {
+            let msg = QueryMsg::Lock {
+                address: OWNERS[owner].into(),
+                id: IDS[id].into(),
+                viewing_key: None,
+            };
+            let info: LockInfo = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+            prop_assert_eq!(coins(amount, DENOM), info.native_balance);
+            prop_assert_eq!(Timestamp::from_seconds(expire), info.expire);
+        }
+    }
+}